@@ -0,0 +1,48 @@
+//! Benchmarks indexing a large repository, sequentially and (with the `parallel` feature) in
+//! parallel. Run with `cargo bench --features parallel`.
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use jobhunt::repository::{Builder, Job, SoftwareJobsBuilder};
+
+/// Creates jobs resembling a large aggregate of scraped sites.
+fn jobs(n: usize) -> Vec<Job> {
+    let titles = [
+        "Senior Backend Engineer",
+        "Junior Frontend Developer",
+        "Lead DevOps Engineer",
+        "Staff Blockchain Engineer",
+        "Fullstack Engineering Manager",
+    ];
+    (0..n)
+        .map(|i| Job {
+            title: titles[i % titles.len()].into(),
+            company: format!("Company_{}", i % 500),
+            date_posted: format!("2022-07-{:02}", i % 28 + 1),
+            location: ["Remote", "London", "Remote (US only)"][i % 3].into(),
+            tags: vec![format!("tag{}", i % 50), "Rust".into(), "Solidity".into()],
+            apply: format!("https://site1.com/jobs/{i}"),
+            site: "https://site1.com",
+            ..Default::default()
+        })
+        .collect()
+}
+
+fn bench_index(c: &mut Criterion) {
+    let jobs = jobs(50_000);
+    let builder = || SoftwareJobsBuilder::new().import(vec![jobs.clone()]);
+
+    c.bench_function("index 50k jobs", |b| {
+        b.iter_batched(builder, |b| black_box(b.index()), BatchSize::LargeInput)
+    });
+    #[cfg(feature = "parallel")]
+    c.bench_function("index_parallel 50k jobs", |b| {
+        b.iter_batched(
+            builder,
+            |b| black_box(b.index_parallel()),
+            BatchSize::LargeInput,
+        )
+    });
+}
+
+criterion_group!(benches, bench_index);
+criterion_main!(benches);
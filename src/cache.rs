@@ -0,0 +1,108 @@
+//! The cache module keeps the jobs from the last scrape on disk between sessions, so that Job Hunt
+//! can start without scraping every site again while they are still fresh.
+
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use chrono::{DateTime, Duration, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::repository::Job;
+
+/// The default cache file name, created in the output directory.
+pub const CACHE_FILE: &str = "cache.json";
+
+/// Represents the contents of the cache file: the jobs and when they were scraped.
+#[derive(Serialize, Deserialize)]
+struct Cache<J> {
+    scraped_at: DateTime<Local>,
+    jobs: J,
+}
+
+/// Writes the jobs to the cache file, recording when they were scraped.
+pub fn save<P: AsRef<Path>>(path: P, jobs: &[Job], scraped_at: DateTime<Local>) -> io::Result<()> {
+    let cache = Cache { scraped_at, jobs };
+    fs::write(path, serde_json::to_string(&cache)?)
+}
+
+/// Reads the jobs from the cache file along with when they were scraped, if they were scraped less
+/// than `max_age` before `now`. A missing or stale cache is Ok(None).
+pub fn load<P: AsRef<Path>>(
+    path: P,
+    max_age: Duration,
+    now: DateTime<Local>,
+) -> io::Result<Option<(Vec<Job>, DateTime<Local>)>> {
+    Ok(read(path)?
+        .filter(|cache| now - cache.scraped_at < max_age)
+        .map(|cache| (cache.jobs, cache.scraped_at)))
+}
+
+/// Returns the URL of each site with jobs in the cache file, however old. A missing cache has
+/// none.
+pub fn sites_with_jobs<P: AsRef<Path>>(path: P) -> io::Result<HashSet<&'static str>> {
+    Ok(read(path)?
+        .map(|cache| cache.jobs.iter().map(|job| job.site).collect())
+        .unwrap_or_default())
+}
+
+/// Reads the cache file, or None if there is none.
+fn read<P: AsRef<Path>>(path: P) -> io::Result<Option<Cache<Vec<Job>>>> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => Err(err)?,
+    };
+    Ok(Some(serde_json::from_str(&contents)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Local};
+
+    use crate::repository::Job;
+
+    use crate::site::REMOTE_OK_URL;
+
+    use super::{load, save, sites_with_jobs};
+
+    #[test]
+    fn test_cache_freshness() {
+        let path = std::env::temp_dir().join(format!("jobhunt-{}-cache.json", std::process::id()));
+        std::fs::remove_file(&path).ok();
+        let now = Local::now();
+        assert!(load(&path, Duration::hours(1), now).unwrap().is_none());
+
+        let job = Job {
+            title: "Backend Engineer".into(),
+            company: "Company_1".into(),
+            ..Default::default()
+        };
+        save(&path, &[job], now - Duration::minutes(30)).unwrap();
+
+        let (jobs, scraped_at) = load(&path, Duration::hours(1), now).unwrap().unwrap();
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].title, "Backend Engineer");
+        assert_eq!(scraped_at, now - Duration::minutes(30));
+        assert!(load(&path, Duration::minutes(20), now).unwrap().is_none());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_sites_with_jobs() {
+        let path = std::env::temp_dir().join(format!("jobhunt-{}-sites.json", std::process::id()));
+        std::fs::remove_file(&path).ok();
+        assert!(sites_with_jobs(&path).unwrap().is_empty());
+
+        let job = Job {
+            site: REMOTE_OK_URL,
+            ..Default::default()
+        };
+        // stale jobs still show which sites had jobs
+        save(&path, &[job], Local::now() - Duration::days(7)).unwrap();
+        let sites = sites_with_jobs(&path).unwrap();
+        assert_eq!(sites.into_iter().collect::<Vec<_>>(), vec![REMOTE_OK_URL]);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
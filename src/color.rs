@@ -0,0 +1,55 @@
+//! The color module decides whether Job Hunt's output is colored. All coloring is done with the
+//! colored crate, so turning colors off here removes every ANSI escape, from job listings and
+//! `Job`'s Debug output to REPL messages.
+
+use std::env;
+use std::io::{self, IsTerminal};
+
+/// Returns whether output should be colored: only when it goes to a terminal, and not when
+/// `NO_COLOR` is set to a non-empty value, following the https://no-color.org convention.
+pub(crate) fn colors_enabled(no_color: Option<&str>, is_terminal: bool) -> bool {
+    is_terminal && no_color.is_none_or(str::is_empty)
+}
+
+/// Turns colors off for the rest of the process if `NO_COLOR` is set or stdout is not a terminal,
+/// e.g. when output is piped to a file or another program. Called once at startup.
+pub(crate) fn init_colors() {
+    let no_color = env::var("NO_COLOR").ok();
+    init_colors_with(no_color.as_deref(), io::stdout().is_terminal());
+}
+
+/// Turns colors off for the rest of the process unless they are enabled for the given `NO_COLOR`
+/// value and terminal.
+fn init_colors_with(no_color: Option<&str>, is_terminal: bool) {
+    if !colors_enabled(no_color, is_terminal) {
+        colored::control::set_override(false);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use colored::Colorize;
+
+    use crate::repository::Job;
+
+    use super::{colors_enabled, init_colors_with};
+
+    #[test]
+    fn test_no_color() {
+        assert!(colors_enabled(None, true));
+        assert!(colors_enabled(Some(""), true));
+        assert!(!colors_enabled(Some("1"), true));
+        assert!(!colors_enabled(None, false));
+
+        init_colors_with(Some("1"), true);
+        let job = Job {
+            title: "Backend Engineer".into(),
+            company: "Company_1".into(),
+            date_posted: "2022-07-27".into(),
+            ..Default::default()
+        };
+        assert!(!format!("{job:?}").contains('\x1b'));
+        assert!(!format!("{}", "Welcome".bold().green()).contains('\x1b'));
+        colored::control::unset_override();
+    }
+}
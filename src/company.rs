@@ -0,0 +1,234 @@
+//! The company module contains the optional company metadata lookup, which finds a company's logo
+//! and website from the domain of a job's apply link. Lookups make an extra request per company, so
+//! they are off by default, and results are cached on disk so that each domain is only requested
+//! once, across refreshes and sessions.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::repository::Job;
+use crate::scraper::{get_body, Error, ScrapeOptions};
+
+/// The default company metadata cache file name, created in the output directory.
+pub const COMPANY_META_FILE: &str = "company-meta.json";
+
+/// Applicant tracking systems that host the apply pages of many companies. An apply link on one of
+/// these, or a subdomain of one, e.g. `boards.greenhouse.io`, says nothing about the company.
+const ATS_HOSTS: [&str; 8] = [
+    "greenhouse.io",
+    "lever.co",
+    "ashbyhq.com",
+    "workable.com",
+    "smartrecruiters.com",
+    "recruitee.com",
+    "breezy.hr",
+    "bamboohr.com",
+];
+
+/// Represents the metadata found for a company.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CompanyMeta {
+    pub logo: Option<String>,
+    pub website: Option<String>,
+}
+
+/// Represents cached company metadata, keyed by domain. A domain whose lookup failed is cached as
+/// None so that it isn't requested again.
+#[derive(Debug, Default)]
+pub struct MetadataCache {
+    entries: HashMap<String, Option<CompanyMeta>>,
+}
+
+impl MetadataCache {
+    /// Reads the metadata cached in the file at `path`. A missing file is an empty cache.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => Err(err)?,
+        };
+        let entries = serde_json::from_str::<HashMap<String, CompanyMeta>>(&contents)?;
+        Ok(Self {
+            entries: entries
+                .into_iter()
+                .map(|(domain, meta)| (domain, Some(meta)))
+                .collect(),
+        })
+    }
+
+    /// Writes the metadata found to the file at `path`. Failed lookups are not written, so that
+    /// they are tried again next session.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let found = self
+            .entries
+            .iter()
+            .filter_map(|(domain, meta)| Some((domain, meta.as_ref()?)))
+            .collect::<HashMap<&String, &CompanyMeta>>();
+        fs::write(path, serde_json::to_string(&found)?)
+    }
+
+    /// Fills in the logo and website of each job from the metadata of its apply link's domain,
+    /// fetching the metadata of each domain not yet cached with `fetch`. Fields a job already has
+    /// are kept. Jobs that apply through the job site itself or an applicant tracking system, or
+    /// whose metadata can't be fetched, are left as they are.
+    pub fn enrich<'a, I, F>(&mut self, jobs: I, mut fetch: F)
+    where
+        I: IntoIterator<Item = &'a mut Job>,
+        F: FnMut(&str) -> Result<CompanyMeta, Error>,
+    {
+        for job in jobs {
+            let Some(domain) = company_domain(job) else {
+                continue;
+            };
+            let meta = self
+                .entries
+                .entry(domain.clone())
+                .or_insert_with(|| fetch(&domain).ok());
+            if let Some(meta) = meta {
+                job.company_logo = job.company_logo.take().or(meta.logo.clone());
+                job.company_website = job.company_website.take().or(meta.website.clone());
+            }
+        }
+    }
+}
+
+/// Returns the domain of a job's apply link, unless it is the domain of the site the job was
+/// scraped from or of an applicant tracking system (see [`ATS_HOSTS`]).
+fn company_domain(job: &Job) -> Option<String> {
+    let host = |url: &str| {
+        Some(
+            Url::parse(url)
+                .ok()?
+                .host_str()?
+                .trim_start_matches("www.")
+                .to_owned(),
+        )
+    };
+    let domain = host(job.apply.trim())?;
+    let is_ats = ATS_HOSTS
+        .iter()
+        .any(|ats| domain == *ats || domain.ends_with(&format!(".{ats}")));
+    (!is_ats && host(job.site) != Some(domain.clone())).then_some(domain)
+}
+
+/// Fetches a company's metadata from the home page of its domain: the website is the home page and
+/// the logo is its Open Graph image, or else its icon. The request is made with the given options.
+pub fn fetch_company_meta(domain: &str, options: ScrapeOptions) -> Result<CompanyMeta, Error> {
+    let website = format!("https://{domain}");
+    let document = Html::parse_document(&get_body(&website, options)?);
+    let selector = |s: &str| Selector::parse(s).map_err(|err| Error::Selector(err.to_string()));
+    let logo = [
+        (selector(r#"meta[property="og:image"]"#)?, "content"),
+        (selector(r#"link[rel~="icon"]"#)?, "href"),
+    ]
+    .iter()
+    .find_map(|(selector, attr)| document.select(selector).next()?.value().attr(attr))
+    .and_then(|logo| Url::parse(&website).ok()?.join(logo).ok())
+    .map(String::from);
+    Ok(CompanyMeta {
+        logo,
+        website: Some(website),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repository::Job;
+    use crate::scraper::Error;
+
+    use super::{CompanyMeta, MetadataCache};
+
+    #[test]
+    fn test_enrich_with_stubbed_fetcher() {
+        let job = |apply: &str| Job {
+            apply: apply.into(),
+            site: "https://site1.com",
+            ..Default::default()
+        };
+        let mut jobs = vec![
+            job("https://acme.com/careers/1"),
+            job("https://www.acme.com/careers/2"),
+            job("https://site1.com/apply/3"),
+            job("https://broken.com/jobs"),
+            job("https://boards.greenhouse.io/acme/jobs/5"),
+            job("https://jobs.lever.co/acme/6"),
+            Job {
+                company_logo: Some("https://cdn.com/logo.png".into()),
+                ..job("https://acme.com/careers/4")
+            },
+        ];
+        let mut requested = vec![];
+
+        MetadataCache::default().enrich(&mut jobs, |domain| {
+            requested.push(domain.to_string());
+            match domain {
+                "acme.com" => Ok(CompanyMeta {
+                    logo: Some("https://acme.com/logo.png".into()),
+                    website: Some("https://acme.com".into()),
+                }),
+                _ => Err(Error::Response(404)),
+            }
+        });
+
+        assert_eq!(requested, vec!["acme.com", "broken.com"]);
+        assert_eq!(
+            jobs[0].company_logo.as_deref(),
+            Some("https://acme.com/logo.png")
+        );
+        assert_eq!(jobs[1].company_website.as_deref(), Some("https://acme.com"));
+        assert_eq!(jobs[2].company_logo, None);
+        assert_eq!(jobs[3].company_logo, None);
+        assert_eq!(jobs[4].company_website, None);
+        assert_eq!(jobs[5].company_website, None);
+        assert_eq!(
+            jobs[6].company_logo.as_deref(),
+            Some("https://cdn.com/logo.png")
+        );
+        assert_eq!(jobs[6].company_website.as_deref(), Some("https://acme.com"));
+    }
+
+    #[test]
+    fn test_cache_kept_across_sessions() {
+        let path =
+            std::env::temp_dir().join(format!("jobhunt-{}-company-meta.json", std::process::id()));
+        std::fs::remove_file(&path).ok();
+        let job = |apply: &str| Job {
+            apply: apply.into(),
+            site: "https://site1.com",
+            ..Default::default()
+        };
+        let fetch = |domain: &str| match domain {
+            "acme.com" => Ok(CompanyMeta {
+                logo: None,
+                website: Some("https://acme.com".into()),
+            }),
+            _ => Err(Error::Response(500)),
+        };
+        let mut cache = MetadataCache::load(&path).unwrap();
+        cache.enrich(
+            &mut [job("https://acme.com/1"), job("https://broken.com/2")],
+            fetch,
+        );
+        cache.save(&path).unwrap();
+
+        let mut requested = vec![];
+        let mut jobs = [job("https://acme.com/3"), job("https://broken.com/4")];
+        MetadataCache::load(&path)
+            .unwrap()
+            .enrich(&mut jobs, |domain| {
+                requested.push(domain.to_string());
+                fetch(domain)
+            });
+
+        // found metadata is reused, and failed lookups are tried again
+        assert_eq!(requested, vec!["broken.com"]);
+        assert_eq!(jobs[0].company_website.as_deref(), Some("https://acme.com"));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
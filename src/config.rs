@@ -0,0 +1,718 @@
+//! The config module contains all user configurable settings for Job Hunt. Settings are read from
+//! `JOBHUNT_*` environment variables, falling back to sensible defaults when unset or invalid.
+
+use std::env;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+
+use log::{info, warn};
+use thiserror::Error;
+
+use crate::export::Format;
+use crate::repl::HISTORY_FILE;
+use crate::repository::{LevelPolicy, SiteFilter, Skill};
+use crate::scraper::{RetryPolicy, ScrapeOptions};
+use crate::site::{redact_url, site_urls};
+use crate::status::STATUS_FILE;
+
+/// Represents errors that can occur while reading command line arguments.
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Unknown argument: {0}")]
+    UnknownArgument(String),
+    #[error("Missing value for argument: {0}")]
+    MissingValue(String),
+    #[error("Invalid value for argument {0}: {1}")]
+    InvalidValue(String, String),
+    #[error("Argument {0} requires Job Hunt to be built with the \"{1}\" feature")]
+    Unsupported(String, &'static str),
+}
+
+/// Default number of days after which a job is flagged as likely expired.
+pub const DEFAULT_EXPIRED_AFTER_DAYS: i64 = 90;
+/// Default number of times a failed request is retried.
+pub const DEFAULT_RETRIES: u32 = 2;
+/// Default delay in milliseconds before a failed request is first retried.
+pub const DEFAULT_RETRY_DELAY_MS: u64 = 500;
+/// The longest delay in milliseconds before a failed request is retried, however many retries came
+/// before it.
+pub const MAX_RETRY_DELAY_MS: u64 = 30_000;
+/// Default age in minutes after which cached jobs are scraped again on start up.
+pub const DEFAULT_CACHE_MAX_AGE_MINS: i64 = 60;
+/// Default number of jobs shown per page by `fetch jobs page <n>`.
+pub const DEFAULT_PAGE_SIZE: usize = 20;
+/// Default most pages read from a site.
+pub const DEFAULT_MAX_PAGES: usize = 5;
+/// Default time in seconds a request may take before it fails.
+pub const DEFAULT_REQUEST_TIMEOUT_SECS: u64 = 15;
+/// Default largest response body in bytes read by a scraper: 10MB.
+pub const DEFAULT_MAX_BODY_BYTES: u64 = 10 * 1024 * 1024;
+/// Default keywords a job title must contain one of for the job to be kept: engineering terms.
+pub const DEFAULT_TITLE_KEYWORDS: [&str; 4] = ["developer", "engineer", "engineering", "technical"];
+/// The application's directory name within the user's data directory.
+const APP_DIR: &str = "jobhunt";
+/// Files earlier versions of Job Hunt wrote to the working directory, with the names they have in
+/// the output directory: the command history and the status file.
+const LEGACY_FILES: [(&str, &str); 2] = [
+    (".jobhunthistory", HISTORY_FILE),
+    (".jobhunt-status.json", STATUS_FILE),
+];
+/// Default REPL prompt.
+pub const DEFAULT_PROMPT: &str = ">> ";
+/// Default welcome banner, shown once the repository is ready.
+pub const DEFAULT_BANNER: &str = "Welcome, please begin your job hunt by entering a query:";
+
+/// Represents the settings in effect for a Job Hunt session.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    /// The directory generated files are written to. Relative artifact paths, e.g. the status file,
+    /// are resolved against it.
+    pub output_dir: PathBuf,
+    /// The age in days after which a job is flagged as likely expired.
+    pub expired_after_days: i64,
+    /// Whether likely expired jobs are omitted from query results.
+    pub exclude_expired: bool,
+    /// A file that favorites are automatically written to on exit.
+    pub favorites_file: Option<PathBuf>,
+    /// The format favorites are written in.
+    pub favorites_format: Format,
+    /// If set, Job Hunt scrapes, writes the jobs to this file and exits without starting the REPL.
+    pub scrape_only: Option<PathBuf>,
+    /// The format jobs are written in by non-interactive modes.
+    pub output_format: Format,
+    /// Whether the same job posted on more than one site is merged into a single job.
+    pub merge_duplicates: bool,
+    /// Whether to launch the terminal UI instead of the REPL (requires the `tui` feature).
+    pub tui: bool,
+    /// Keywords and their weights, used to rank matching jobs first in `fetch jobs`.
+    pub boosts: Vec<(String, i64)>,
+    /// The skills a job must require to be recommended by `recommend`.
+    pub required_skills: Vec<Skill>,
+    /// The lowest salary a recommended job may pay. Jobs with an unknown salary are still
+    /// recommended, but rank below those known to pay at least this.
+    pub min_salary: Option<u32>,
+    /// Whether scrape failures are summarized in one line rather than reported per site.
+    pub compact_errors: bool,
+    /// The file application statuses are persisted to. If None, statuses last for the session only.
+    pub status_file: Option<PathBuf>,
+    /// Whether to print the effective config and exit.
+    pub print_config: bool,
+    /// Whether `--scrape-only` writes each site's jobs as soon as that site is scraped, as NDJSON.
+    pub stream: bool,
+    /// If set, `--stream` appends to its file rather than overwriting it, and compacts the file once
+    /// it holds more than this many records. None overwrites the file on every run.
+    pub compact_log_over: Option<usize>,
+    /// The REPL prompt. Never empty.
+    pub prompt: String,
+    /// The welcome banner shown when the REPL starts, or None to show no banner.
+    pub banner: Option<String>,
+    /// How jobs whose title matches more than one level are indexed.
+    pub level_policy: LevelPolicy,
+    /// The keywords a job title must contain one of for the job to be kept, or empty to keep every
+    /// job. Always lowercase.
+    pub title_keywords: Vec<String>,
+    /// Title filters for specific sites, used in place of the global title keywords.
+    pub site_filters: Vec<SiteFilter>,
+    /// Whether company logos and websites are looked up, with an extra request per company.
+    pub fetch_company_meta: bool,
+    /// The fewest jobs expected from a scrape. Fewer fails `--scrape-only` and warns in the REPL.
+    pub min_jobs: Option<usize>,
+    /// Whether scrape warnings are treated as errors: a site with no jobs or a job missing a
+    /// required field fails the site, and too few jobs stops the REPL from starting.
+    pub strict: bool,
+    /// The number of times a request that failed with a transient error is retried.
+    pub retries: u32,
+    /// The delay in milliseconds before a failed request is first retried, doubled for each retry.
+    pub retry_delay_ms: u64,
+    /// Whether the REPL starts from the jobs cached by the last session, if fresh, rather than
+    /// scraping every site.
+    pub cache: bool,
+    /// The age in minutes after which cached jobs are no longer used.
+    pub cache_max_age_mins: i64,
+    /// The number of jobs shown per page by `fetch jobs page <n>`. Never zero.
+    pub page_size: usize,
+    /// The most sites scraped at once, or None to scrape every site at once. Never zero.
+    pub max_concurrent_scrapes: Option<usize>,
+    /// The most pages read from a site, which is read until a page has no new jobs. Never zero.
+    pub max_pages: usize,
+    /// The largest response body in bytes a scraper reads; a larger response fails its site.
+    pub max_body_bytes: u64,
+    /// The time in seconds a request may take before it fails. Never zero.
+    pub request_timeout_secs: u64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            output_dir: default_output_dir(),
+            expired_after_days: DEFAULT_EXPIRED_AFTER_DAYS,
+            exclude_expired: false,
+            favorites_file: None,
+            favorites_format: Format::Json,
+            scrape_only: None,
+            output_format: Format::Json,
+            merge_duplicates: false,
+            tui: false,
+            boosts: vec![],
+            required_skills: vec![],
+            min_salary: None,
+            compact_errors: false,
+            status_file: Some(PathBuf::from(STATUS_FILE)),
+            print_config: false,
+            stream: false,
+            compact_log_over: None,
+            prompt: DEFAULT_PROMPT.into(),
+            banner: Some(DEFAULT_BANNER.into()),
+            level_policy: LevelPolicy::default(),
+            title_keywords: DEFAULT_TITLE_KEYWORDS.map(String::from).to_vec(),
+            site_filters: vec![],
+            min_jobs: None,
+            fetch_company_meta: false,
+            strict: false,
+            retries: DEFAULT_RETRIES,
+            retry_delay_ms: DEFAULT_RETRY_DELAY_MS,
+            cache: true,
+            cache_max_age_mins: DEFAULT_CACHE_MAX_AGE_MINS,
+            page_size: DEFAULT_PAGE_SIZE,
+            max_concurrent_scrapes: None,
+            max_pages: DEFAULT_MAX_PAGES,
+            max_body_bytes: DEFAULT_MAX_BODY_BYTES,
+            request_timeout_secs: DEFAULT_REQUEST_TIMEOUT_SECS,
+        }
+    }
+}
+
+impl Config {
+    /// Creates a config from the environment. The variables read are:
+    /// - `JOBHUNT_OUTPUT_DIR` - the directory generated files are written to (default
+    ///   `$XDG_DATA_HOME/jobhunt`, or `~/.local/share/jobhunt`)
+    /// - `JOBHUNT_EXPIRED_AFTER_DAYS` - age in days after which a job is likely expired (default 90)
+    /// - `JOBHUNT_EXCLUDE_EXPIRED` - `true` to omit likely expired jobs from results (default false)
+    /// - `JOBHUNT_FAVORITES_FILE` - a file favorites are written to on exit (default none)
+    /// - `JOBHUNT_FAVORITES_FORMAT` - `json`, `jsonl`, `csv` or `markdown-table` (default json)
+    /// - `JOBHUNT_MERGE_DUPLICATES` - `true` to merge jobs posted on more than one site (default false)
+    /// - `JOBHUNT_BOOSTS` - comma separated `keyword:weight` pairs used to rank jobs, e.g.
+    ///   `rust:10,senior:5` (default none)
+    /// - `JOBHUNT_REQUIRED_SKILLS` - comma separated skills a recommended job must require, e.g.
+    ///   `backend,blockchain` (default none)
+    /// - `JOBHUNT_MIN_SALARY` - the lowest salary a recommended job may pay (default none)
+    /// - `JOBHUNT_COMPACT_ERRORS` - `true` to summarize scrape failures in one line (default false)
+    /// - `JOBHUNT_STATUS_FILE` - the file application statuses are kept in (default `status.json`)
+    /// - `JOBHUNT_PROMPT` - the REPL prompt, ignored if empty (default `>> `)
+    /// - `JOBHUNT_BANNER` - the welcome banner, or empty for none (default a welcome message)
+    /// - `JOBHUNT_LEVEL_POLICY` - `all` to index a job under every level its title matches, or
+    ///   `highest` for the most senior only (default highest)
+    /// - `JOBHUNT_FETCH_COMPANY_META` - `true` to look up company logos and websites (default false)
+    /// - `JOBHUNT_MIN_JOBS` - the fewest jobs expected from a scrape (default none)
+    /// - `JOBHUNT_COMPACT_LOG_OVER` - the records above which the `--stream` log is compacted
+    ///   (default none, the file is overwritten)
+    /// - `JOBHUNT_STRICT` - `true` to treat scrape warnings as errors (default false)
+    /// - `JOBHUNT_RETRIES` - the number of times a failed request is retried (default 2)
+    /// - `JOBHUNT_RETRY_DELAY_MS` - the delay before the first retry, doubled for each retry after
+    ///   it up to [`MAX_RETRY_DELAY_MS`] (default 500)
+    /// - `JOBHUNT_CACHE` - `false` to always scrape on start up rather than use cached jobs (default
+    ///   true)
+    /// - `JOBHUNT_CACHE_MAX_AGE_MINS` - the age in minutes after which cached jobs are scraped again
+    ///   (default 60)
+    /// - `JOBHUNT_PAGE_SIZE` - the number of jobs shown per page by `fetch jobs page <n>`, ignored
+    ///   if zero (default 20)
+    /// - `JOBHUNT_MAX_CONCURRENT_SCRAPES` - the most sites scraped at once, ignored if zero (default
+    ///   none, every site at once)
+    /// - `JOBHUNT_MAX_PAGES` - the most pages read from a site, ignored if zero (default 5)
+    /// - `JOBHUNT_MAX_BODY_BYTES` - the largest response body in bytes read (default 10MB)
+    /// - `JOBHUNT_REQUEST_TIMEOUT_SECS` - the time in seconds a request may take, ignored if zero
+    ///   (default 15)
+    /// - `JOBHUNT_TITLE_KEYWORDS` - comma separated keywords; a job is kept if its title contains any
+    ///   of them, or every job for `*` (default `developer,engineer,engineering,technical`)
+    /// - `JOBHUNT_SITE_FILTERS` - comma separated `site=keyword|keyword` filters; a site's jobs are
+    ///   kept if their title contains any of its keywords, or all of them for `site=*`, e.g.
+    ///   `board.com=engineer,web3.career=*` (default none, the title keywords apply everywhere)
+    pub fn from_env() -> Self {
+        let default = Self::default();
+        Self {
+            output_dir: env_path("JOBHUNT_OUTPUT_DIR").unwrap_or(default.output_dir),
+            expired_after_days: env_or("JOBHUNT_EXPIRED_AFTER_DAYS", default.expired_after_days),
+            exclude_expired: env_or("JOBHUNT_EXCLUDE_EXPIRED", default.exclude_expired),
+            favorites_file: env_path("JOBHUNT_FAVORITES_FILE").or(default.favorites_file),
+            favorites_format: env_or("JOBHUNT_FAVORITES_FORMAT", default.favorites_format),
+            merge_duplicates: env_or("JOBHUNT_MERGE_DUPLICATES", default.merge_duplicates),
+            boosts: env::var("JOBHUNT_BOOSTS")
+                .map(|v| parse_boosts(&v))
+                .unwrap_or(default.boosts),
+            required_skills: env::var("JOBHUNT_REQUIRED_SKILLS")
+                .map(|v| parse_skills(&v))
+                .unwrap_or(default.required_skills),
+            min_salary: env::var("JOBHUNT_MIN_SALARY")
+                .ok()
+                .and_then(|v| v.trim().parse().ok()),
+            compact_errors: env_or("JOBHUNT_COMPACT_ERRORS", default.compact_errors),
+            status_file: env_path("JOBHUNT_STATUS_FILE").or(default.status_file),
+            prompt: env::var("JOBHUNT_PROMPT")
+                .ok()
+                .filter(|v| !v.trim().is_empty())
+                .unwrap_or(default.prompt),
+            banner: match env::var("JOBHUNT_BANNER") {
+                Ok(v) if v.trim().is_empty() => None,
+                Ok(v) => Some(v),
+                Err(_) => default.banner,
+            },
+            level_policy: env_or("JOBHUNT_LEVEL_POLICY", default.level_policy),
+            fetch_company_meta: env_or("JOBHUNT_FETCH_COMPANY_META", default.fetch_company_meta),
+            strict: env_or("JOBHUNT_STRICT", default.strict),
+            retries: env_or("JOBHUNT_RETRIES", default.retries),
+            retry_delay_ms: env_or("JOBHUNT_RETRY_DELAY_MS", default.retry_delay_ms),
+            cache: env_or("JOBHUNT_CACHE", default.cache),
+            cache_max_age_mins: env_or("JOBHUNT_CACHE_MAX_AGE_MINS", default.cache_max_age_mins),
+            page_size: env::var("JOBHUNT_PAGE_SIZE")
+                .ok()
+                .and_then(|v| v.trim().parse().ok())
+                .filter(|n| *n > 0)
+                .unwrap_or(default.page_size),
+            max_concurrent_scrapes: env::var("JOBHUNT_MAX_CONCURRENT_SCRAPES")
+                .ok()
+                .and_then(|v| v.trim().parse().ok())
+                .filter(|n| *n > 0),
+            max_pages: env::var("JOBHUNT_MAX_PAGES")
+                .ok()
+                .and_then(|v| v.trim().parse().ok())
+                .filter(|n| *n > 0)
+                .unwrap_or(default.max_pages),
+            max_body_bytes: env_or("JOBHUNT_MAX_BODY_BYTES", default.max_body_bytes),
+            request_timeout_secs: env::var("JOBHUNT_REQUEST_TIMEOUT_SECS")
+                .ok()
+                .and_then(|v| v.trim().parse().ok())
+                .filter(|n| *n > 0)
+                .unwrap_or(default.request_timeout_secs),
+            min_jobs: env::var("JOBHUNT_MIN_JOBS")
+                .ok()
+                .and_then(|v| v.trim().parse().ok()),
+            compact_log_over: env::var("JOBHUNT_COMPACT_LOG_OVER")
+                .ok()
+                .and_then(|v| v.trim().parse().ok()),
+            title_keywords: env::var("JOBHUNT_TITLE_KEYWORDS")
+                .map(|v| parse_title_keywords(&v))
+                .unwrap_or(default.title_keywords),
+            site_filters: env::var("JOBHUNT_SITE_FILTERS")
+                .map(|v| parse_site_filters(&v))
+                .unwrap_or(default.site_filters),
+            ..default
+        }
+    }
+
+    /// Creates a config from the environment, then applies command line arguments over it. The
+    /// arguments accepted are:
+    /// - `--output-dir <dir>` - the directory generated files are written to
+    /// - `--scrape-only <path>` - scrape, write the jobs to the file and exit
+    /// - `--format <format>` - the format for `--scrape-only`, `json`, `jsonl`, `csv` or
+    ///   `markdown-table`
+    /// - `--tui` - launch the terminal UI instead of the REPL
+    /// - `--compact-errors` - summarize scrape failures in one line
+    /// - `--verbose` - report each scrape failure in full (the default)
+    /// - `--print-config` - print the effective config and exit
+    /// - `--prompt <prompt>` - the REPL prompt, which must not be empty
+    /// - `--no-banner` - don't show the welcome banner
+    /// - `--min-jobs <n>` - exit with an error if fewer than n jobs are found
+    /// - `--stream` - with `--scrape-only`, write each site's jobs as NDJSON as soon as they're scraped
+    /// - `--compact-log-over <n>` - with `--stream`, append to the file and compact it over n records
+    /// - `--strict` - treat scrape warnings as errors
+    /// - `--retries <n>` - the number of times a failed request is retried, 0 for none
+    /// - `--retry-delay <ms>` - the delay before the first retry, doubled for each retry after it
+    /// - `--request-timeout <secs>` - the time a request may take before it fails, which must not be 0
+    /// - `--no-cache` - scrape on start up even if the cached jobs are fresh
+    pub fn from_env_and_args<I>(args: I) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut config = Self::from_env();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or(Error::MissingValue(arg.clone()));
+            match arg.as_str() {
+                "--output-dir" => config.output_dir = PathBuf::from(value()?),
+                "--scrape-only" => config.scrape_only = Some(PathBuf::from(value()?)),
+                "--format" => {
+                    let v = value()?;
+                    config.output_format = v.parse().map_err(|_| Error::InvalidValue(arg, v))?;
+                }
+                "--compact-errors" => config.compact_errors = true,
+                "--verbose" => config.compact_errors = false,
+                "--print-config" => config.print_config = true,
+                "--stream" => config.stream = true,
+                "--strict" => config.strict = true,
+                "--no-cache" => config.cache = false,
+                "--prompt" => {
+                    let v = value()?;
+                    if v.trim().is_empty() {
+                        Err(Error::InvalidValue(arg, v))?;
+                    } else {
+                        config.prompt = v;
+                    }
+                }
+                "--no-banner" => config.banner = None,
+                "--retries" => {
+                    let v = value()?;
+                    config.retries = v.parse().map_err(|_| Error::InvalidValue(arg, v))?;
+                }
+                "--retry-delay" => {
+                    let v = value()?;
+                    config.retry_delay_ms = v.parse().map_err(|_| Error::InvalidValue(arg, v))?;
+                }
+                "--request-timeout" => {
+                    let v = value()?;
+                    match v.parse() {
+                        Ok(secs) if secs > 0 => config.request_timeout_secs = secs,
+                        _ => Err(Error::InvalidValue(arg, v))?,
+                    }
+                }
+                "--min-jobs" => {
+                    let v = value()?;
+                    config.min_jobs = Some(v.parse().map_err(|_| Error::InvalidValue(arg, v))?);
+                }
+                "--compact-log-over" => {
+                    let v = value()?;
+                    config.compact_log_over =
+                        Some(v.parse().map_err(|_| Error::InvalidValue(arg, v))?);
+                }
+                "--tui" if cfg!(feature = "tui") => config.tui = true,
+                "--tui" => Err(Error::Unsupported(arg, "tui"))?,
+                _ => Err(Error::UnknownArgument(arg))?,
+            }
+        }
+        Ok(config)
+    }
+
+    /// Resolves an artifact path against the output directory. Absolute paths are returned as is.
+    pub fn output_path<P: AsRef<Path>>(&self, path: P) -> PathBuf {
+        self.output_dir.join(path)
+    }
+
+    /// Creates the output directory, and any missing parents, if it doesn't exist.
+    pub fn create_output_dir(&self) -> io::Result<()> {
+        fs::create_dir_all(&self.output_dir)
+    }
+
+    /// Moves files earlier versions of Job Hunt wrote to the working directory into the output
+    /// directory, so that upgrading keeps the command history and application statuses. A file is
+    /// left where it is if the output directory already has one of its name. Failures are logged
+    /// rather than returned, as Job Hunt can run without the old files.
+    pub fn migrate_legacy_files(&self) {
+        self.migrate_legacy_files_from(Path::new("."));
+    }
+
+    /// Moves the legacy files in `dir` into the output directory, returning the paths moved to.
+    fn migrate_legacy_files_from(&self, dir: &Path) -> Vec<PathBuf> {
+        let mut moved = vec![];
+        for (legacy, name) in LEGACY_FILES {
+            let (from, to) = (dir.join(legacy), self.output_path(name));
+            if !from.is_file() || to.exists() {
+                continue;
+            }
+            // a rename fails if the output directory is on another file system
+            let result = fs::rename(&from, &to)
+                .or_else(|_| fs::copy(&from, &to).and_then(|_| fs::remove_file(&from)));
+            match result {
+                Ok(_) => {
+                    info!("Moved {} to {}", from.display(), to.display());
+                    moved.push(to);
+                }
+                Err(err) => warn!(
+                    "Could not move {} to {}: {err}",
+                    from.display(),
+                    to.display()
+                ),
+            }
+        }
+        moved
+    }
+
+    /// Returns the policy failed requests are retried with.
+    pub fn retry_policy(&self) -> RetryPolicy {
+        RetryPolicy {
+            retries: self.retries,
+            delay: Duration::from_millis(self.retry_delay_ms),
+        }
+    }
+
+    /// Returns the settings scrapers make their requests with.
+    pub fn scrape_options(&self) -> ScrapeOptions {
+        ScrapeOptions {
+            retry: self.retry_policy(),
+            max_pages: self.max_pages,
+            max_body_bytes: self.max_body_bytes,
+            timeout: Duration::from_secs(self.request_timeout_secs),
+        }
+    }
+
+    /// Returns the expired threshold to apply to query results, if likely expired jobs are to be
+    /// excluded.
+    pub fn exclude_expired_after(&self) -> Option<i64> {
+        self.exclude_expired.then_some(self.expired_after_days)
+    }
+}
+
+/// Prints the effective config, one `key = value` setting per line, followed by the sites scraped.
+/// Credentials in site URLs are redacted.
+impl Display for Config {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let path = |path: &Option<PathBuf>| {
+            path.as_deref().map_or("none".to_string(), |p: &Path| {
+                self.output_path(p).display().to_string()
+            })
+        };
+        let boosts = if self.boosts.is_empty() {
+            "none".to_string()
+        } else {
+            self.boosts
+                .iter()
+                .map(|(keyword, weight)| format!("{keyword}:{weight}"))
+                .collect::<Vec<String>>()
+                .join(",")
+        };
+        let site_filters = if self.site_filters.is_empty() {
+            "none".to_string()
+        } else {
+            self.site_filters
+                .iter()
+                .map(|(site, keywords)| {
+                    if keywords.is_empty() {
+                        format!("{site}=*")
+                    } else {
+                        format!("{site}={}", keywords.join("|"))
+                    }
+                })
+                .collect::<Vec<String>>()
+                .join(",")
+        };
+        writeln!(f, "output_dir = {}", self.output_dir.display())?;
+        writeln!(f, "expired_after_days = {}", self.expired_after_days)?;
+        writeln!(f, "exclude_expired = {}", self.exclude_expired)?;
+        writeln!(f, "favorites_file = {}", path(&self.favorites_file))?;
+        writeln!(f, "favorites_format = {}", self.favorites_format)?;
+        writeln!(f, "scrape_only = {}", path(&self.scrape_only))?;
+        writeln!(f, "output_format = {}", self.output_format)?;
+        writeln!(f, "stream = {}", self.stream)?;
+        writeln!(f, "merge_duplicates = {}", self.merge_duplicates)?;
+        writeln!(f, "tui = {}", self.tui)?;
+        writeln!(f, "boosts = {}", boosts)?;
+        let required_skills = if self.required_skills.is_empty() {
+            "none".to_string()
+        } else {
+            self.required_skills
+                .iter()
+                .map(|skill| format!("{skill:?}").to_lowercase())
+                .collect::<Vec<String>>()
+                .join(",")
+        };
+        writeln!(f, "required_skills = {}", required_skills)?;
+        let min_salary = self
+            .min_salary
+            .map_or("none".to_string(), |n| n.to_string());
+        writeln!(f, "min_salary = {}", min_salary)?;
+        writeln!(f, "compact_errors = {}", self.compact_errors)?;
+        writeln!(f, "status_file = {}", path(&self.status_file))?;
+        writeln!(f, "prompt = {:?}", self.prompt)?;
+        writeln!(f, "banner = {}", self.banner.as_deref().unwrap_or("none"))?;
+        writeln!(f, "level_policy = {}", self.level_policy)?;
+        let title_keywords = if self.title_keywords.is_empty() {
+            "*".to_string()
+        } else {
+            self.title_keywords.join(",")
+        };
+        writeln!(f, "title_keywords = {}", title_keywords)?;
+        writeln!(f, "site_filters = {}", site_filters)?;
+        let min_jobs = self.min_jobs.map_or("none".to_string(), |n| n.to_string());
+        writeln!(f, "min_jobs = {}", min_jobs)?;
+        let compact_log_over = self
+            .compact_log_over
+            .map_or("none".to_string(), |n| n.to_string());
+        writeln!(f, "compact_log_over = {}", compact_log_over)?;
+        writeln!(f, "fetch_company_meta = {}", self.fetch_company_meta)?;
+        writeln!(f, "strict = {}", self.strict)?;
+        writeln!(f, "retries = {}", self.retries)?;
+        writeln!(f, "retry_delay_ms = {}", self.retry_delay_ms)?;
+        writeln!(f, "cache = {}", self.cache)?;
+        writeln!(f, "cache_max_age_mins = {}", self.cache_max_age_mins)?;
+        writeln!(f, "page_size = {}", self.page_size)?;
+        let max_concurrent_scrapes = self
+            .max_concurrent_scrapes
+            .map_or("none".to_string(), |n| n.to_string());
+        writeln!(f, "max_concurrent_scrapes = {}", max_concurrent_scrapes)?;
+        writeln!(f, "max_body_bytes = {}", self.max_body_bytes)?;
+        writeln!(f, "max_pages = {}", self.max_pages)?;
+        writeln!(f, "request_timeout_secs = {}", self.request_timeout_secs)?;
+        writeln!(f, "sites =")?;
+        for url in site_urls() {
+            writeln!(f, "  {}", redact_url(url))?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the default output directory, `jobhunt` within the XDG data directory. Falls back to the
+/// working directory if no data directory can be found.
+fn default_output_dir() -> PathBuf {
+    env_path("XDG_DATA_HOME")
+        .or_else(|| env_path("HOME").map(|home| home.join(".local").join("share")))
+        .map_or(PathBuf::from("."), |dir| dir.join(APP_DIR))
+}
+
+/// Reads and parses an environment variable, returning the default if it is unset or invalid.
+fn env_or<T: FromStr>(key: &str, default: T) -> T {
+    env::var(key)
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(default)
+}
+
+/// Reads an environment variable as a path, returning None if it is unset or empty.
+fn env_path(key: &str) -> Option<PathBuf> {
+    env::var(key)
+        .ok()
+        .filter(|v| !v.trim().is_empty())
+        .map(PathBuf::from)
+}
+
+/// Parses comma separated `keyword:weight` pairs, skipping any that are malformed.
+fn parse_boosts(s: &str) -> Vec<(String, i64)> {
+    s.split(',')
+        .filter_map(|pair| {
+            let (keyword, weight) = pair.rsplit_once(':')?;
+            let keyword = keyword.trim();
+            (!keyword.is_empty()).then_some(())?;
+            Some((keyword.to_lowercase(), weight.trim().parse().ok()?))
+        })
+        .collect()
+}
+
+/// Parses comma separated skill names, skipping any that are unknown.
+fn parse_skills(s: &str) -> Vec<Skill> {
+    s.split(',')
+        .filter_map(|skill| skill.parse().ok())
+        .collect()
+}
+
+/// Parses comma separated title keywords, lowercased. A `*` keyword accepts every job, returning no
+/// keywords.
+pub(crate) fn parse_title_keywords(s: &str) -> Vec<String> {
+    let keywords = s
+        .split(',')
+        .map(|keyword| keyword.trim().to_lowercase())
+        .filter(|keyword| !keyword.is_empty())
+        .collect::<Vec<_>>();
+    if keywords.iter().any(|keyword| keyword == "*") {
+        vec![]
+    } else {
+        keywords
+    }
+}
+
+/// Parses comma separated `site=keyword|keyword` filters, skipping any that are malformed. A `*`
+/// keyword accepts every job from the site.
+fn parse_site_filters(s: &str) -> Vec<SiteFilter> {
+    s.split(',')
+        .filter_map(|filter| {
+            let (site, keywords) = filter.split_once('=')?;
+            let site = site.trim();
+            (!site.is_empty()).then_some(())?;
+            let keywords = keywords
+                .split('|')
+                .map(|keyword| keyword.trim().to_lowercase())
+                .filter(|keyword| !keyword.is_empty() && keyword != "*")
+                .collect();
+            Some((site.to_lowercase(), keywords))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::Config;
+
+    #[test]
+    fn test_migrate_legacy_files() {
+        let dir = std::env::temp_dir().join(format!("jobhunt-{}-legacy", std::process::id()));
+        fs::remove_dir_all(&dir).ok();
+        let config = Config {
+            output_dir: dir.join("output"),
+            ..Default::default()
+        };
+        config.create_output_dir().unwrap();
+        fs::write(dir.join(".jobhunthistory"), "fetch jobs\n").unwrap();
+        fs::write(dir.join(".jobhunt-status.json"), "{}").unwrap();
+        fs::write(config.output_path("status.json"), "{\"kept\": 1}").unwrap();
+
+        let moved = config.migrate_legacy_files_from(&dir);
+        assert_eq!(moved, vec![config.output_path("history")]);
+        assert_eq!(
+            fs::read_to_string(config.output_path("history")).unwrap(),
+            "fetch jobs\n"
+        );
+        assert!(!dir.join(".jobhunthistory").exists());
+        // the status file already in the output directory isn't overwritten
+        assert!(dir.join(".jobhunt-status.json").exists());
+        assert!(config.migrate_legacy_files_from(&dir).is_empty());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_print_config_with_overrides() {
+        let config =
+            Config::from_env_and_args(["--compact-errors".to_string(), "--print-config".into()])
+                .unwrap();
+        let config = Config {
+            expired_after_days: 30,
+            boosts: vec![("rust".into(), 10), ("senior".into(), 5)],
+            max_pages: 3,
+            max_body_bytes: 1024,
+            ..config
+        };
+        assert!(config.print_config);
+        assert_eq!(config.scrape_options().max_pages, 3);
+        assert_eq!(config.scrape_options().max_body_bytes, 1024);
+
+        let printed = config.to_string();
+        assert!(printed.contains("expired_after_days = 30\n"));
+        assert!(printed.contains("compact_errors = true\n"));
+        assert!(printed.contains("boosts = rust:10,senior:5\n"));
+        assert!(printed.contains("favorites_format = json\n"));
+        assert!(printed.contains("max_pages = 3\n"));
+        assert!(printed.contains("max_body_bytes = 1024\n"));
+        assert!(printed.contains("  https://web3.career\n"));
+    }
+
+    #[test]
+    fn test_retry_args() {
+        let args = |args: &[&str]| Config::from_env_and_args(args.iter().map(|a| a.to_string()));
+
+        let config = args(&["--retries", "0", "--retry-delay", "250"]).unwrap();
+        assert_eq!((config.retries, config.retry_delay_ms), (0, 250));
+        assert_eq!(
+            config.retry_policy().delay,
+            std::time::Duration::from_millis(250)
+        );
+        assert_eq!(config.scrape_options().retry, config.retry_policy());
+        assert_ne!(config.scrape_options(), Config::default().scrape_options());
+        assert!(config.to_string().contains("retries = 0\n"));
+
+        let err = args(&["--retries", "-1"]).unwrap_err();
+        assert_eq!(err.to_string(), "Invalid value for argument --retries: -1");
+        assert!(args(&["--retry-delay", "soon"]).is_err());
+
+        let config = args(&["--request-timeout", "5"]).unwrap();
+        assert_eq!(
+            config.scrape_options().timeout,
+            std::time::Duration::from_secs(5)
+        );
+        assert!(config.to_string().contains(
+            "request_timeout_secs = 5
+"
+        ));
+        assert!(args(&["--request-timeout", "0"]).is_err());
+    }
+}
@@ -0,0 +1,567 @@
+//! The export module contains all code for rendering jobs into file formats that can be used outside
+//! of Job Hunt.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+use itertools::Itertools;
+use serde::ser::{Serialize, SerializeMap, Serializer};
+
+use crate::repository::{Job, JobRef};
+
+/// Escapes a field for use in a GitHub-flavored markdown table cell. Pipe characters would end the
+/// cell early and newlines would end the row, so both are neutralised.
+fn escape_markdown_cell(s: &str) -> String {
+    s.replace('|', "\\|")
+        .replace(['\r', '\n'], " ")
+        .trim()
+        .to_owned()
+}
+
+/// Percent-encodes the characters of a URL that would break a markdown link in a table cell: spaces
+/// and pipes, parentheses, which would end the link early, and angle brackets.
+fn escape_markdown_url(url: &str) -> String {
+    url.chars()
+        .map(|c| match c {
+            ' ' => "%20".to_string(),
+            '|' => "%7C".to_string(),
+            '(' => "%28".to_string(),
+            ')' => "%29".to_string(),
+            '<' => "%3C".to_string(),
+            '>' => "%3E".to_string(),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+/// Renders jobs as a GitHub-flavored markdown table with the columns Date, Company, Title, Location,
+/// Salary and Apply, where Apply is rendered as a markdown link.
+pub fn markdown_table(jobs: &[JobRef]) -> String {
+    let mut table = String::from(
+        "| Date | Company | Title | Location | Salary | Apply |\n\
+        | --- | --- | --- | --- | --- | --- |\n",
+    );
+    for job in jobs {
+        let apply = if job.apply.is_empty() {
+            "".into()
+        } else {
+            format!("[Apply]({})", escape_markdown_url(&job.apply))
+        };
+        table.push_str(&format!(
+            "| {} | {} | {} | {} | {} | {} |\n",
+            escape_markdown_cell(&job.date_posted),
+            escape_markdown_cell(&job.company),
+            escape_markdown_cell(&job.title),
+            escape_markdown_cell(&job.location),
+            escape_markdown_cell(&job.remuneration),
+            apply
+        ));
+    }
+    table
+}
+
+/// Renders jobs as a pretty printed JSON array.
+pub fn json(jobs: &[JobRef]) -> io::Result<String> {
+    let jobs = jobs.iter().map(|job| job.as_ref()).collect::<Vec<&Job>>();
+    Ok(serde_json::to_string_pretty(&jobs)?)
+}
+
+/// Represents a job field that can be exported, selected with `--fields`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Field {
+    Title,
+    Company,
+    DatePosted,
+    Location,
+    Remuneration,
+    Tags,
+    Apply,
+    Site,
+}
+
+impl Field {
+    /// Every field, in the order they are exported by default.
+    pub const ALL: [Field; 8] = [
+        Field::Title,
+        Field::Company,
+        Field::DatePosted,
+        Field::Location,
+        Field::Remuneration,
+        Field::Tags,
+        Field::Apply,
+        Field::Site,
+    ];
+
+    /// Returns the field's column or key name.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Title => "title",
+            Self::Company => "company",
+            Self::DatePosted => "date_posted",
+            Self::Location => "location",
+            Self::Remuneration => "remuneration",
+            Self::Tags => "tags",
+            Self::Apply => "apply",
+            Self::Site => "site",
+        }
+    }
+
+    /// Returns the field's value for a job as text. Tags are joined with ";".
+    pub fn text(self, job: &Job) -> String {
+        match self {
+            Self::Title => job.title.clone(),
+            Self::Company => job.company.clone(),
+            Self::DatePosted => job.date_posted.clone(),
+            Self::Location => job.location.clone(),
+            Self::Remuneration => job.remuneration.clone(),
+            Self::Tags => job.tags.join(";"),
+            Self::Apply => job.apply.clone(),
+            Self::Site => job.site.to_string(),
+        }
+    }
+}
+
+/// Parses a field by name, or by the aliases "salary" for remuneration and "date" for date_posted.
+impl FromStr for Field {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("salary") {
+            return Ok(Self::Remuneration);
+        } else if s.eq_ignore_ascii_case("date") {
+            return Ok(Self::DatePosted);
+        }
+        Self::ALL
+            .into_iter()
+            .find(|field| field.name().eq_ignore_ascii_case(s))
+            .ok_or(format!(
+                "Unknown field \"{s}\", expected one of: {}",
+                Self::ALL.iter().map(|field| field.name()).join(", ")
+            ))
+    }
+}
+
+/// Parses a comma separated list of fields, e.g. "title,company,apply", keeping their order.
+pub fn parse_fields(s: &str) -> Result<Vec<Field>, String> {
+    s.split(',').map(str::parse).collect()
+}
+
+/// A job restricted to the given fields, serialized as an object with the fields' keys in order.
+struct Selected<'a>(&'a Job, &'a [Field]);
+
+impl Serialize for Selected<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.1.len()))?;
+        for &field in self.1 {
+            match field {
+                Field::Tags => map.serialize_entry(field.name(), &self.0.tags)?,
+                _ => map.serialize_entry(field.name(), &field.text(self.0))?,
+            }
+        }
+        map.end()
+    }
+}
+
+/// Writes jobs as CSV with a header row and every field as a column (see [`write_csv_fields`]).
+pub fn write_csv<W: Write>(jobs: &[JobRef], writer: W) -> io::Result<()> {
+    write_csv_fields(jobs, &Field::ALL, writer)
+}
+
+/// Writes jobs as CSV with a header row and a column for each of the given fields, in order. Tags
+/// are joined into one cell with ";", and fields are quoted as needed per RFC 4180.
+pub fn write_csv_fields<W: Write>(jobs: &[JobRef], fields: &[Field], writer: W) -> io::Result<()> {
+    let mut writer = csv::Writer::from_writer(writer);
+    writer.write_record(fields.iter().map(|field| field.name()))?;
+    for job in jobs {
+        writer.write_record(fields.iter().map(|field| field.text(job)))?;
+    }
+    writer.flush()
+}
+
+/// Writes jobs as JSON Lines, one JSON job per line, through a buffer that is written out whenever
+/// it fills, so that the jobs are never all serialized in memory at once.
+pub fn write_json_lines<W: Write>(jobs: &[JobRef], writer: W) -> io::Result<()> {
+    let mut writer = BufWriter::new(writer);
+    for job in jobs {
+        serde_json::to_writer(&mut writer, job.as_ref())?;
+        writeln!(writer)?;
+    }
+    writer.flush()
+}
+
+/// Reads jobs from a JSON file, e.g. one written by `export json`, or a JSON Lines file, e.g. one
+/// written by `export jsonl`.
+pub fn read_jobs<P: AsRef<Path>>(path: P) -> io::Result<Vec<Job>> {
+    let contents = fs::read_to_string(path)?;
+    if contents.trim_start().starts_with('[') {
+        Ok(serde_json::from_str(&contents)?)
+    } else {
+        read_json_lines(contents.as_bytes())
+    }
+}
+
+/// Reads jobs from JSON Lines, skipping blank lines.
+pub fn read_json_lines<R: io::Read>(reader: R) -> io::Result<Vec<Job>> {
+    let mut jobs = vec![];
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        if !line.trim().is_empty() {
+            jobs.push(serde_json::from_str(&line)?);
+        }
+    }
+    Ok(jobs)
+}
+
+/// Compacts a JSON Lines jobs file, e.g. one appended to by repeated `--stream` runs, keeping only
+/// the latest record of each job by stable id. Jobs keep the position of their first record. The
+/// compacted file is written alongside the original and then renamed over it, so the file is never
+/// left half written. Returns the number of records before and after compacting.
+pub fn compact_json_lines<P: AsRef<Path>>(path: P) -> io::Result<(usize, usize)> {
+    let path = path.as_ref();
+    let records = read_json_lines(File::open(path)?)?;
+    let before = records.len();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    let mut jobs: Vec<JobRef> = vec![];
+    for job in records {
+        match positions.get(&job.id()) {
+            Some(&i) => jobs[i] = job.into(),
+            None => {
+                positions.insert(job.id(), jobs.len());
+                jobs.push(job.into());
+            }
+        }
+    }
+
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(".tmp");
+    write_json_lines(&jobs, File::create(&temp)?)?;
+    fs::rename(&temp, path)?;
+    Ok((before, jobs.len()))
+}
+
+/// Compacts a JSON Lines jobs file as [`compact_json_lines`] does, but only if it holds more than
+/// `max` records. Returns the number of records before and after compacting, or None if the file
+/// was left as it was.
+pub fn compact_json_lines_over<P: AsRef<Path>>(
+    path: P,
+    max: usize,
+) -> io::Result<Option<(usize, usize)>> {
+    let path = path.as_ref();
+    let mut records = 0;
+    for line in BufReader::new(File::open(path)?).lines() {
+        if !line?.trim().is_empty() {
+            records += 1;
+        }
+    }
+    if records > max {
+        compact_json_lines(path).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+/// Returns the jobs that are not in the baseline, compared by stable id, in their original order.
+pub fn new_since(jobs: &[JobRef], baseline: &[Job]) -> Vec<JobRef> {
+    let seen = baseline.iter().map(Job::id).collect::<HashSet<String>>();
+    jobs.iter()
+        .filter(|job| !seen.contains(&job.id()))
+        .cloned()
+        .collect()
+}
+
+/// Represents the file formats jobs can be exported to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Json,
+    JsonLines,
+    MarkdownTable,
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "json" => Ok(Self::Json),
+            "jsonl" | "ndjson" => Ok(Self::JsonLines),
+            "csv" => Ok(Self::Csv),
+            "markdown-table" | "markdown" | "md" => Ok(Self::MarkdownTable),
+            _ => Err(format!("Unknown export format \"{s}\"")),
+        }
+    }
+}
+
+impl Display for Format {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Json => write!(f, "json"),
+            Self::JsonLines => write!(f, "jsonl"),
+            Self::MarkdownTable => write!(f, "markdown-table"),
+            Self::Csv => write!(f, "csv"),
+        }
+    }
+}
+
+/// Writes jobs to a file in the given format.
+pub fn write_jobs<P: AsRef<Path>>(jobs: &[JobRef], path: P, format: Format) -> io::Result<()> {
+    let contents = match format {
+        Format::Json => json(jobs)?,
+        Format::JsonLines => return write_json_lines(jobs, File::create(path)?),
+        Format::MarkdownTable => markdown_table(jobs),
+        Format::Csv => return write_csv(jobs, File::create(path)?),
+    };
+    fs::write(path, contents)
+}
+
+/// Writes jobs to a file in the given format, restricted to the given fields in order. Only the
+/// JSON, JSON Lines and CSV formats can be restricted.
+pub fn write_jobs_fields<P: AsRef<Path>>(
+    jobs: &[JobRef],
+    path: P,
+    format: Format,
+    fields: &[Field],
+) -> io::Result<()> {
+    let selected = jobs.iter().map(|job| Selected(job, fields));
+    match format {
+        Format::Json => {
+            let contents = serde_json::to_string_pretty(&selected.collect::<Vec<Selected>>())?;
+            fs::write(path, contents)
+        }
+        Format::JsonLines => {
+            let mut writer = BufWriter::new(File::create(path)?);
+            for job in selected {
+                serde_json::to_writer(&mut writer, &job)?;
+                writeln!(writer)?;
+            }
+            writer.flush()
+        }
+        Format::Csv => write_csv_fields(jobs, fields, File::create(path)?),
+        Format::MarkdownTable => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("fields can't be selected for the {format} format"),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use crate::repository::Job;
+    use crate::site::WEB3_CAREERS_URL;
+
+    use super::{
+        compact_json_lines, compact_json_lines_over, json, markdown_table, new_since, parse_fields,
+        read_jobs, write_csv, write_csv_fields, write_jobs, write_jobs_fields, Format,
+    };
+
+    #[test]
+    fn test_markdown_table() {
+        let job = Job {
+            title: "Senior Backend Engineer | Rust".into(),
+            company: "Company_1".into(),
+            date_posted: "2022-07-27".into(),
+            location: "Remote".into(),
+            remuneration: "$165k - $200k".into(),
+            tags: vec!["tag1".into(), "tag2".into()],
+            apply: "https://site1.com/apply".into(),
+            site: "https://site1.com",
+            ..Default::default()
+        };
+        let jobs = vec![
+            Rc::new(job.clone()),
+            Rc::new(Job {
+                title: "Engineer".into(),
+                apply: "https://site1.com/Foo_(bar)/<id> 1|2".into(),
+                ..job
+            }),
+        ];
+        let table = markdown_table(&jobs);
+        let mut lines = table.lines();
+
+        assert_eq!(
+            lines.next().unwrap(),
+            "| Date | Company | Title | Location | Salary | Apply |"
+        );
+        assert_eq!(
+            lines.next().unwrap(),
+            "| --- | --- | --- | --- | --- | --- |"
+        );
+        assert_eq!(
+            lines.next().unwrap(),
+            "| 2022-07-27 | Company_1 | Senior Backend Engineer \\| Rust | Remote | $165k - $200k | \
+            [Apply](https://site1.com/apply) |"
+        );
+        assert_eq!(
+            lines.next().unwrap(),
+            "| 2022-07-27 | Company_1 | Engineer | Remote | $165k - $200k | \
+            [Apply](https://site1.com/Foo_%28bar%29/%3Cid%3E%201%7C2) |"
+        );
+        assert_eq!(lines.next(), None);
+    }
+
+    #[test]
+    fn test_new_since_baseline() {
+        let job = |title: &str, date_posted: &str| {
+            Rc::new(Job {
+                title: title.into(),
+                company: "Company_1".into(),
+                date_posted: date_posted.into(),
+                site: WEB3_CAREERS_URL,
+                ..Default::default()
+            })
+        };
+        let baseline = vec![
+            job("Backend Engineer", "2022-07-27"),
+            job("Frontend Engineer", "2022-07-27"),
+        ];
+        let baseline = serde_json::from_str::<Vec<Job>>(&json(&baseline).unwrap()).unwrap();
+        let current = vec![
+            job("Backend  engineer", "2022-07-27"),
+            job("Frontend Engineer", "2022-07-28"),
+            job("DevOps Engineer", "2022-07-28"),
+        ];
+
+        let titles = new_since(&current, &baseline)
+            .iter()
+            .map(|job| job.title.clone())
+            .collect::<Vec<String>>();
+        assert_eq!(titles, vec!["Frontend Engineer", "DevOps Engineer"]);
+
+        // a baseline job from a site that is no longer known doesn't fail the baseline
+        let stale = json(&baseline.into_iter().map(Rc::new).collect::<Vec<_>>())
+            .unwrap()
+            .replacen(WEB3_CAREERS_URL, "https://removed-site.com", 1);
+        let baseline = serde_json::from_str::<Vec<Job>>(&stale).unwrap();
+        assert_eq!(baseline[0].site, "");
+        assert_eq!(baseline[1].site, WEB3_CAREERS_URL);
+        assert_eq!(new_since(&current, &baseline).len(), 3);
+    }
+
+    #[test]
+    fn test_json_lines_round_trip() {
+        let path = std::env::temp_dir().join(format!("jobhunt-{}-jobs.jsonl", std::process::id()));
+        let jobs = ["Backend Engineer", "Frontend Engineer\nRust"]
+            .into_iter()
+            .map(|title| {
+                Rc::new(Job {
+                    title: title.into(),
+                    company: "Company_1".into(),
+                    site: WEB3_CAREERS_URL,
+                    ..Default::default()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        write_jobs(&jobs, &path, "jsonl".parse::<Format>().unwrap()).unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        // each line is a job by itself
+        let read = contents
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect::<Vec<Job>>();
+        assert_eq!(read.len(), 2);
+        assert_eq!(read[1].title, "Frontend Engineer\nRust");
+        assert_eq!(read_jobs(&path).unwrap(), read);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_compact_json_lines() {
+        let path = std::env::temp_dir().join(format!("jobhunt-{}-log.jsonl", std::process::id()));
+        let job = |title: &str, tags: &[&str]| {
+            Rc::new(Job {
+                title: title.into(),
+                company: "Company_1".into(),
+                date_posted: "2022-07-27".into(),
+                tags: tags.iter().map(|tag| tag.to_string()).collect(),
+                site: WEB3_CAREERS_URL,
+                ..Default::default()
+            })
+        };
+        let records = vec![
+            job("Backend Engineer", &["v1"]),
+            job("Frontend Engineer", &[]),
+            job("Backend Engineer", &["v2"]),
+            job("Backend Engineer", &["v3"]),
+        ];
+        write_jobs(&records, &path, Format::JsonLines).unwrap();
+
+        assert_eq!(compact_json_lines_over(&path, 4).unwrap(), None);
+        assert_eq!(read_jobs(&path).unwrap().len(), 4);
+        assert_eq!(compact_json_lines_over(&path, 3).unwrap(), Some((4, 2)));
+        write_jobs(&records, &path, Format::JsonLines).unwrap();
+        assert_eq!(compact_json_lines(&path).unwrap(), (4, 2));
+        let jobs = read_jobs(&path).unwrap();
+        assert_eq!(jobs[0].title, "Backend Engineer");
+        assert_eq!(jobs[0].tags, vec!["v3"]);
+        assert_eq!(jobs[1].title, "Frontend Engineer");
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_csv() {
+        let jobs = vec![Rc::new(Job {
+            title: "Engineer, \"Rust\"".into(),
+            company: "Company_1".into(),
+            date_posted: "2022-07-27".into(),
+            tags: vec!["rust".into(), "tokio".into()],
+            apply: "https://site1.com/apply".into(),
+            site: "https://site1.com",
+            ..Default::default()
+        })];
+        let mut out = vec![];
+        write_csv(&jobs, &mut out).unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "title,company,date_posted,location,remuneration,tags,apply,site\n\
+            \"Engineer, \"\"Rust\"\"\",Company_1,2022-07-27,,,rust;tokio,https://site1.com/apply,\
+            https://site1.com\n"
+        );
+    }
+
+    #[test]
+    fn test_restricted_fields() {
+        let jobs = vec![Rc::new(Job {
+            title: "Backend Engineer".into(),
+            company: "Company_1".into(),
+            date_posted: "2022-07-27".into(),
+            tags: vec!["rust".into(), "tokio".into()],
+            apply: "https://site1.com/apply".into(),
+            site: "https://site1.com",
+            ..Default::default()
+        })];
+        let fields = parse_fields("apply,title,tags").unwrap();
+
+        let mut out = vec![];
+        write_csv_fields(&jobs, &fields, &mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "apply,title,tags\nhttps://site1.com/apply,Backend Engineer,rust;tokio\n"
+        );
+
+        let path =
+            std::env::temp_dir().join(format!("jobhunt-{}-fields.jsonl", std::process::id()));
+        write_jobs_fields(&jobs, &path, Format::JsonLines, &fields).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "{\"apply\":\"https://site1.com/apply\",\"title\":\"Backend Engineer\",\
+            \"tags\":[\"rust\",\"tokio\"]}\n"
+        );
+        assert!(write_jobs_fields(&jobs, &path, Format::MarkdownTable, &fields).is_err());
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            parse_fields("title,pay").unwrap_err(),
+            "Unknown field \"pay\", expected one of: title, company, date_posted, location, \
+            remuneration, tags, apply, site"
+        );
+    }
+}
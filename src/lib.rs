@@ -1,23 +1,85 @@
 //! The library module for the application.
 
+use std::env;
 use std::error::Error;
 use std::io;
+use std::process::ExitCode;
 
+use crate::config::Config;
+use crate::lock::{Lockfile, LOCKFILE};
 use crate::repl::Repl;
+use crate::repository::SoftwareJobs;
 
+mod cache;
+mod color;
+mod company;
+pub mod config;
+mod export;
+mod lock;
 mod repl;
 pub mod repository;
 mod scraper;
+mod shutdown;
 mod site;
+mod status;
+#[cfg(feature = "tui")]
+mod tui;
 
-/// Initialize Job Hunt for job repo type T, e.g. SoftwareJobs.
-pub fn init_jobhunt<T>() -> Result<(), Box<dyn Error>>
+pub use crate::scraper::Error as ScrapeError;
+
+/// Scrapes every site and indexes the jobs, without printing anything or starting the REPL, so that
+/// Job Hunt can be used as a library, e.g. behind a web frontend. Sites that fail to scrape are left
+/// out of the repository and returned with their URL and error (see
+/// [`SoftwareJobs::try_init_repo`]).
+/// ```no_run
+/// use jobhunt::config::Config;
+///
+/// let (repo, failures) = jobhunt::fetch_jobs(&Config::from_env());
+/// for job in &repo.all {
+///     println!("{} at {}", job.title, job.company);
+/// }
+/// for (url, err) in failures {
+///     eprintln!("{url}: {err}");
+/// }
+/// ```
+pub fn fetch_jobs(config: &Config) -> (SoftwareJobs, Vec<(String, ScrapeError)>) {
+    SoftwareJobs::try_init_repo(config)
+}
+
+/// Initialize Job Hunt for job repo type T, e.g. SoftwareJobs. Command line arguments select
+/// between the REPL and non-interactive modes.
+pub fn init_jobhunt<T>() -> Result<ExitCode, Box<dyn Error>>
 where
     T: Repl,
 {
     let stdout = io::stdout();
+    color::init_colors();
+    let config = Config::from_env_and_args(env::args().skip(1))?;
+
+    if config.print_config {
+        print!("{config}");
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    scraper::check_selectors()?;
+    config.create_output_dir()?;
+    config.migrate_legacy_files();
+    // held until Job Hunt exits, so a second instance refuses to start
+    let lock = Lockfile::acquire(config.output_path(LOCKFILE))?;
+    let lock_path = lock.path().to_path_buf();
+    shutdown::on_signal().register("lockfile", move || {
+        lock::release(&lock_path).map_err(|err| err.to_string())
+    });
+    shutdown::install_signal_handler()?;
+
+    if let Some(path) = &config.scrape_only {
+        return T::scrape_only(&mut stdout.lock(), &config, path);
+    }
+    #[cfg(feature = "tui")]
+    if config.tui {
+        T::init_tui(&mut stdout.lock(), &config)?;
+        return Ok(ExitCode::SUCCESS);
+    }
 
-    T::init_repl(&mut stdout.lock())
-        .unwrap_or_else(|err| panic!("An error occurred while initializing Job Hunt: {err}"));
-    Ok(())
+    T::init_repl(&mut stdout.lock(), &config)
 }
@@ -0,0 +1,223 @@
+//! The lock module contains the lockfile used to detect duplicate runs of Job Hunt, which would
+//! otherwise scrape simultaneously and clobber each other's history and saved files.
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+
+use chrono::{Duration, Local, NaiveDateTime};
+use thiserror::Error;
+
+/// The default lockfile name, created in the output directory.
+pub const LOCKFILE: &str = "jobhunt.lock";
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Represents errors that can occur while acquiring the lock.
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Job Hunt is already running (process {0}). If it is not, delete \"{1}\" and retry")]
+    Held(u32, String),
+    #[error("Could not create lockfile: {0}")]
+    Io(#[from] io::Error),
+}
+
+/// A lock held for as long as this value lives. The lockfile is removed on drop.
+#[derive(Debug)]
+pub struct Lockfile {
+    path: PathBuf,
+}
+
+impl Lockfile {
+    /// Acquires the lock at the given path. A lock left behind by a crashed run is detected as
+    /// stale and replaced; a lock held by a live process is an error.
+    pub fn acquire<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let path = path.as_ref().to_path_buf();
+        match Self::create(&path) {
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {
+                let contents = fs::read_to_string(&path).unwrap_or_default();
+                if !is_stale(&contents, Local::now().naive_local(), process_running)
+                    || !remove_stale(&path, &contents)?
+                {
+                    return Err(held(&path));
+                }
+                match Self::create(&path) {
+                    // another run replaced the stale lock first
+                    Err(err) if err.kind() == io::ErrorKind::AlreadyExists => Err(held(&path)),
+                    result => Ok(result?),
+                }
+            }
+            result => Ok(result?),
+        }
+    }
+
+    /// Returns the lockfile's path.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Atomically creates the lockfile, recording the current process id and time. The contents are
+    /// written to a temporary file first and then moved into place, so that another run never reads
+    /// a lockfile that is still empty and mistakes it for a stale one. The move is a hard link
+    /// rather than a rename, as a rename would replace a lockfile created in the meantime.
+    fn create(path: &Path) -> io::Result<Self> {
+        let temp = sibling(path, "tmp");
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&temp)?;
+        let linked = write!(
+            file,
+            "{}\n{}",
+            process::id(),
+            Local::now().format(TIMESTAMP_FORMAT)
+        )
+        .and_then(|_| file.sync_all())
+        .and_then(|_| fs::hard_link(&temp, path));
+        fs::remove_file(&temp).ok();
+        linked?;
+        Ok(Self {
+            path: path.to_path_buf(),
+        })
+    }
+}
+
+impl Drop for Lockfile {
+    fn drop(&mut self) {
+        release(&self.path).ok();
+    }
+}
+
+/// Removes the lockfile at the given path if it is held by this process, leaving a lock taken by
+/// another run in place.
+pub fn release(path: &Path) -> io::Result<()> {
+    let contents = fs::read_to_string(path)?;
+    if parse(&contents).is_some_and(|(pid, _)| pid == process::id()) {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+/// Removes a stale lockfile with the given contents. It is first renamed to a name unique to this
+/// process, so that of several runs replacing it at once only one removes it. If the file renamed
+/// turns out to be a lock another run has taken in the meantime, it is put back. Returns whether
+/// the stale lock was removed, by this run or another.
+fn remove_stale(path: &Path, contents: &str) -> io::Result<bool> {
+    let stale = sibling(path, "stale");
+    match fs::rename(path, &stale) {
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(true),
+        result => result?,
+    }
+    let removed = fs::read_to_string(&stale).unwrap_or_default() == contents;
+    if !removed {
+        fs::hard_link(&stale, path).ok();
+    }
+    fs::remove_file(&stale)?;
+    Ok(removed)
+}
+
+/// Returns the error for a lock held by another run, with the process id in its lockfile.
+fn held(path: &Path) -> Error {
+    let contents = fs::read_to_string(path).unwrap_or_default();
+    Error::Held(
+        parse(&contents).map_or(0, |(pid, _)| pid),
+        path.display().to_string(),
+    )
+}
+
+/// Returns a path next to the lockfile unique to this process, e.g. `jobhunt.lock.1234.tmp`.
+fn sibling(path: &Path, extension: &str) -> PathBuf {
+    let mut sibling = path.as_os_str().to_owned();
+    sibling.push(format!(".{}.{extension}", process::id()));
+    PathBuf::from(sibling)
+}
+
+/// Parses lockfile contents into the owning process id and the time the lock was taken.
+fn parse(contents: &str) -> Option<(u32, NaiveDateTime)> {
+    let mut lines = contents.lines();
+    let pid = lines.next()?.trim().parse().ok()?;
+    let taken = NaiveDateTime::parse_from_str(lines.next()?.trim(), TIMESTAMP_FORMAT).ok()?;
+    Some((pid, taken))
+}
+
+/// A lock is stale if it cannot be parsed or its owning process is no longer running. If it can't
+/// be told whether the process is running, a lock taken more than a day ago is stale instead, so
+/// that a long running session keeps its lock wherever that can be checked.
+fn is_stale<F>(contents: &str, now: NaiveDateTime, is_running: F) -> bool
+where
+    F: Fn(u32) -> Option<bool>,
+{
+    match parse(contents) {
+        Some((pid, taken)) => match is_running(pid) {
+            Some(running) => !running,
+            None => now - taken > Duration::days(1),
+        },
+        None => true,
+    }
+}
+
+/// Checks whether a process is running, or None if that can't be told. This relies on procfs, so
+/// on platforms without it stale locks expire by age alone.
+fn process_running(pid: u32) -> Option<bool> {
+    let proc = Path::new("/proc");
+    proc.exists().then(|| proc.join(pid.to_string()).exists())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Local};
+
+    use super::{is_stale, remove_stale, sibling, Error, Lockfile, TIMESTAMP_FORMAT};
+
+    #[test]
+    fn test_stale_lock_detection() {
+        let now = Local::now().naive_local();
+        let contents =
+            |taken: chrono::NaiveDateTime| format!("1234\n{}", taken.format(TIMESTAMP_FORMAT));
+
+        let old = contents(now - Duration::days(2));
+
+        assert!(!is_stale(&contents(now), now, |_| Some(true)));
+        assert!(is_stale(&contents(now), now, |_| Some(false)));
+        assert!(!is_stale(&old, now, |_| Some(true)));
+        assert!(!is_stale(&contents(now), now, |_| None));
+        assert!(is_stale(&old, now, |_| None));
+        assert!(is_stale("garbage", now, |_| Some(true)));
+    }
+
+    #[test]
+    fn test_lockfile_cleanup() {
+        let path = std::env::temp_dir().join(format!("jobhunt-{}.lock", std::process::id()));
+        std::fs::write(&path, "4294967295\n2000-01-01 00:00:00").unwrap();
+
+        let lock = Lockfile::acquire(&path).unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(contents.starts_with(&format!("{}\n", std::process::id())));
+        assert!(matches!(Lockfile::acquire(&path), Err(Error::Held(..))));
+        assert!(!sibling(&path, "tmp").exists());
+        drop(lock);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_lock_taken_by_another_run_is_kept() {
+        let path = std::env::temp_dir().join(format!("jobhunt-{}-other.lock", std::process::id()));
+        std::fs::write(&path, "4294967295\n2000-01-01 00:00:00").unwrap();
+        let lock = Lockfile::acquire(&path).unwrap();
+
+        // another run replaced this run's lock
+        std::fs::write(&path, "1\n2000-01-01 00:00:00").unwrap();
+        drop(lock);
+        assert!(path.exists());
+
+        // a stale lock replaced by another run's lock before it could be removed is put back
+        assert!(!remove_stale(&path, "4294967295\n2000-01-01 00:00:00").unwrap());
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "1\n2000-01-01 00:00:00"
+        );
+        assert!(!sibling(&path, "stale").exists());
+        assert!(remove_stale(&path, "1\n2000-01-01 00:00:00").unwrap());
+        assert!(!path.exists());
+    }
+}
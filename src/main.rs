@@ -1,9 +1,11 @@
 use std::error::Error;
+use std::process::ExitCode;
 
 use jobhunt::init_jobhunt;
 use jobhunt::repository::SoftwareJobs;
 
-fn main() -> Result<(), Box<dyn Error>> {
-    init_jobhunt::<SoftwareJobs>()?;
-    Ok(())
+fn main() -> Result<ExitCode, Box<dyn Error>> {
+    // diagnostics are only logged if requested, e.g. RUST_LOG=jobhunt=info, to keep the REPL clean
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("off")).init();
+    init_jobhunt::<SoftwareJobs>()
 }
@@ -1,18 +1,426 @@
 //! The repl module contains all read/eval print loop (the terminal UI for the application) code.
 //! The rustyline crate is used to provide all standard CLI functionality, e.g. command history,
-//! CTRL-L to clear screen, CTRL-C to interrupt, etc.
+//! CTRL-L to clear screen, CTRL-C to interrupt, tab completion of commands, etc.
 
-use std::cmp::Reverse;
 use std::error::Error;
 use std::fmt::{Display, Formatter};
-use std::io::Write;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode, Stdio};
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
 
-use chrono::Local;
+use chrono::{DateTime, Local, NaiveDate};
 use colored::Colorize;
+use itertools::Itertools;
+use regex::Regex;
+use rustyline::completion::Completer;
 use rustyline::error::ReadlineError;
-use rustyline::DefaultEditor;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{CompletionType, Context, Editor, Helper};
 
-use crate::repository::SoftwareJobs;
+use crate::cache::{self, CACHE_FILE};
+use crate::config::{parse_title_keywords, Config};
+use crate::export::{
+    compact_json_lines, compact_json_lines_over, new_since, parse_fields, read_jobs, write_jobs,
+    write_jobs_fields, Field, Format,
+};
+use crate::repository::{
+    benefit_name, check_site_jobs, scrape_streaming, CompanyOrder, Job, JobOrder, JobRef, Level,
+    Listing, Location, Region, SiteResult, Skill, SoftwareJobs, BENEFITS, DATE_FORMAT,
+};
+use crate::scraper::{Error as ScrapeError, SITES};
+use crate::shutdown::{on_signal, Shutdown};
+use crate::status::{Status, StatusStore};
+
+/// The exit code used when jobs were written but one or more sites failed to scrape.
+const PARTIAL_FAILURE: u8 = 2;
+/// The exit code used when fewer jobs than `--min-jobs` were found.
+const TOO_FEW_JOBS: u8 = 3;
+/// The REPL command history file name, created in the output directory.
+pub(crate) const HISTORY_FILE: &str = "history";
+/// The ANSI sequence that clears the terminal and moves the cursor to its top left corner.
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+/// Every REPL command with a one-line description, as printed by `help`. Add new commands here.
+const COMMANDS: &[(&str, &str)] = &[
+    ("fetch jobs", "list all jobs, newest first"),
+    (
+        "fetch jobs page <n>",
+        "list one page of all jobs, newest first",
+    ),
+    (
+        "fetch jobs sort <order>",
+        "list all jobs by date [asc|desc], company, remuneration or confidence",
+    ),
+    (
+        "fetch jobs shuffle [seed]",
+        "list all jobs in a random order",
+    ),
+    (
+        "fetch jobs status <status>",
+        "list jobs with an application status",
+    ),
+    (
+        "fetch jobs benefit <benefit>",
+        "list jobs offering a benefit, e.g. \"unlimited PTO\"",
+    ),
+    (
+        "fetch jobs closing-soon",
+        "list jobs with an application deadline, soonest first",
+    ),
+    (
+        "fetch skill <skill,skill...>",
+        "list jobs requiring any of the skills",
+    ),
+    (
+        "fetch <skill> <tag...>",
+        "list jobs requiring a skill with any of the tags",
+    ),
+    (
+        "fetch stack <tag+tag...>",
+        "list jobs tagged with every one of the tags",
+    ),
+    (
+        "fetch remote region <region>",
+        "list remote jobs open to a region",
+    ),
+    ("fetch country <country>", "list jobs in a country"),
+    ("filter skill <skill>", "list jobs requiring a skill"),
+    ("filter level <level>", "list jobs at a level, e.g. senior"),
+    ("filter location <location>", "list remote or onsite jobs"),
+    ("filter tag <tag>", "list jobs with a tag, e.g. solidity"),
+    ("filter has-salary", "list jobs that disclose their pay"),
+    (
+        "filter remote-only",
+        "list fully remote jobs, leaving out hybrid ones",
+    ),
+    (
+        "filter date since <date>",
+        "list jobs posted on or after a date, e.g. 2024-01-31",
+    ),
+    (
+        "filter date between <date> and <date>",
+        "list jobs posted between two dates",
+    ),
+    (
+        "filter company <name>",
+        "list a company's jobs; the name may be quoted",
+    ),
+    (
+        "filter salary above <n> [--include-unknown]",
+        "list jobs paying at least n",
+    ),
+    (
+        "recommend [n]",
+        "list the n (default 10) jobs best suiting your boosts, skills and salary floor",
+    ),
+    (
+        "search <words>",
+        "list jobs ranked by how well they match the words",
+    ),
+    (
+        "search \"<keyword>\"",
+        "list jobs whose title or tags contain the keyword",
+    ),
+    (
+        "companies [recent]",
+        "list companies by job count, or most recent posting",
+    ),
+    (
+        "compare <company> vs <company>",
+        "compare two companies' jobs",
+    ),
+    (
+        "compare-skill <skill> vs <skill>",
+        "compare two skills' jobs and salaries",
+    ),
+    (
+        "stats locations",
+        "count jobs by remote, onsite, country and city",
+    ),
+    (
+        "count",
+        "count jobs, companies and jobs per skill, level and location",
+    ),
+    ("timeline", "count jobs posted each week"),
+    ("duplicates", "list jobs posted more than once"),
+    (
+        "missing <field>",
+        "list jobs with a field empty, grouped by site",
+    ),
+    (
+        "similar <n>",
+        "list jobs similar to job n of the last results",
+    ),
+    (
+        "explain <n>",
+        "show how job n of the last results is indexed",
+    ),
+    ("open <n>", "open job n's apply link in the browser"),
+    (
+        "open all",
+        "open the apply link of every job in the last results",
+    ),
+    (
+        "apply <n>",
+        "open and copy job n's apply link and mark it applied",
+    ),
+    ("applied <n>", "mark job n as applied"),
+    ("status <n> <status>", "set job n's application status"),
+    ("favorite <n>", "add job n to favorites"),
+    ("favorites", "list favorites"),
+    (
+        "export <format> <path> [--fields <field,field...>]",
+        "write all jobs to a file",
+    ),
+    (
+        "export-new <baseline> <path> [--fields <field,field...>]",
+        "write jobs not in a baseline export to a file",
+    ),
+    (
+        "compact-log <path>",
+        "keep only the latest record of each job in a JSON Lines file",
+    ),
+    ("pin fetch ...", "re-run a fetch query after every refresh"),
+    ("unpin", "stop re-running the pinned query"),
+    ("status", "show the pinned query"),
+    (
+        "dedup on|off",
+        "merge or keep jobs posted on more than one site",
+    ),
+    (
+        "keywords [<keyword,..>|*]",
+        "show or set the title keywords jobs are kept for, then refresh",
+    ),
+    ("config", "show the configuration in effect"),
+    ("timings", "show how long each site took to scrape"),
+    ("refresh", "scrape every site again"),
+    (
+        "refresh site <site>",
+        "scrape one site again, e.g. web3careers",
+    ),
+    ("clear", "clear the terminal"),
+    (
+        "<command> > <path>",
+        "write a command's output to a file, without colors",
+    ),
+    ("help", "show this list"),
+    ("exit", "exit Job Hunt"),
+];
+
+/// Splits a line redirecting a command's output to a file, e.g. `fetch jobs > results.txt`, into
+/// the command and path. A `>` within quotes, e.g. in `search "a > b"`, is not a redirection.
+fn parse_redirect(line: &str) -> Option<(&str, &str)> {
+    let (command, path) = line.rsplit_once('>')?;
+    let (command, path) = (command.trim(), path.trim());
+    (!command.is_empty() && !path.is_empty() && command.matches('"').count() % 2 == 0)
+        .then_some((command, path))
+}
+
+/// Removes ANSI escape sequences, e.g. colors, from text.
+fn strip_ansi(text: &str) -> String {
+    Regex::new(r"\x1b\[[0-9;?]*[A-Za-z]")
+        .map(|re| re.replace_all(text, "").to_string())
+        .unwrap_or(text.to_string())
+}
+
+/// Parses a date argument in the format "%Y-%m-%d", or returns a message for the user.
+fn parse_date_arg(date: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(date, DATE_FORMAT)
+        .map_err(|_| format!("Invalid date \"{date}\", expected a date like 2024-01-31.\n"))
+}
+
+/// Returns every REPL command with its description, aligned in two columns.
+fn help() -> String {
+    let width = COMMANDS
+        .iter()
+        .map(|(usage, _)| usage.len())
+        .max()
+        .unwrap_or(0);
+    COMMANDS
+        .iter()
+        .map(|(usage, description)| format!("{usage:width$}  {description}\n"))
+        .collect()
+}
+
+/// The words completed in place of a placeholder in a command's usage.
+const PLACEHOLDER_WORDS: &[(&str, &[&str])] =
+    &[("<format>", &["json", "jsonl", "csv", "markdown-table"])];
+
+/// Returns the words that can follow `words` in a command and start with `partial`, sorted. The
+/// commands are read from `COMMANDS`, up to the first placeholder that has no words to complete.
+fn complete_command(words: &[&str], partial: &str) -> Vec<&'static str> {
+    COMMANDS
+        .iter()
+        .filter_map(|(usage, _)| {
+            let mut positions =
+                usage.split_whitespace().map_while(|word| {
+                    match PLACEHOLDER_WORDS
+                        .iter()
+                        .find(|(placeholder, _)| *placeholder == word)
+                    {
+                        Some((_, words)) => Some(words.to_vec()),
+                        None => (!word.starts_with(['<', '[', '"']) && !word.contains("..."))
+                            .then(|| word.split('|').collect()),
+                    }
+                });
+            for word in words {
+                if !positions.next()?.contains(word) {
+                    return None;
+                }
+            }
+            positions.next()
+        })
+        .flatten()
+        .filter(|word| word.starts_with(partial))
+        .sorted()
+        .dedup()
+        .collect()
+}
+
+/// Completes the word under the cursor to the commands in `COMMANDS` when Tab is pressed.
+struct CommandHelper;
+
+impl Completer for CommandHelper {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        let line = &line[..pos];
+        let start = line.rfind(' ').map_or(0, |i| i + 1);
+        let words = line[..start].split_whitespace().collect::<Vec<&str>>();
+        let candidates = complete_command(&words, &line[start..])
+            .into_iter()
+            .map(String::from)
+            .collect();
+        Ok((start, candidates))
+    }
+}
+
+impl Hinter for CommandHelper {
+    type Hint = String;
+}
+
+impl Highlighter for CommandHelper {}
+
+impl Validator for CommandHelper {}
+
+impl Helper for CommandHelper {}
+
+/// The most results `search` shows.
+const SEARCH_RESULTS: usize = 20;
+/// The number of jobs `recommend` lists when no number is given.
+const RECOMMENDATIONS: usize = 10;
+/// `open all` asks for confirmation before opening more than this many links.
+const OPEN_ALL_CONFIRM_THRESHOLD: usize = 10;
+/// The most links `open all` opens, even after confirmation.
+const OPEN_ALL_MAX: usize = 50;
+
+/// Opens a URL in the default browser.
+fn open_in_browser(url: &str) -> std::io::Result<()> {
+    let mut command = if cfg!(target_os = "windows") {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    } else if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else {
+        Command::new("xdg-open")
+    };
+    command
+        .arg(url)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(|_| ())
+}
+
+/// A function that opens a link.
+type Opener = Box<dyn FnMut(&str) -> std::io::Result<()>>;
+
+/// A function that copies text to the clipboard, returning why it couldn't if it fails.
+type Copier = Box<dyn FnMut(&str) -> Result<(), String>>;
+
+/// Copies text to the system clipboard.
+#[cfg(feature = "clipboard")]
+fn copy_to_clipboard(text: &str) -> Result<(), String> {
+    arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.set_text(text))
+        .map_err(|err| err.to_string())
+}
+
+/// Copying to the clipboard requires the `clipboard` feature.
+#[cfg(not(feature = "clipboard"))]
+fn copy_to_clipboard(_text: &str) -> Result<(), String> {
+    Err("Job Hunt was built without the \"clipboard\" feature".into())
+}
+
+/// A function that rebuilds the repository, by scraping outside of tests.
+type Refresher = Box<dyn FnMut(&Config) -> SoftwareJobs>;
+
+/// A repository's scraped jobs and when they were scraped, as cached.
+type ScrapedJobs = (Vec<Job>, DateTime<Local>);
+
+/// Warns loudly if fewer than the minimum number of jobs were found, which likely means that most
+/// scrapers broke at once. Returns true if there were too few jobs.
+fn warn_too_few_jobs<W: Write>(
+    count: usize,
+    min_jobs: Option<usize>,
+    writer: &mut W,
+) -> std::io::Result<bool> {
+    match min_jobs {
+        Some(min) if count < min => {
+            let message = format!(
+                "WARNING: only {count} jobs were found, fewer than the minimum of {min}. Scrapers may \
+                be broken.\n"
+            );
+            writer.write_all(message.bold().red().to_string().as_bytes())?;
+            writer.flush()?;
+            Ok(true)
+        }
+        _ => Ok(false),
+    }
+}
+
+/// Parses the optional `--fields <field,field...>` that may follow an export command's arguments.
+fn parse_export_fields(rest: &[&str]) -> Result<Option<Vec<Field>>, String> {
+    match rest {
+        [] => Ok(None),
+        ["--fields", fields] => parse_fields(fields).map(Some),
+        _ => Err("Expected \"--fields <field,field...>\" after the export path".into()),
+    }
+}
+
+/// Writes jobs to a file in the given format, restricted to the given fields if any.
+fn export(
+    jobs: &[JobRef],
+    path: &Path,
+    format: Format,
+    fields: Option<&[Field]>,
+) -> std::io::Result<()> {
+    match fields {
+        Some(fields) => write_jobs_fields(jobs, path, format, fields),
+        None => write_jobs(jobs, path, format),
+    }
+}
+
+/// Returns the non-empty apply links of the given jobs, up to `OPEN_ALL_MAX`.
+fn links_to_open(jobs: &[JobRef]) -> Vec<String> {
+    jobs.iter()
+        .map(|job| job.apply.trim())
+        .filter(|link| !link.is_empty())
+        .take(OPEN_ALL_MAX)
+        .map(String::from)
+        .collect()
+}
 
 /// A trait to be implemented by both the String and str types.
 trait ReplStringConverter {
@@ -58,95 +466,2058 @@ impl Display for ReplString {
     }
 }
 
-/// This trait must be implemented by the specific job repo struct to be used in Job Hunt (e.g. SoftwareJobs).
-pub trait Repl {
-    /// Initializes a repository for the job repo type that is implementing this trait; then
-    /// initializes the REPL and parses queries.
-    fn init_repl<W>(writer: &mut W) -> Result<(), Box<dyn Error>>
-    where
-        W: Write;
+/// Represents the outcome of evaluating a line of input.
+#[derive(Debug, PartialEq)]
+enum Flow {
+    Continue,
+    Exit,
 }
 
-impl Repl for SoftwareJobs {
-    fn init_repl<W>(writer: &mut W) -> Result<(), Box<dyn Error>>
+/// Represents the state of a single REPL session.
+struct Session {
+    config: Config,
+    /// The jobs returned by the last query, numbered from 1 in listings.
+    last: Vec<JobRef>,
+    /// Jobs bookmarked by the user during the session.
+    favorites: Vec<JobRef>,
+    /// A copy of the favorites that can be saved from the signal handler's thread.
+    favorites_snapshot: Arc<Mutex<Vec<Job>>>,
+    /// A copy of the repository's scraped jobs and when they were scraped, that can be cached from
+    /// the signal handler's thread.
+    scraped_snapshot: Arc<Mutex<Option<ScrapedJobs>>>,
+    /// Application statuses, persisted between sessions.
+    statuses: StatusStore,
+    /// Links waiting for the user to confirm `open all`.
+    pending_open: Option<Vec<String>>,
+    /// Opens a link, in the browser outside of tests.
+    opener: Opener,
+    /// A query re-run after every refresh.
+    pinned: Option<String>,
+    /// Rebuilds the repository on `refresh`.
+    refresher: Refresher,
+    /// Copies a link, to the system clipboard outside of tests.
+    copier: Copier,
+}
+
+impl Session {
+    /// Creates a session, loading application statuses from the configured status file. If the file
+    /// can't be read, statuses are kept for this session only so the file isn't overwritten.
+    fn new(config: &Config) -> Self {
+        let statuses = config
+            .status_file
+            .as_ref()
+            .and_then(|path| StatusStore::load(config.output_path(path)).ok())
+            .unwrap_or_default();
+        Self {
+            config: config.clone(),
+            last: vec![],
+            favorites: vec![],
+            favorites_snapshot: Arc::default(),
+            scraped_snapshot: Arc::default(),
+            statuses,
+            pending_open: None,
+            opener: Box::new(open_in_browser),
+            pinned: None,
+            refresher: Box::new(SoftwareJobs::init_repo),
+            copier: Box::new(copy_to_clipboard),
+        }
+    }
+
+    /// Opens each link, returning a message reporting how many were opened.
+    fn open_links(&mut self, links: &[String]) -> String {
+        let opened = links
+            .iter()
+            .filter(|link| (self.opener)(link).is_ok())
+            .count();
+        format!("Opened {} of {} links.\n", opened, links.len())
+    }
+
+    /// Writes numbered job listings followed by a count line, and remembers the jobs as the last
+    /// result set.
+    fn write_listings<W>(&mut self, jobs: Vec<JobRef>, writer: &mut W) -> std::io::Result<()>
     where
         W: Write,
     {
-        "Populating/indexing local datastore...\n"
+        self.write_scored_listings(jobs, &[], writer)
+    }
+
+    /// Writes listings as `write_listings` does, showing the score of each job that has one.
+    fn write_scored_listings<W>(
+        &mut self,
+        jobs: Vec<JobRef>,
+        scores: &[u32],
+        writer: &mut W,
+    ) -> std::io::Result<()>
+    where
+        W: Write,
+    {
+        for (i, job) in jobs.iter().enumerate() {
+            self.write_listing(i + 1, job, scores.get(i), writer)?;
+        }
+        format!("{} items returned.\n", jobs.len())
             .to_repl_string()
             .write(writer)?;
-        let mut repo = Self::init_repo();
-        "Population/indexing completed successfully! Welcome, please begin your job \
-        hunt by entering a query:\n"
+        self.last = jobs;
+        Ok(())
+    }
+
+    /// Writes the jobs on page `page` (numbered from 1) of `page_size` jobs each, numbered by their
+    /// position in all the jobs so they can be referred to as if every job had been listed.
+    fn write_page<W>(
+        &mut self,
+        jobs: Vec<JobRef>,
+        page: usize,
+        writer: &mut W,
+    ) -> std::io::Result<()>
+    where
+        W: Write,
+    {
+        let size = self.config.page_size;
+        let pages = jobs.len().div_ceil(size);
+        if page == 0 || page > pages {
+            return format!("There is no page {page}; there are {pages} pages of {size} jobs.\n")
+                .to_repl_string()
+                .write(writer);
+        }
+        let start = (page - 1) * size;
+        let end = (start + size).min(jobs.len());
+        for (i, job) in jobs[start..end].iter().enumerate() {
+            self.write_listing(start + i + 1, job, None, writer)?;
+        }
+        format!("Showing {}\u{2013}{end} of {}.\n", start + 1, jobs.len())
             .to_repl_string()
             .write(writer)?;
+        self.last = jobs;
+        Ok(())
+    }
 
-        let mut rl = DefaultEditor::new()?;
-        rl.load_history(".jobhunthistory").ok();
-
-        loop {
-            let readline = rl.readline(">> ");
-            match readline {
-                Ok(line) => {
-                    rl.add_history_entry(line.as_str())?;
-
-                    match line.as_str() {
-                        "fetch jobs" => {
-                            repo.all.sort_by_key(|job| {
-                                (job.date_posted.clone(), Reverse(job.company.clone()))
-                            });
-                            for job in &repo.all {
-                                writer.write_all(format!("{:?}\n", job).as_bytes())?;
-                                writer.flush()?;
-                            }
-                            format!("{} items returned.\n", repo.all.len())
-                                .to_repl_string()
-                                .write(writer)?;
+    /// Writes a job's listing, numbered `n`, with its score if it has one.
+    fn write_listing<W>(
+        &self,
+        n: usize,
+        job: &JobRef,
+        score: Option<&u32>,
+        writer: &mut W,
+    ) -> std::io::Result<()>
+    where
+        W: Write,
+    {
+        let status = self
+            .statuses
+            .get(&job.id())
+            .map(|entry| format!("{} {}", entry.status, entry.updated.format(DATE_FORMAT)));
+        let listing = Listing::new(job, self.config.expired_after_days).with_status(status);
+        let score = score
+            .map(|score| format!(" (score {score})"))
+            .unwrap_or_default();
+        writer.write_all(
+            format!(
+                "{}{} {:?}\n",
+                format!("[{n}]").bold().bright_green(),
+                score.bright_green(),
+                listing
+            )
+            .as_bytes(),
+        )?;
+        writer.flush()
+    }
+
+    /// Returns the job numbered `n` in the last result set.
+    fn last_job(&self, n: &str) -> Option<JobRef> {
+        let n = n.parse::<usize>().ok()?;
+        self.last.get(n.checked_sub(1)?).cloned()
+    }
+
+    /// Sets the status of the job numbered `n` in the last result set, returning a message for the
+    /// user.
+    fn set_status(&mut self, n: &str, status: Status) -> String {
+        match self.last_job(n) {
+            Some(job) => {
+                match self
+                    .statuses
+                    .set(&job.id(), status, Local::now().naive_local())
+                {
+                    Ok(_) => format!(
+                        "Marked \"{}\" at {} as {}.\n",
+                        job.title, job.company, status
+                    ),
+                    Err(err) => format!("Could not save status: {err}\n"),
+                }
+            }
+            None => format!("There is no job numbered {n} in the last results.\n"),
+        }
+    }
+
+    /// Applies to the job numbered `n` in the last result set: opens its apply link in the browser,
+    /// copies the link to the clipboard and marks the job as applied. The job is marked as applied
+    /// even if it has no apply link or the link can't be opened or copied. Returns a message for
+    /// the user.
+    fn apply(&mut self, n: &str) -> String {
+        let Some(job) = self.last_job(n) else {
+            return format!("There is no job numbered {n} in the last results.\n");
+        };
+        let link = job.apply.trim();
+        let mut message = if link.is_empty() {
+            format!("\"{}\" has no apply link.\n", job.title)
+        } else {
+            let opened = match (self.opener)(link) {
+                Ok(_) => "Opened the apply link".to_string(),
+                Err(err) => format!("Could not open the apply link ({err})"),
+            };
+            match (self.copier)(link) {
+                Ok(_) => format!("{opened} and copied it to the clipboard.\n"),
+                Err(err) => format!("{opened}. Could not copy it to the clipboard: {err}.\n"),
+            }
+        };
+        message.push_str(&self.set_status(n, Status::Applied));
+        message
+    }
+
+    /// Writes the session's favorites to the configured favorites file, if any. Nothing is written
+    /// when there are no favorites. Returns the path written to.
+    fn save_favorites(&self) -> std::io::Result<Option<PathBuf>> {
+        match &self.config.favorites_file {
+            Some(path) if !self.favorites.is_empty() => {
+                let path = self.config.output_path(path);
+                write_jobs(&self.favorites, &path, self.config.favorites_format)?;
+                Ok(Some(path))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Evaluates a command as `eval` does, writing its output to the file at `path` without colors
+    /// rather than to the writer, which is told whether the file was written.
+    fn eval_to_file<W>(
+        &mut self,
+        repo: &mut SoftwareJobs,
+        command: &str,
+        path: &str,
+        writer: &mut W,
+    ) -> Result<Flow, Box<dyn Error>>
+    where
+        W: Write,
+    {
+        let mut output = vec![];
+        let flow = self.eval(repo, command, &mut output)?;
+        match fs::write(path, strip_ansi(&String::from_utf8_lossy(&output))) {
+            Ok(()) => format!("Output written to {path}.\n"),
+            Err(err) => format!("Could not write output to {path}: {err}\n"),
+        }
+        .to_repl_string()
+        .write(writer)?;
+        Ok(flow)
+    }
+
+    /// Evaluates a line of input against the repository, writing any output to the writer.
+    fn eval<W>(
+        &mut self,
+        repo: &mut SoftwareJobs,
+        line: &str,
+        writer: &mut W,
+    ) -> Result<Flow, Box<dyn Error>>
+    where
+        W: Write,
+    {
+        if let Some(links) = self.pending_open.take() {
+            let message = match line.trim().to_lowercase().as_str() {
+                "y" | "yes" => self.open_links(&links),
+                _ => "Cancelled.\n".to_string(),
+            };
+            message.to_repl_string().write(writer)?;
+            return Ok(Flow::Continue);
+        }
+        if let Some((command, path)) = parse_redirect(line) {
+            return self.eval_to_file(repo, command, path, writer);
+        }
+        match line.split_whitespace().collect::<Vec<&str>>().as_slice() {
+            ["fetch", "jobs"] => {
+                let jobs = repo.fetch_jobs(&self.config);
+                self.write_listings(jobs, writer)?;
+                if repo.all.is_empty() {
+                    "No jobs are loaded - every scrape may have failed. Try \"refresh\".\n"
+                        .to_repl_string()
+                        .write(writer)?;
+                }
+            }
+            ["fetch", "jobs", "sort", order @ ..]
+                if order.join(" ").parse::<JobOrder>().is_ok() =>
+            {
+                let mut jobs = repo.fetch_jobs(&self.config);
+                order.join(" ").parse::<JobOrder>()?.sort(&mut jobs);
+                self.write_listings(jobs, writer)?;
+            }
+            ["fetch", "jobs", "page", page] if page.parse::<usize>().is_ok() => {
+                let jobs = repo.fetch_jobs(&self.config);
+                self.write_page(jobs, page.parse()?, writer)?;
+            }
+            ["fetch", "jobs", "shuffle"] => {
+                let seed = rand::random();
+                let jobs = repo.fetch_jobs_shuffled(&self.config, seed);
+                self.write_listings(jobs, writer)?;
+                format!(
+                    "Shuffled with seed {seed}; use \"fetch jobs shuffle {seed}\" to repeat.\n"
+                )
+                .to_repl_string()
+                .write(writer)?;
+            }
+            ["fetch", "jobs", "shuffle", seed] if seed.parse::<u64>().is_ok() => {
+                let jobs = repo.fetch_jobs_shuffled(&self.config, seed.parse()?);
+                self.write_listings(jobs, writer)?;
+            }
+            ["fetch", "jobs", "benefit", name @ ..] if !name.is_empty() => {
+                // benefit names contain spaces, so the name may be quoted
+                let name = name.join(" ");
+                match benefit_name(name.trim_matches('"')) {
+                    Some(benefit) => {
+                        let jobs = repo.fetch_benefit(benefit);
+                        self.write_listings(jobs, writer)?;
+                    }
+                    None => format!(
+                        "Unknown benefit {name}. Known benefits are: {}.\n",
+                        BENEFITS
+                            .iter()
+                            .map(|(name, _)| format!("\"{name}\""))
+                            .join(", ")
+                    )
+                    .to_repl_string()
+                    .write(writer)?,
+                }
+            }
+            ["fetch", "jobs", "status", status] if status.parse::<Status>().is_ok() => {
+                let status = status.parse::<Status>()?;
+                let jobs = repo
+                    .fetch_jobs(&self.config)
+                    .into_iter()
+                    .filter(|job| {
+                        self.statuses
+                            .get(&job.id())
+                            .is_some_and(|entry| entry.status == status)
+                    })
+                    .collect();
+                self.write_listings(jobs, writer)?;
+            }
+            ["fetch", "remote", "region", region] => match region.parse::<Region>() {
+                Ok(region) => {
+                    let jobs = repo.fetch_remote_region(region);
+                    self.write_listings(jobs, writer)?;
+                }
+                Err(err) => format!(
+                    "{err}. Valid regions are: {}.\n",
+                    Region::ALL
+                        .iter()
+                        .map(|region| format!("{:?}", region).to_lowercase())
+                        .join(", ")
+                )
+                .to_repl_string()
+                .write(writer)?,
+            },
+            ["fetch", "country", country @ ..] if !country.is_empty() => {
+                let jobs = repo.fetch_country(&country.join(" "));
+                self.write_listings(jobs, writer)?;
+            }
+            ["fetch", "jobs", "closing-soon"] => {
+                let jobs = repo.fetch_closing_soon();
+                self.write_listings(jobs, writer)?;
+            }
+            ["fetch", "skill", list @ ..] if !list.is_empty() => {
+                let (skills, unknown): (Vec<_>, Vec<_>) = list
+                    .join("")
+                    .split(',')
+                    .filter(|name| !name.is_empty())
+                    .map(|name| name.parse::<Skill>().map_err(|_| name.to_string()))
+                    .partition(Result::is_ok);
+                if !unknown.is_empty() {
+                    format!(
+                        "Ignoring unknown skills: {}. Valid skills are: {}.\n",
+                        unknown.into_iter().filter_map(Result::err).join(", "),
+                        Skill::ALL
+                            .iter()
+                            .map(|skill| format!("{:?}", skill))
+                            .join(", ")
+                    )
+                    .to_repl_string()
+                    .write(writer)?;
+                }
+                let skills = skills
+                    .into_iter()
+                    .filter_map(Result::ok)
+                    .collect::<Vec<_>>();
+                let jobs = repo.fetch_skills(&skills);
+                self.write_listings(jobs, writer)?;
+            }
+            ["filter", "skill", skill] if skill.parse::<Skill>().is_ok() => {
+                let jobs = repo.fetch_skills(&[skill.parse()?]);
+                self.write_listings(jobs, writer)?;
+            }
+            ["recommend", n @ ..]
+                if n.len() <= 1 && n.iter().all(|n| n.parse::<usize>().is_ok_and(|n| n > 0)) =>
+            {
+                let limit = n.first().map_or(RECOMMENDATIONS, |n| n.parse().unwrap());
+                let (scores, jobs) = repo
+                    .recommend(&self.config, limit)
+                    .into_iter()
+                    .unzip::<_, _, Vec<u32>, Vec<JobRef>>();
+                self.write_scored_listings(jobs, &scores, writer)?;
+            }
+            // a quoted keyword is matched as a phrase, e.g. search "smart contract"
+            ["search", query @ ..]
+                if query.first().is_some_and(|word| word.starts_with('"'))
+                    && query
+                        .last()
+                        .is_some_and(|word| word.len() > 1 && word.ends_with('"')) =>
+            {
+                let jobs = repo.search_keyword(query.join(" ").trim_matches('"'));
+                self.write_listings(jobs, writer)?;
+            }
+            ["search", query @ ..] if !query.is_empty() => {
+                let (scores, jobs) = repo
+                    .search(&query.join(" "))
+                    .into_iter()
+                    .take(SEARCH_RESULTS)
+                    .unzip::<_, _, Vec<u32>, Vec<JobRef>>();
+                self.write_scored_listings(jobs, &scores, writer)?;
+            }
+            ["fetch", "stack", stack] => {
+                let jobs = repo.fetch_stack(&stack.split('+').collect::<Vec<&str>>());
+                self.write_listings(jobs, writer)?;
+            }
+            ["filter", "location", location] if location.parse::<Location>().is_ok() => {
+                let jobs = repo.fetch_location(&location.parse()?);
+                self.write_listings(jobs, writer)?;
+            }
+            ["filter", "tag", tag @ ..] if !tag.is_empty() => {
+                // tags may contain spaces, so the tag may be quoted
+                let tag = tag.join(" ");
+                let jobs = repo.fetch_tag(tag.trim_matches('"'));
+                self.write_listings(jobs, writer)?;
+            }
+            ["filter", "company", name @ ..] if !name.is_empty() => {
+                // company names may contain spaces, so the name may be quoted
+                let name = name.join(" ");
+                let name = name.trim_matches('"');
+                let jobs = repo.fetch_company(name);
+                if jobs.is_empty() {
+                    format!("No jobs found for company \"{name}\".\n")
+                        .to_repl_string()
+                        .write(writer)?;
+                } else {
+                    self.write_listings(jobs, writer)?;
+                }
+            }
+            ["filter", "date", "since", since] => match parse_date_arg(since) {
+                Ok(since) => {
+                    let jobs = repo.fetch_posted_between(since, None);
+                    self.write_listings(jobs, writer)?;
+                }
+                Err(message) => message.to_repl_string().write(writer)?,
+            },
+            ["filter", "date", "between", since, "and", until] => {
+                match (parse_date_arg(since), parse_date_arg(until)) {
+                    (Ok(since), Ok(until)) => {
+                        // accept the dates in either order
+                        let jobs =
+                            repo.fetch_posted_between(since.min(until), Some(since.max(until)));
+                        self.write_listings(jobs, writer)?;
+                    }
+                    (Err(message), _) | (_, Err(message)) => {
+                        message.to_repl_string().write(writer)?
+                    }
+                }
+            }
+            ["filter", "has-salary"] => {
+                let jobs = repo.fetch_has_salary();
+                self.write_listings(jobs, writer)?;
+            }
+            ["filter", "remote-only"] => {
+                let jobs = repo.fetch_fully_remote();
+                self.write_listings(jobs, writer)?;
+            }
+            ["filter", "salary", "above", amount, flags @ ..]
+                if amount.parse::<u32>().is_ok()
+                    && flags.iter().all(|f| *f == "--include-unknown") =>
+            {
+                let jobs = repo.fetch_salary_above(amount.parse()?, !flags.is_empty());
+                self.write_listings(jobs, writer)?;
+            }
+            ["filter", "level", level] if level.parse::<Level>().is_ok() => {
+                let jobs = repo.fetch_level(&level.parse()?);
+                self.write_listings(jobs, writer)?;
+            }
+            ["fetch", skill, tags @ ..] if !tags.is_empty() && skill.parse::<Skill>().is_ok() => {
+                let jobs = repo.fetch_skill_with_tags(&skill.parse()?, tags);
+                self.write_listings(jobs, writer)?;
+            }
+            ["export", format, path, rest @ ..] if format.parse::<Format>().is_ok() => {
+                let format = format.parse::<Format>()?;
+                let path = PathBuf::from(path);
+                match parse_export_fields(rest) {
+                    Ok(fields) => match export(&repo.all, &path, format, fields.as_deref()) {
+                        Ok(_) => format!(
+                            "{} items exported to \"{}\".\n",
+                            repo.all.len(),
+                            path.display()
+                        ),
+                        Err(err) => {
+                            format!("Could not export to \"{}\": {err}\n", path.display())
                         }
-                        "exit" => break,
-                        "refresh" => {
-                            "Refreshing...\n".to_repl_string().write(writer)?;
-                            repo = Self::init_repo();
-                            format!(
-                                "Refresh completed successfully at {}.\n",
-                                Local::now().format("%d-%m-%Y %H:%M:%S")
-                            )
-                            .to_repl_string()
-                            .write(writer)?;
+                    },
+                    Err(err) => format!("{err}\n"),
+                }
+                .to_repl_string()
+                .write(writer)?;
+            }
+            ["export-new", baseline, path, rest @ ..] => {
+                let baseline = PathBuf::from(baseline);
+                match (read_jobs(&baseline), parse_export_fields(rest)) {
+                    (Err(err), _) => format!(
+                        "Could not read baseline \"{}\": {err}\n",
+                        baseline.display()
+                    ),
+                    (_, Err(err)) => format!("{err}\n"),
+                    (Ok(baseline), Ok(fields)) => {
+                        let jobs = new_since(&repo.all, &baseline);
+                        let path = PathBuf::from(path);
+                        match export(&jobs, &path, Format::Json, fields.as_deref()) {
+                            Ok(_) => format!(
+                                "{} new items exported to \"{}\".\n",
+                                jobs.len(),
+                                path.display()
+                            ),
+                            Err(err) => {
+                                format!("Could not export to \"{}\": {err}\n", path.display())
+                            }
                         }
-                        _ => {
-                            format!(
-                                "Does not compute! 🤖 \"{}\" is not a valid \
-                                query/command.\n",
-                                line.trim()
-                            )
-                            .to_repl_string()
-                            .write(writer)?;
+                    }
+                }
+                .to_repl_string()
+                .write(writer)?
+            }
+            ["compact-log", path] => {
+                let path = PathBuf::from(path);
+                match compact_json_lines(&path) {
+                    Ok((before, after)) => format!(
+                        "Compacted \"{}\" from {before} to {after} records.\n",
+                        path.display()
+                    ),
+                    Err(err) => format!("Could not compact \"{}\": {err}\n", path.display()),
+                }
+                .to_repl_string()
+                .write(writer)?
+            }
+            ["companies"] => repo
+                .companies(CompanyOrder::Count)
+                .to_string()
+                .to_repl_string()
+                .write(writer)?,
+            ["companies", "recent"] => repo
+                .companies(CompanyOrder::Recent)
+                .to_string()
+                .to_repl_string()
+                .write(writer)?,
+            ["timeline"] => repo.timeline().to_string().to_repl_string().write(writer)?,
+            ["missing", field] if field.parse::<Field>().is_ok() => repo
+                .missing(field.parse()?)
+                .to_string()
+                .to_repl_string()
+                .write(writer)?,
+            ["duplicates"] => repo
+                .duplicates()
+                .to_string()
+                .to_repl_string()
+                .write(writer)?,
+            ["count"] => repo.counts().to_string().to_repl_string().write(writer)?,
+            ["stats", "locations"] => repo
+                .location_stats()
+                .to_string()
+                .to_repl_string()
+                .write(writer)?,
+            ["compare-skill", left, "vs", right] => {
+                match (left.parse::<Skill>(), right.parse::<Skill>()) {
+                    (Ok(left), Ok(right)) => repo.compare_skills(&left, &right).to_string(),
+                    (Err(err), _) | (_, Err(err)) => format!(
+                        "{err}. Valid skills are: {}.\n",
+                        Skill::ALL
+                            .iter()
+                            .map(|skill| format!("{:?}", skill))
+                            .join(", ")
+                    ),
+                }
+                .to_repl_string()
+                .write(writer)?
+            }
+            ["compare", args @ ..] if args.contains(&"vs") => {
+                let i = args.iter().position(|a| *a == "vs").unwrap_or_default();
+                let (left, right) = (args[..i].join(" "), args[i + 1..].join(" "));
+                repo.compare_companies(&left, &right)
+                    .to_string()
+                    .to_repl_string()
+                    .write(writer)?;
+            }
+            ["favorite", n] => match self.last_job(n) {
+                Some(job) => {
+                    format!("Added \"{}\" at {} to favorites.\n", job.title, job.company)
+                        .to_repl_string()
+                        .write(writer)?;
+                    if !self.favorites.contains(&job) {
+                        if let Ok(mut snapshot) = self.favorites_snapshot.lock() {
+                            snapshot.push(job.as_ref().clone());
                         }
+                        self.favorites.push(job);
                     }
                 }
-                Err(ReadlineError::Interrupted) => {
-                    // CTRL-C
-                    break;
+                None => format!("There is no job numbered {n} in the last results.\n")
+                    .to_repl_string()
+                    .write(writer)?,
+            },
+            ["apply", n] => self.apply(n).to_repl_string().write(writer)?,
+            ["applied", n] => self
+                .set_status(n, Status::Applied)
+                .to_repl_string()
+                .write(writer)?,
+            ["status", n, status] if status.parse::<Status>().is_ok() => self
+                .set_status(n, status.parse()?)
+                .to_repl_string()
+                .write(writer)?,
+            ["similar", n] => match self.last_job(n) {
+                Some(job) => {
+                    let jobs = repo.fetch_similar(&job);
+                    self.write_listings(jobs, writer)?;
                 }
-                Err(ReadlineError::Eof) => {
-                    // CTRL-D
-                    break;
+                None => format!("There is no job numbered {n} in the last results.\n")
+                    .to_repl_string()
+                    .write(writer)?,
+            },
+            ["explain", n] => match self.last_job(n) {
+                Some(job) => repo
+                    .explain(&job)
+                    .to_string()
+                    .to_repl_string()
+                    .write(writer)?,
+                None => format!("There is no job numbered {n} in the last results.\n")
+                    .to_repl_string()
+                    .write(writer)?,
+            },
+            ["open", "all"] => {
+                let links = links_to_open(&self.last);
+                if links.len() > OPEN_ALL_CONFIRM_THRESHOLD {
+                    format!("Open {} links in the browser? (y/n)\n", links.len())
+                        .to_repl_string()
+                        .write(writer)?;
+                    self.pending_open = Some(links);
+                } else {
+                    self.open_links(&links).to_repl_string().write(writer)?;
                 }
-                Err(err) => {
-                    format!("An error has occurred: {err}")
+            }
+            ["open", n] => match self.last_job(n) {
+                Some(job) if !job.apply.trim().is_empty() => {
+                    self.open_links(&[job.apply.trim().to_string()])
                         .to_repl_string()
                         .write(writer)?;
+                }
+                Some(job) => format!("\"{}\" has no apply link.\n", job.title)
+                    .to_repl_string()
+                    .write(writer)?,
+                None => format!("There is no job numbered {n} in the last results.\n")
+                    .to_repl_string()
+                    .write(writer)?,
+            },
+            ["favorites"] => {
+                let favorites = self.favorites.clone();
+                self.write_listings(favorites, writer)?;
+            }
+            ["pin", "fetch", ..] => {
+                let query = line.split_whitespace().skip(1).join(" ");
+                format!("Pinned \"{query}\", it will be re-run after every refresh.\n")
+                    .to_repl_string()
+                    .write(writer)?;
+                self.pinned = Some(query);
+            }
+            ["pin", ..] => "Only fetch queries can be pinned, e.g. \"pin fetch jobs\".\n"
+                .to_repl_string()
+                .write(writer)?,
+            ["unpin"] => {
+                match self.pinned.take() {
+                    Some(query) => format!("Unpinned \"{query}\".\n"),
+                    None => "No query is pinned.\n".to_string(),
+                }
+                .to_repl_string()
+                .write(writer)?;
+            }
+            ["status"] => match &self.pinned {
+                Some(query) => format!("Pinned query: {query}\n"),
+                None => "No query is pinned.\n".to_string(),
+            }
+            .to_repl_string()
+            .write(writer)?,
+            ["dedup", "on"] => {
+                self.config.merge_duplicates = true;
+                let before = repo.all.len();
+                repo.rebuild(&self.config);
+                format!(
+                    "Duplicates are merged. {} jobs posted on more than one site were merged.\n",
+                    before - repo.all.len()
+                )
+                .to_repl_string()
+                .write(writer)?;
+            }
+            ["dedup", "off"] => {
+                self.config.merge_duplicates = false;
+                let before = repo.all.len();
+                repo.rebuild(&self.config);
+                format!(
+                    "Duplicates are no longer merged. {} duplicate jobs were restored.\n",
+                    repo.all.len() - before
+                )
+                .to_repl_string()
+                .write(writer)?;
+            }
+            ["keywords"] => {
+                let keywords = if self.config.title_keywords.is_empty() {
+                    "every job (*)".to_string()
+                } else {
+                    self.config.title_keywords.join(", ")
+                };
+                format!("Jobs are kept if their title contains: {keywords}.\n")
+                    .to_repl_string()
+                    .write(writer)?;
+            }
+            ["keywords", keywords @ ..] => {
+                self.config.title_keywords = parse_title_keywords(&keywords.join(" "));
+                return self.eval(repo, "refresh", writer);
+            }
+            ["config"] => self.config.to_string().to_repl_string().write(writer)?,
+            ["timings"] => repo.timings.to_string().to_repl_string().write(writer)?,
+            ["help"] => help().to_repl_string().write(writer)?,
+            // the prompt is printed again by the read loop, at the top of the cleared screen
+            ["clear"] => {
+                writer.write_all(CLEAR_SCREEN.as_bytes())?;
+                writer.flush()?;
+            }
+            ["exit"] => return Ok(Flow::Exit),
+            ["refresh"] => {
+                "Refreshing...\n".to_repl_string().write(writer)?;
+                *repo = (self.refresher)(&self.config);
+                self.snapshot_scraped(repo);
+                format!(
+                    "Refresh completed successfully at {}.\n",
+                    Local::now().format("%d-%m-%Y %H:%M:%S")
+                )
+                .to_repl_string()
+                .write(writer)?;
+                if let Some(query) = self.pinned.clone() {
+                    format!("Re-running pinned query \"{query}\":\n")
+                        .to_repl_string()
+                        .write(writer)?;
+                    self.eval(repo, &query, writer)?;
+                }
+            }
+            ["refresh", "site", name @ ..] if !name.is_empty() => {
+                let name = name.join(" ");
+                format!("Refreshing {name}...\n")
+                    .to_repl_string()
+                    .write(writer)?;
+                match repo.refresh_site(&name, &self.config) {
+                    Some((url, Ok(count))) => {
+                        self.snapshot_scraped(repo);
+                        format!("Refreshed {url}, which now has {count} jobs.\n")
+                    }
+                    Some((url, Err(err))) => {
+                        format!("Could not scrape {url}: {err}. Its jobs were kept.\n")
+                    }
+                    None => format!(
+                        "Unknown site \"{name}\". The sites are: {}.\n",
+                        SITES.iter().map(|site| site.name).join(", ")
+                    ),
+                }
+                .to_repl_string()
+                .write(writer)?;
+            }
+            _ => {
+                format!(
+                    "Does not compute! 🤖 \"{}\" is not a valid query/command.\n",
+                    line.trim()
+                )
+                .to_repl_string()
+                .write(writer)?;
+            }
+        }
+        Ok(Flow::Continue)
+    }
+
+    /// Registers a callback that saves the session's favorites if Job Hunt is stopped by a signal
+    /// rather than exiting the REPL.
+    fn save_favorites_on_signal(&self, shutdown: &Shutdown) {
+        let snapshot = self.favorites_snapshot.clone();
+        let (config, path) = (self.config.clone(), self.config.favorites_file.clone());
+        shutdown.register("favorites", move || {
+            let favorites = snapshot.lock().map_err(|err| err.to_string())?;
+            match &path {
+                Some(path) if !favorites.is_empty() => {
+                    let jobs = favorites
+                        .iter()
+                        .cloned()
+                        .map(Rc::new)
+                        .collect::<Vec<JobRef>>();
+                    write_jobs(&jobs, config.output_path(path), config.favorites_format)
+                        .map_err(|err| err.to_string())
+                }
+                _ => Ok(()),
+            }
+        });
+    }
+
+    /// Registers a callback that caches the scraped jobs, as `save_cache` does on exit, if Job Hunt
+    /// is stopped by a signal rather than exiting the REPL.
+    fn save_cache_on_signal(&self, shutdown: &Shutdown) {
+        let snapshot = self.scraped_snapshot.clone();
+        let config = self.config.clone();
+        shutdown.register("cache", move || {
+            let snapshot = snapshot.lock().map_err(|err| err.to_string())?;
+            match &*snapshot {
+                Some((jobs, scraped_at)) if config.cache => {
+                    cache::save(config.output_path(CACHE_FILE), jobs, *scraped_at)
+                        .map_err(|err| err.to_string())
+                }
+                _ => Ok(()),
+            }
+        });
+    }
+
+    /// Updates the copy of the scraped jobs cached if Job Hunt is stopped by a signal. Called
+    /// whenever the repository is scraped.
+    fn snapshot_scraped(&self, repo: &SoftwareJobs) {
+        if let (Ok(mut snapshot), Some(scraped_at)) =
+            (self.scraped_snapshot.lock(), repo.scraped_at)
+        {
+            *snapshot = Some((repo.scraped.clone(), scraped_at));
+        }
+    }
+
+    /// Finishes the session, saving anything that should outlive it.
+    fn finish<W>(&self, writer: &mut W) -> std::io::Result<()>
+    where
+        W: Write,
+    {
+        match self.save_favorites() {
+            Ok(Some(path)) => format!("Favorites saved to \"{}\".\n", path.display()),
+            Ok(None) => return Ok(()),
+            Err(err) => format!("Could not save favorites: {err}\n"),
+        }
+        .to_repl_string()
+        .write(writer)
+    }
+}
+
+/// An abstraction over the line editor, so that the REPL loop can be driven without a terminal.
+trait LineEditor {
+    /// Reads a line of input, showing the prompt.
+    fn readline(&mut self, prompt: &str) -> rustyline::Result<String>;
+
+    /// Adds a line to the history.
+    fn add_history_entry(&mut self, line: &str) -> rustyline::Result<()>;
+}
+
+impl<H: Helper> LineEditor for Editor<H, DefaultHistory> {
+    fn readline(&mut self, prompt: &str) -> rustyline::Result<String> {
+        Editor::readline(self, prompt)
+    }
+
+    fn add_history_entry(&mut self, line: &str) -> rustyline::Result<()> {
+        Editor::add_history_entry(self, line).map(|_| ())
+    }
+}
+
+/// Reads and evaluates lines until the user exits, CTRL-C or CTRL-D is pressed, or the editor
+/// fails.
+fn run_loop<E, W>(
+    editor: &mut E,
+    session: &mut Session,
+    repo: &mut SoftwareJobs,
+    writer: &mut W,
+) -> Result<(), Box<dyn Error>>
+where
+    E: LineEditor,
+    W: Write,
+{
+    loop {
+        let readline = editor.readline(&session.config.prompt);
+        match readline {
+            Ok(line) => {
+                editor.add_history_entry(line.as_str())?;
+
+                if session.eval(repo, &line, writer)? == Flow::Exit {
                     break;
                 }
             }
+            Err(ReadlineError::Interrupted) => {
+                // CTRL-C
+                break;
+            }
+            Err(ReadlineError::Eof) => {
+                // CTRL-D
+                break;
+            }
+            Err(err) => {
+                format!("An error has occurred: {err}")
+                    .to_repl_string()
+                    .write(writer)?;
+                break;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Scrapes using the given scrape function, then writes all jobs to `path` in `format`. Returns a
+/// failure exit code if the file could not be written, or a partial-failure exit code if the file was
+/// written but one or more sites failed to scrape.
+fn scrape_only_with<W, F>(
+    scrape: F,
+    path: &Path,
+    format: Format,
+    min_jobs: Option<usize>,
+    writer: &mut W,
+) -> Result<ExitCode, Box<dyn Error>>
+where
+    W: Write,
+    F: FnOnce() -> (SoftwareJobs, Vec<(String, ScrapeError)>),
+{
+    let (repo, failures) = scrape();
+    for (url, err) in &failures {
+        format!("Failed to scrape \"{url}\": {err}\n")
+            .to_repl_string()
+            .write(writer)?;
+    }
+    for (url, err) in &repo.warnings {
+        format!("WARNING: \"{url}\": {err}\n")
+            .to_repl_string()
+            .write(writer)?;
+    }
+    if let Err(err) = write_jobs(&repo.all, path, format) {
+        format!("Could not write to \"{}\": {err}\n", path.display())
+            .to_repl_string()
+            .write(writer)?;
+        return Ok(ExitCode::FAILURE);
+    }
+    format!(
+        "{} items written to \"{}\".\n",
+        repo.all.len(),
+        path.display()
+    )
+    .to_repl_string()
+    .write(writer)?;
+
+    Ok(if warn_too_few_jobs(repo.all.len(), min_jobs, writer)? {
+        ExitCode::from(TOO_FEW_JOBS)
+    } else if failures.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::from(PARTIAL_FAILURE)
+    })
+}
+
+/// Writes each site's jobs of interest to `path` as NDJSON (one JSON job per line) as soon as the
+/// site's result is received, reporting any failures to the writer. Duplicates across sites are not
+/// merged as jobs are written before every site is done. If `--compact-log-over` is set the jobs are
+/// appended to the file, which is then compacted if it has grown past the threshold. Returns the
+/// same exit codes as `scrape_only_with`.
+fn stream_with<W, I>(
+    results: I,
+    config: &Config,
+    path: &Path,
+    writer: &mut W,
+) -> Result<ExitCode, Box<dyn Error>>
+where
+    W: Write,
+    I: IntoIterator<Item = SiteResult>,
+{
+    let file = match config.compact_log_over {
+        Some(_) => OpenOptions::new().create(true).append(true).open(path),
+        None => File::create(path),
+    };
+    let mut file = match file {
+        Ok(file) => BufWriter::new(file),
+        Err(err) => {
+            format!("Could not write to \"{}\": {err}\n", path.display())
+                .to_repl_string()
+                .write(writer)?;
+            return Ok(ExitCode::FAILURE);
+        }
+    };
+    let today = Local::now().date_naive();
+    let (mut written, mut failed) = (0, false);
+    for (url, result) in results {
+        let result = result.and_then(|jobs| {
+            if config.strict {
+                check_site_jobs(&jobs)?;
+            }
+            Ok(jobs)
+        });
+        match result {
+            Ok(jobs) => {
+                if jobs.is_empty() {
+                    format!("WARNING: \"{url}\": {}\n", ScrapeError::EmptyResult)
+                        .to_repl_string()
+                        .write(writer)?;
+                }
+                let jobs = jobs.into_iter().filter(|job| {
+                    job.is_of_interest_with(&config.site_filters, &config.title_keywords)
+                });
+                for mut job in jobs {
+                    job.derive_fields(today);
+                    writeln!(file, "{}", serde_json::to_string(&job)?)?;
+                    written += 1;
+                }
+                file.flush()?;
+            }
+            Err(err) => {
+                failed = true;
+                format!("Failed to scrape \"{url}\": {err}\n")
+                    .to_repl_string()
+                    .write(writer)?;
+            }
+        }
+    }
+    format!("{} items written to \"{}\".\n", written, path.display())
+        .to_repl_string()
+        .write(writer)?;
+    if let Some(max) = config.compact_log_over {
+        drop(file);
+        let message = match compact_json_lines_over(path, max) {
+            Ok(Some((before, after))) => Some(format!(
+                "Compacted \"{}\" from {before} to {after} records.\n",
+                path.display()
+            )),
+            Ok(None) => None,
+            Err(err) => Some(format!("Could not compact \"{}\": {err}\n", path.display())),
+        };
+        if let Some(message) = message {
+            message.to_repl_string().write(writer)?;
+        }
+    }
+
+    Ok(if warn_too_few_jobs(written, config.min_jobs, writer)? {
+        ExitCode::from(TOO_FEW_JOBS)
+    } else if failed {
+        ExitCode::from(PARTIAL_FAILURE)
+    } else {
+        ExitCode::SUCCESS
+    })
+}
+
+/// Creates the repository from the jobs cached by the last session if caching is enabled and they
+/// are fresh, telling the user how old they are, otherwise by scraping every site.
+fn load_or_scrape<W>(config: &Config, writer: &mut W) -> std::io::Result<SoftwareJobs>
+where
+    W: Write,
+{
+    if !config.cache {
+        return Ok(SoftwareJobs::init_repo(config));
+    }
+    let max_age = chrono::Duration::minutes(config.cache_max_age_mins);
+    match cache::load(config.output_path(CACHE_FILE), max_age, Local::now()) {
+        Ok(Some((jobs, scraped_at))) => {
+            format!(
+                "Using jobs cached at {}; enter \"refresh\" to scrape again.\n",
+                scraped_at.format("%d-%m-%Y %H:%M:%S")
+            )
+            .to_repl_string()
+            .write(writer)?;
+            Ok(SoftwareJobs::from_cache(jobs, scraped_at, config))
         }
+        Ok(None) => Ok(SoftwareJobs::init_repo(config)),
+        Err(err) => {
+            format!("Could not read cached jobs, scraping instead: {err}\n")
+                .to_repl_string()
+                .write(writer)?;
+            Ok(SoftwareJobs::init_repo(config))
+        }
+    }
+}
 
+/// Caches the repository's jobs as scraped, before filtering and merging, for the next session, if
+/// caching is enabled.
+fn save_cache<W>(repo: &SoftwareJobs, config: &Config, writer: &mut W) -> std::io::Result<()>
+where
+    W: Write,
+{
+    match (config.cache, repo.scraped_at) {
+        (true, Some(scraped_at)) => {
+            if let Err(err) = cache::save(config.output_path(CACHE_FILE), &repo.scraped, scraped_at)
+            {
+                format!("Could not cache jobs: {err}\n")
+                    .to_repl_string()
+                    .write(writer)?;
+            }
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// This trait must be implemented by the specific job repo struct to be used in Job Hunt (e.g. SoftwareJobs).
+pub trait Repl {
+    /// Initializes a repository for the job repo type that is implementing this trait; then
+    /// initializes the REPL and parses queries. Returns an exit code, which is a failure if too few
+    /// jobs were found in strict mode and the REPL was not started.
+    fn init_repl<W>(writer: &mut W, config: &Config) -> Result<ExitCode, Box<dyn Error>>
+    where
+        W: Write;
+
+    /// Initializes a repository for the job repo type that is implementing this trait, writes its
+    /// jobs to `path` and returns an exit code, without starting the REPL.
+    fn scrape_only<W>(
+        writer: &mut W,
+        config: &Config,
+        path: &Path,
+    ) -> Result<ExitCode, Box<dyn Error>>
+    where
+        W: Write;
+
+    /// Initializes a repository for the job repo type that is implementing this trait; then runs
+    /// the terminal UI over its jobs.
+    #[cfg(feature = "tui")]
+    fn init_tui<W>(writer: &mut W, config: &Config) -> Result<(), Box<dyn Error>>
+    where
+        W: Write;
+}
+
+impl Repl for SoftwareJobs {
+    fn init_repl<W>(writer: &mut W, config: &Config) -> Result<ExitCode, Box<dyn Error>>
+    where
+        W: Write,
+    {
+        "Populating/indexing local datastore...\n"
+            .to_repl_string()
+            .write(writer)?;
+        let mut repo = load_or_scrape(config, writer)?;
+        match &config.banner {
+            Some(banner) => format!("Population/indexing completed successfully! {banner}\n"),
+            None => "Population/indexing completed successfully!\n".to_string(),
+        }
+        .to_repl_string()
+        .write(writer)?;
+        if config.banner.is_some() {
+            format!("Enter one of the following commands:\n{}", help())
+                .to_repl_string()
+                .write(writer)?;
+        }
+        // interactively, too few jobs is only a warning, unless in strict mode
+        if warn_too_few_jobs(repo.all.len(), config.min_jobs, writer)? && config.strict {
+            return Ok(ExitCode::from(TOO_FEW_JOBS));
+        }
+
+        let mut session = Session::new(config);
+        session.save_favorites_on_signal(on_signal());
+        session.save_cache_on_signal(on_signal());
+        session.snapshot_scraped(&repo);
+        let mut rl = Editor::with_config(
+            rustyline::Config::builder()
+                .completion_type(CompletionType::List)
+                .build(),
+        )?;
+        rl.set_helper(Some(CommandHelper));
+        rl.load_history(&config.output_path(HISTORY_FILE)).ok();
+
+        run_loop(&mut rl, &mut session, &mut repo, writer)?;
+
+        session.finish(writer)?;
+        save_cache(&repo, config, writer)?;
         "\nThank you for using Job Hunt. Goodbye!\n"
             .to_repl_string()
             .write(writer)?;
-        rl.save_history(".jobhunthistory")?;
+        rl.save_history(&config.output_path(HISTORY_FILE))?;
 
-        Ok(())
+        Ok(ExitCode::SUCCESS)
+    }
+
+    fn scrape_only<W>(
+        writer: &mut W,
+        config: &Config,
+        path: &Path,
+    ) -> Result<ExitCode, Box<dyn Error>>
+    where
+        W: Write,
+    {
+        if config.stream {
+            return stream_with(scrape_streaming(config), config, path, writer);
+        }
+        scrape_only_with(
+            || Self::try_init_repo(config),
+            path,
+            config.output_format,
+            config.min_jobs,
+            writer,
+        )
+    }
+
+    #[cfg(feature = "tui")]
+    fn init_tui<W>(writer: &mut W, config: &Config) -> Result<(), Box<dyn Error>>
+    where
+        W: Write,
+    {
+        "Populating/indexing local datastore...\n"
+            .to_repl_string()
+            .write(writer)?;
+        let mut repo = Self::init_repo(config);
+        crate::tui::run(repo.fetch_jobs(config))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::fs;
+    use std::path::PathBuf;
+    use std::process::ExitCode;
+    use std::rc::Rc;
+
+    use chrono::Local;
+    use rustyline::error::ReadlineError;
+
+    use crate::cache::{self, CACHE_FILE};
+    use crate::config::Config;
+    use crate::export::Format;
+    use crate::repository::{Builder, Job, SoftwareJobs, SoftwareJobsBuilder};
+    use crate::scraper::Error;
+    use crate::shutdown::Shutdown;
+    use crate::site::{REMOTE_OK_URL, WEB3_CAREERS_URL};
+    use crate::status::Status;
+
+    use super::{
+        complete_command, parse_redirect, run_loop, scrape_only_with, stream_with, strip_ansi,
+        Flow, LineEditor, Repl, Session, CLEAR_SCREEN, COMMANDS, OPEN_ALL_CONFIRM_THRESHOLD,
+        OPEN_ALL_MAX, PARTIAL_FAILURE, TOO_FEW_JOBS,
+    };
+
+    /// Creates a test repo with one job per given title.
+    fn repo(titles: &[&str]) -> SoftwareJobs {
+        SoftwareJobsBuilder::new()
+            .import(vec![titles
+                .iter()
+                .map(|title| Job {
+                    title: title.to_string(),
+                    company: "Company_1".into(),
+                    date_posted: "2022-07-27".into(),
+                    location: "Remote".into(),
+                    remuneration: "".into(),
+                    tags: vec![],
+                    apply: "https://site1.com".into(),
+                    site: WEB3_CAREERS_URL,
+                    ..Default::default()
+                })
+                .collect()])
+            .index()
+    }
+
+    /// Returns the default config with generated files written to the temp dir rather than the
+    /// user's data directory.
+    fn test_config() -> Config {
+        let config = Config {
+            output_dir: std::env::temp_dir().join(format!("jobhunt-{}-output", std::process::id())),
+            ..Default::default()
+        };
+        config.create_output_dir().unwrap();
+        config
+    }
+
+    /// Returns a unique path in the temp dir for a test artifact.
+    fn temp_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("jobhunt-{}-{}", std::process::id(), name));
+        fs::remove_file(&path).ok();
+        path
+    }
+
+    #[test]
+    fn test_scrape_only_writes_file() {
+        let path = temp_path("scrape-only.json");
+        let mut out = vec![];
+
+        let code = scrape_only_with(
+            || (repo(&["Backend Engineer"]), vec![]),
+            &path,
+            Format::Json,
+            None,
+            &mut out,
+        )
+        .unwrap();
+
+        assert_eq!(code, ExitCode::SUCCESS);
+        assert!(fs::read_to_string(&path)
+            .unwrap()
+            .contains("Backend Engineer"));
+        assert!(String::from_utf8(out).unwrap().contains("1 items written"));
+        fs::remove_file(&path).unwrap();
+
+        let code = scrape_only_with(
+            || {
+                (
+                    repo(&[]),
+                    vec![("https://site1.com".into(), Error::Response(500))],
+                )
+            },
+            &path,
+            Format::Json,
+            None,
+            &mut vec![],
+        )
+        .unwrap();
+        assert_eq!(code, ExitCode::from(PARTIAL_FAILURE));
+        assert!(path.exists());
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_scrape_only_min_jobs() {
+        let path = temp_path("min-jobs.json");
+        let scrape = || (repo(&["Backend Engineer", "Frontend Engineer"]), vec![]);
+
+        let mut out = vec![];
+        let code = scrape_only_with(scrape, &path, Format::Json, Some(3), &mut out).unwrap();
+        assert_eq!(code, ExitCode::from(TOO_FEW_JOBS));
+        assert!(String::from_utf8(out)
+            .unwrap()
+            .contains("only 2 jobs were found, fewer than the minimum of 3"));
+
+        let mut out = vec![];
+        let code = scrape_only_with(scrape, &path, Format::Json, Some(2), &mut out).unwrap();
+        assert_eq!(code, ExitCode::SUCCESS);
+        assert!(!String::from_utf8(out).unwrap().contains("WARNING"));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_help_lists_commands() {
+        let mut repo = repo(&[]);
+        let mut session = Session::new(&test_config());
+        let mut out = vec![];
+        session.eval(&mut repo, "help", &mut out).unwrap();
+
+        let out = String::from_utf8(out).unwrap();
+        for (usage, description) in COMMANDS {
+            assert!(out.contains(usage) && out.contains(description), "{usage}");
+        }
+        for command in ["fetch jobs", "refresh", "exit"] {
+            assert!(
+                out.lines().any(|line| line.starts_with(command)),
+                "{command}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut repo = repo(&["Backend Engineer"]);
+        let mut session = Session::new(&test_config());
+        let mut out = vec![];
+        let flow = session.eval(&mut repo, "clear", &mut out).unwrap();
+        assert!(matches!(flow, Flow::Continue));
+        assert_eq!(String::from_utf8(out).unwrap(), CLEAR_SCREEN);
+    }
+
+    #[test]
+    fn test_favorites_saved_on_exit() {
+        let path = temp_path("favorites.json");
+        let config = Config {
+            favorites_file: Some(path.clone()),
+            ..test_config()
+        };
+        let mut repo = repo(&["Backend Engineer", "Frontend Engineer"]);
+        let mut session = Session::new(&config);
+        let mut out = vec![];
+
+        session.eval(&mut repo, "fetch jobs", &mut out).unwrap();
+        session.eval(&mut repo, "favorite 2", &mut out).unwrap();
+        session.finish(&mut out).unwrap();
+
+        let contents = fs::read_to_string(&path).unwrap();
+        assert!(contents.contains("Frontend Engineer"));
+        assert!(!contents.contains("Backend Engineer"));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_favorites_saved_on_signal() {
+        let path = temp_path("signal-favorites.json");
+        let config = Config {
+            favorites_file: Some(path.clone()),
+            ..test_config()
+        };
+        let mut repo = repo(&["Backend Engineer", "Frontend Engineer"]);
+        let mut session = Session::new(&config);
+        let shutdown = Shutdown::default();
+        session.save_favorites_on_signal(&shutdown);
+        let mut out = vec![];
+
+        session.eval(&mut repo, "fetch jobs", &mut out).unwrap();
+        session.eval(&mut repo, "favorite 1", &mut out).unwrap();
+        assert!(shutdown.run().is_empty());
+
+        let contents = fs::read_to_string(&path).unwrap();
+        assert!(contents.contains("Backend Engineer"));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_cache_saved_on_signal() {
+        let config = Config {
+            output_dir: temp_path("signal-cache"),
+            ..test_config()
+        };
+        config.create_output_dir().unwrap();
+        let mut repo = repo(&["Backend Engineer"]);
+        repo.scraped_at = Some(Local::now());
+        let session = Session::new(&config);
+        let shutdown = Shutdown::default();
+        session.save_cache_on_signal(&shutdown);
+        session.snapshot_scraped(&repo);
+        assert!(shutdown.run().is_empty());
+
+        let (jobs, _) = cache::load(
+            config.output_path(CACHE_FILE),
+            chrono::Duration::hours(1),
+            Local::now(),
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(jobs.len(), repo.scraped.len());
+        assert_eq!(jobs[0].title, "Backend Engineer");
+        fs::remove_dir_all(&config.output_dir).unwrap();
+    }
+
+    #[test]
+    fn test_strict_too_few_jobs_exit_code() {
+        let config = Config {
+            output_dir: temp_path("strict-repl"),
+            strict: true,
+            min_jobs: Some(2),
+            banner: None,
+            ..test_config()
+        };
+        config.create_output_dir().unwrap();
+        let repo = repo(&["Backend Engineer"]);
+        cache::save(config.output_path(CACHE_FILE), &repo.scraped, Local::now()).unwrap();
+        let mut out = vec![];
+
+        let code = SoftwareJobs::init_repl(&mut out, &config).unwrap();
+
+        assert_eq!(code, ExitCode::from(TOO_FEW_JOBS));
+        assert!(String::from_utf8(out)
+            .unwrap()
+            .contains("only 1 jobs were found"));
+        fs::remove_dir_all(&config.output_dir).unwrap();
+    }
+
+    #[test]
+    fn test_artifacts_written_under_output_dir() {
+        let dir = temp_path("output-dir").join("nested");
+        let config =
+            Config::from_env_and_args(["--output-dir".to_string(), dir.display().to_string()])
+                .unwrap();
+        let config = Config {
+            favorites_file: Some("favorites.json".into()),
+            ..config
+        };
+        config.create_output_dir().unwrap();
+        let mut repo = repo(&["Backend Engineer"]);
+        let mut session = Session::new(&config);
+        let mut out = vec![];
+
+        for line in ["fetch jobs", "favorite 1", "applied 1"] {
+            session.eval(&mut repo, line, &mut out).unwrap();
+        }
+        session.finish(&mut out).unwrap();
+
+        for name in ["favorites.json", "status.json"] {
+            assert!(dir.join(name).exists(), "{name}");
+        }
+        assert!(config.to_string().contains(&format!(
+            "status_file = {}\n",
+            dir.join("status.json").display()
+        )));
+        fs::remove_dir_all(dir.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_favorites_not_saved_when_empty() {
+        let path = temp_path("no-favorites.json");
+        let config = Config {
+            favorites_file: Some(path.clone()),
+            ..test_config()
+        };
+        let mut repo = repo(&["Backend Engineer"]);
+        let mut session = Session::new(&config);
+        let mut out = vec![];
+
+        session.eval(&mut repo, "fetch jobs", &mut out).unwrap();
+        session.finish(&mut out).unwrap();
+
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_commands_on_empty_repo() {
+        let (export, export_new) = (temp_path("empty.json"), temp_path("empty-new.json"));
+        let mut repo = repo(&[]);
+        let mut session = Session::new(&test_config());
+        let mut out = vec![];
+
+        for line in [
+            "fetch jobs".to_string(),
+            "fetch jobs closing-soon".into(),
+            "fetch jobs sort confidence".into(),
+            "fetch jobs sort date asc".into(),
+            "search rust engineer".into(),
+            "missing salary".into(),
+            "fetch jobs shuffle".into(),
+            "fetch jobs shuffle 42".into(),
+            "recommend".into(),
+            "count".into(),
+            "filter tag solidity".into(),
+            "filter has-salary".into(),
+            "filter remote-only".into(),
+            "filter date since 2024-01-01".into(),
+            "filter date since 01/01/2024".into(),
+            "filter date between 2024-01-01 and 2023-01-01".into(),
+            "dedup on".into(),
+            "dedup off".into(),
+            "fetch jobs page 1".into(),
+            "refresh site nowhere".into(),
+            "keywords".into(),
+            "search \"smart contract\"".into(),
+            "search \"rust\"".into(),
+            "recommend 3".into(),
+            "fetch jobs benefit \"4-day week\"".into(),
+            "fetch jobs benefit free lunch".into(),
+            "filter salary above 120000 --include-unknown".into(),
+            "export csv jobs.csv --fields title,pay".into(),
+            "fetch stack rust+tokio".into(),
+            "filter location remote".into(),
+            "fetch skill backend,devops".into(),
+            "fetch backend rust".into(),
+            format!("export json {}", export.display()),
+            format!("export json {}", export.join("unwritable.json").display()),
+            format!("export-new {} {}", export.display(), export_new.display()),
+            "compare Company_1 vs Company_2".into(),
+            "compare-skill backend vs frontend".into(),
+            "companies".into(),
+            "companies recent".into(),
+            "stats locations".into(),
+            "duplicates".into(),
+            "timeline".into(),
+            "similar 1".into(),
+            "explain 1".into(),
+            "open 1".into(),
+            "open all".into(),
+            "favorite 1".into(),
+            "favorite 0".into(),
+            "favorites".into(),
+            "timings".into(),
+            "".into(),
+        ] {
+            assert_eq!(
+                session.eval(&mut repo, &line, &mut out).unwrap(),
+                Flow::Continue,
+                "{line}"
+            );
+        }
+
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("0 items returned."));
+        assert!(out.contains("Try \"refresh\""));
+        assert!(out.contains("There is no job numbered 1"));
+        assert!(out.contains("0 new items exported"));
+        assert!(out.contains(&format!(
+            "Could not export to \"{}\"",
+            export.join("unwritable.json").display()
+        )));
+        fs::remove_file(&export).unwrap();
+        fs::remove_file(&export_new).unwrap();
+    }
+
+    #[test]
+    fn test_status_filter_across_reload() {
+        let path = temp_path("status.json");
+        let config = Config {
+            status_file: Some(path.clone()),
+            ..test_config()
+        };
+        let mut repo = repo(&["Backend Engineer", "Frontend Engineer", "DevOps Engineer"]);
+        let mut session = Session::new(&config);
+        let mut out = vec![];
+
+        session.eval(&mut repo, "fetch jobs", &mut out).unwrap();
+        session.eval(&mut repo, "applied 1", &mut out).unwrap();
+        session
+            .eval(&mut repo, "status 3 interviewing", &mut out)
+            .unwrap();
+
+        let mut session = Session::new(&config);
+        let mut out = vec![];
+        session
+            .eval(&mut repo, "fetch jobs status applied", &mut out)
+            .unwrap();
+        assert_eq!(session.last.len(), 1);
+        assert_eq!(session.last[0].title, "Backend Engineer");
+        assert!(String::from_utf8(out).unwrap().contains("[applied "));
+
+        session
+            .eval(&mut repo, "fetch jobs status interviewing", &mut vec![])
+            .unwrap();
+        assert_eq!(session.last[0].title, "DevOps Engineer");
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_stream_writes_each_site_as_it_arrives() {
+        let path = temp_path("stream.ndjson");
+        let (tx, rx) = std::sync::mpsc::channel();
+        let site = |url: &'static str, titles: &[&str]| {
+            let jobs = titles
+                .iter()
+                .map(|title| Job {
+                    title: title.to_string(),
+                    site: url,
+                    ..Default::default()
+                })
+                .collect();
+            (url.to_string(), Ok(jobs))
+        };
+        tx.send(site(REMOTE_OK_URL, &["Backend Engineer", "Marketing Lead"]))
+            .unwrap();
+        tx.send(("https://site3.com".into(), Err(Error::Response(500))))
+            .unwrap();
+        tx.send(site(WEB3_CAREERS_URL, &["Frontend Engineer"]))
+            .unwrap();
+        drop(tx);
+        let mut out = vec![];
+
+        let code = stream_with(rx, &test_config(), &path, &mut out).unwrap();
+
+        assert_eq!(code, ExitCode::from(PARTIAL_FAILURE));
+        let jobs = fs::read_to_string(&path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str::<Job>(line).unwrap())
+            .map(|job| (job.site, job.title))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            jobs,
+            vec![
+                (REMOTE_OK_URL, "Backend Engineer".to_string()),
+                (WEB3_CAREERS_URL, "Frontend Engineer".to_string()),
+            ]
+        );
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("Failed to scrape \"https://site3.com\""));
+        assert!(out.contains("2 items written"));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_stream_compacts_log() {
+        let path = temp_path("stream-log.ndjson");
+        let config = Config {
+            compact_log_over: Some(2),
+            ..test_config()
+        };
+        let results = |titles: &[&str]| {
+            let jobs = titles
+                .iter()
+                .map(|title| Job {
+                    title: title.to_string(),
+                    company: "Company_1".into(),
+                    site: REMOTE_OK_URL,
+                    ..Default::default()
+                })
+                .collect();
+            vec![(REMOTE_OK_URL.to_string(), Ok(jobs))]
+        };
+        let records = || fs::read_to_string(&path).unwrap().lines().count();
+        let mut out = vec![];
+
+        stream_with(results(&["Backend Engineer"]), &config, &path, &mut out).unwrap();
+        stream_with(results(&["Backend Engineer"]), &config, &path, &mut out).unwrap();
+        assert_eq!(records(), 2);
+        stream_with(
+            results(&["Backend Engineer", "Frontend Engineer"]),
+            &config,
+            &path,
+            &mut out,
+        )
+        .unwrap();
+
+        assert_eq!(records(), 2);
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("from 4 to 2 records"));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_filter_skill() {
+        let mut repo = repo(&["Backend Engineer", "Frontend Engineer", "Backend Developer"]);
+        let mut session = Session::new(&test_config());
+        let mut out = vec![];
+
+        session
+            .eval(&mut repo, "filter skill BACKEND", &mut out)
+            .unwrap();
+        assert_eq!(session.last.len(), 2);
+        assert!(String::from_utf8(out)
+            .unwrap()
+            .contains("2 items returned."));
+
+        let mut out = vec![];
+        session
+            .eval(&mut repo, "filter skill cobol", &mut out)
+            .unwrap();
+        assert!(String::from_utf8(out)
+            .unwrap()
+            .contains("Does not compute!"));
+    }
+
+    #[test]
+    fn test_filter_level() {
+        let mut repo = repo(&[
+            "Senior Backend Engineer",
+            "Snr Engineer",
+            "Staff Engineer",
+            "Principal Engineer",
+        ]);
+        let mut session = Session::new(&test_config());
+        let mut out = vec![];
+
+        for query in ["filter level senior", "filter level SR", "filter level snr"] {
+            session.eval(&mut repo, query, &mut out).unwrap();
+            assert_eq!(session.last.len(), 2, "{query}");
+        }
+        for query in ["filter level principal", "filter level principle"] {
+            session.eval(&mut repo, query, &mut out).unwrap();
+            assert_eq!(session.last.len(), 1, "{query}");
+            assert_eq!(session.last[0].title, "Principal Engineer");
+        }
+        session
+            .eval(&mut repo, "filter level wizard", &mut out)
+            .unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("2 items returned."));
+        assert!(out.contains("Does not compute!"));
+    }
+
+    #[test]
+    fn test_filter_company() {
+        let mut repo = SoftwareJobsBuilder::new()
+            .import(vec![vec![
+                Job {
+                    title: "Backend Engineer".into(),
+                    company: "Acme Corp".into(),
+                    ..Default::default()
+                },
+                Job {
+                    title: "Frontend Engineer".into(),
+                    company: "Other".into(),
+                    ..Default::default()
+                },
+            ]])
+            .index();
+        let mut session = Session::new(&test_config());
+        let mut out = vec![];
+
+        session
+            .eval(&mut repo, "filter company \"acme corp\"", &mut out)
+            .unwrap();
+        assert_eq!(session.last.len(), 1);
+        assert_eq!(session.last[0].title, "Backend Engineer");
+        session
+            .eval(&mut repo, "filter company Initech", &mut out)
+            .unwrap();
+        assert!(String::from_utf8(out)
+            .unwrap()
+            .contains("No jobs found for company \"Initech\"."));
+    }
+
+    #[test]
+    fn test_apply_workflow() {
+        let config = Config {
+            status_file: None,
+            ..test_config()
+        };
+        let mut session = Session::new(&config);
+        let (opened, copied) = (Rc::new(RefCell::new(vec![])), Rc::new(RefCell::new(vec![])));
+        let (open_recorder, copy_recorder) = (opened.clone(), copied.clone());
+        session.opener = Box::new(move |url| {
+            open_recorder.borrow_mut().push(url.to_string());
+            Ok(())
+        });
+        session.copier = Box::new(move |url| {
+            copy_recorder.borrow_mut().push(url.to_string());
+            Err("no clipboard".into())
+        });
+        let mut repo = SoftwareJobsBuilder::new()
+            .import(vec![vec![
+                Job {
+                    title: "Backend Engineer".into(),
+                    apply: "https://site1.com/apply/1".into(),
+                    ..Default::default()
+                },
+                Job {
+                    title: "Frontend Engineer".into(),
+                    ..Default::default()
+                },
+            ]])
+            .index();
+        let mut out = vec![];
+        session.eval(&mut repo, "fetch jobs", &mut out).unwrap();
+        let status = |session: &Session, n: usize| {
+            session
+                .statuses
+                .get(&session.last[n].id())
+                .map(|entry| entry.status)
+        };
+
+        let message = session.apply("1");
+        assert_eq!(*opened.borrow(), vec!["https://site1.com/apply/1"]);
+        assert_eq!(*copied.borrow(), vec!["https://site1.com/apply/1"]);
+        assert!(message.contains("Could not copy it to the clipboard: no clipboard."));
+        assert_eq!(status(&session, 0), Some(Status::Applied));
+
+        // with no link, nothing is opened but the job is still marked as applied
+        assert!(session.apply("2").contains("has no apply link"));
+        assert_eq!(opened.borrow().len(), 1);
+        assert_eq!(status(&session, 1), Some(Status::Applied));
+        assert!(session.apply("3").contains("There is no job numbered 3"));
+    }
+
+    #[test]
+    fn test_complete_command() {
+        assert_eq!(complete_command(&[], "re"), vec!["recommend", "refresh"]);
+        assert_eq!(complete_command(&["fetch"], "j"), vec!["jobs"]);
+        assert_eq!(
+            complete_command(&["fetch", "jobs"], "s"),
+            vec!["shuffle", "sort", "status"]
+        );
+        assert_eq!(complete_command(&["filter"], "sk"), vec!["skill"]);
+        assert_eq!(complete_command(&["export"], "js"), vec!["json", "jsonl"]);
+        assert_eq!(complete_command(&["dedup"], ""), vec!["off", "on"]);
+        // placeholders other than export formats aren't completed
+        assert!(complete_command(&["filter", "skill"], "").is_empty());
+        assert!(complete_command(&["unknown"], "").is_empty());
+    }
+
+    #[test]
+    fn test_fetch_jobs_page() {
+        let titles = (1..=45)
+            .map(|i| format!("Engineer {i}"))
+            .collect::<Vec<_>>();
+        let mut repo = repo(&titles.iter().map(String::as_str).collect::<Vec<_>>());
+        let mut session = Session::new(&test_config());
+        let mut out = vec![];
+
+        session
+            .eval(&mut repo, "fetch jobs page 3", &mut out)
+            .unwrap();
+        let output = String::from_utf8(out.clone()).unwrap();
+        assert!(output.contains("[41]"));
+        assert!(!output.contains("[40]"));
+        assert!(output.contains("Showing 41\u{2013}45 of 45."));
+        assert_eq!(session.last.len(), 45);
+
+        out.clear();
+        session
+            .eval(&mut repo, "fetch jobs page 4", &mut out)
+            .unwrap();
+        assert!(String::from_utf8(out)
+            .unwrap()
+            .contains("There is no page 4; there are 3 pages of 20 jobs."));
+    }
+
+    #[test]
+    fn test_refresh_unknown_site() {
+        let mut repo = repo(&["Backend Engineer"]);
+        let mut session = Session::new(&test_config());
+        let mut out = vec![];
+
+        session
+            .eval(&mut repo, "refresh site nowhere", &mut out)
+            .unwrap();
+        assert!(String::from_utf8(out).unwrap().contains(
+            "Unknown site \"nowhere\". The sites are: web3careers, useweb3, cryptojobslist,"
+        ));
+        assert_eq!(repo.all.len(), 1);
+    }
+
+    #[test]
+    fn test_redirect_output() {
+        assert_eq!(
+            parse_redirect("fetch jobs > results.txt"),
+            Some(("fetch jobs", "results.txt"))
+        );
+        assert_eq!(parse_redirect("search \"a > b\""), None);
+        assert_eq!(parse_redirect("fetch jobs >"), None);
+        assert_eq!(strip_ansi("\x1b[1;32mJobs\x1b[0m"), "Jobs");
+
+        let path = temp_path("redirect.txt");
+        let mut repo = repo(&["Backend Engineer"]);
+        let mut session = Session::new(&test_config());
+        let mut out = vec![];
+        session
+            .eval(
+                &mut repo,
+                &format!("fetch jobs > {}", path.display()),
+                &mut out,
+            )
+            .unwrap();
+        let output = String::from_utf8(out).unwrap();
+        assert!(output.contains("Output written to"));
+        assert!(!output.contains("Backend Engineer"));
+        let written = fs::read_to_string(&path).unwrap();
+        assert!(written.contains("Backend Engineer"));
+        assert!(!written.contains('\x1b'));
+        assert_eq!(session.last.len(), 1);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_keywords() {
+        let mut session = Session::new(&test_config());
+        session.refresher = Box::new(|config| {
+            let mut titles = vec![];
+            if config.title_keywords.is_empty() {
+                titles.push("Marketing Lead");
+            }
+            repo(&titles)
+        });
+        let mut repo = repo(&["Backend Engineer"]);
+        let mut out = vec![];
+
+        session.eval(&mut repo, "keywords", &mut out).unwrap();
+        assert!(String::from_utf8(out.clone())
+            .unwrap()
+            .contains("contains: developer, engineer, engineering, technical."));
+
+        session
+            .eval(&mut repo, "keywords Manager, lead", &mut out)
+            .unwrap();
+        assert_eq!(session.config.title_keywords, vec!["manager", "lead"]);
+        assert!(repo.all.is_empty());
+
+        session.eval(&mut repo, "keywords *", &mut out).unwrap();
+        assert!(session.config.title_keywords.is_empty());
+        assert_eq!(repo.all[0].title, "Marketing Lead");
+    }
+
+    #[test]
+    fn test_pinned_query_rerun_after_refresh() {
+        let mut session = Session::new(&test_config());
+        session.refresher = Box::new(|_| repo(&["Backend Engineer", "Frontend Engineer"]));
+        let mut repo = repo(&["Backend Engineer"]);
+        let mut out = vec![];
+
+        session.eval(&mut repo, "pin refresh", &mut out).unwrap();
+        assert!(session.pinned.is_none());
+        session.eval(&mut repo, "pin fetch jobs", &mut out).unwrap();
+        session.eval(&mut repo, "status", &mut out).unwrap();
+        assert!(String::from_utf8(out.clone())
+            .unwrap()
+            .contains("Pinned query: fetch jobs"));
+
+        session.eval(&mut repo, "refresh", &mut out).unwrap();
+        assert_eq!(session.last.len(), 2);
+        assert_eq!(session.last[1].title, "Frontend Engineer");
+
+        session.eval(&mut repo, "unpin", &mut out).unwrap();
+        session.last.clear();
+        session.eval(&mut repo, "refresh", &mut out).unwrap();
+        assert!(session.last.is_empty());
+    }
+
+    #[test]
+    fn test_open_all_confirmation() {
+        let opened = Rc::new(RefCell::new(vec![]));
+        let mut session = Session::new(&test_config());
+        let recorder = opened.clone();
+        session.opener = Box::new(move |url| {
+            recorder.borrow_mut().push(url.to_string());
+            Ok(())
+        });
+        let titles = (0..60)
+            .map(|i| format!("Engineer {i}"))
+            .collect::<Vec<String>>();
+        let titles = titles.iter().map(String::as_str).collect::<Vec<&str>>();
+        let mut out = vec![];
+
+        // at the threshold, links are opened without confirmation
+        let mut few = repo(&titles[..OPEN_ALL_CONFIRM_THRESHOLD]);
+        session.eval(&mut few, "fetch jobs", &mut out).unwrap();
+        session.eval(&mut few, "open all", &mut out).unwrap();
+        assert_eq!(opened.borrow().len(), OPEN_ALL_CONFIRM_THRESHOLD);
+
+        // over the threshold, nothing is opened unless confirmed
+        opened.borrow_mut().clear();
+        let mut many = repo(&titles);
+        session.eval(&mut many, "fetch jobs", &mut out).unwrap();
+        session.eval(&mut many, "open all", &mut out).unwrap();
+        session.eval(&mut many, "n", &mut out).unwrap();
+        assert!(opened.borrow().is_empty());
+
+        // and the cap applies after confirmation
+        session.eval(&mut many, "open all", &mut out).unwrap();
+        session.eval(&mut many, "y", &mut out).unwrap();
+        assert_eq!(opened.borrow().len(), OPEN_ALL_MAX);
+
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains(&format!("Open {OPEN_ALL_MAX} links in the browser? (y/n)")));
+        assert!(out.contains("Cancelled."));
+        assert!(out.contains(&format!("Opened {OPEN_ALL_MAX} of {OPEN_ALL_MAX} links.")));
+    }
+
+    /// A line editor that reads scripted lines, recording the prompts shown.
+    struct ScriptedEditor {
+        lines: Vec<&'static str>,
+        prompts: Vec<String>,
+    }
+
+    impl LineEditor for ScriptedEditor {
+        fn readline(&mut self, prompt: &str) -> rustyline::Result<String> {
+            self.prompts.push(prompt.to_string());
+            if self.lines.is_empty() {
+                Err(ReadlineError::Eof)
+            } else {
+                Ok(self.lines.remove(0).to_string())
+            }
+        }
+
+        fn add_history_entry(&mut self, _line: &str) -> rustyline::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_configured_prompt() {
+        let config = Config::from_env_and_args(["--prompt".to_string(), "jobs> ".into()]).unwrap();
+        let config = Config {
+            output_dir: test_config().output_dir,
+            ..config
+        };
+        assert!(Config::from_env_and_args(["--prompt".to_string(), " ".into()]).is_err());
+        let mut editor = ScriptedEditor {
+            lines: vec!["fetch jobs"],
+            prompts: vec![],
+        };
+        let mut out = vec![];
+
+        run_loop(
+            &mut editor,
+            &mut Session::new(&config),
+            &mut repo(&["Backend Engineer"]),
+            &mut out,
+        )
+        .unwrap();
+
+        assert_eq!(editor.prompts, vec!["jobs> ", "jobs> "]);
+        assert!(String::from_utf8(out)
+            .unwrap()
+            .contains("1 items returned."));
     }
 }
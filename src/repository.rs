@@ -1,32 +1,80 @@
 //! The repository module contains all datastore code.
 
-use std::collections::HashMap;
-use std::fmt::{Debug, Formatter};
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Debug, Display, Formatter};
 use std::hash::Hash;
 use std::rc::Rc;
+use std::str::FromStr;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, OnceLock};
 use std::thread;
+use std::time::{Duration, Instant};
 
-use colored::Colorize;
+use chrono::{DateTime, Datelike, Local, NaiveDate};
+use colored::{ColoredString, Colorize};
+use itertools::Itertools;
+use log::{error, info, warn};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use regex::Regex;
+use serde::{Deserialize, Deserializer, Serialize};
+#[cfg(feature = "async")]
+use tokio::sync::Semaphore;
 
-use crate::scraper::Scraper;
+use crate::cache::{self, CACHE_FILE};
+use crate::company::{fetch_company_meta, MetadataCache, COMPANY_META_FILE};
+use crate::config::Config;
+use crate::export::Field;
+use crate::scraper::{Error, ScrapeLimit, ScrapeOptions, SiteEntry, SITES};
 use crate::site::{
-    CryptoJobsList, NearJobs, Site, SolanaJobs, SubstrateJobs, UseWeb3, Web3Careers,
+    known_site_url, normalize_location, print_scrape_error, print_scrape_warning, site_name,
+    summarize_scrape_errors,
 };
 
 pub const THREAD_ERROR: &str = "Error in Scraper thread";
-const NOT_AVAILABLE: &str = "Not available";
+pub const NOT_AVAILABLE: &str = "Not available";
+const EXPIRED_NOTE: &str = "(likely expired)";
+pub const DATE_FORMAT: &str = "%Y-%m-%d";
 
 /// The Job struct is the repository primitive.
-#[derive(Clone, Eq, Hash, PartialEq)]
+#[derive(Clone, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(default)]
 pub struct Job {
     pub title: String,
     pub company: String,
     pub date_posted: String,
     pub location: String,
     pub remuneration: String,
+    /// The lowest salary in whole currency units, parsed from the remuneration, e.g. 90000 for
+    /// "$90k - $140k".
+    pub remuneration_min: Option<u32>,
+    /// The highest salary in whole currency units, parsed from the remuneration.
+    pub remuneration_max: Option<u32>,
     pub tags: Vec<String>,
     pub apply: String,
-    pub site: &'static str,
+    #[serde(deserialize_with = "deserialize_site")]
+    pub site: SiteUrl,
+    /// The other sites the job was posted on, if duplicates were merged into it.
+    pub also_on: Vec<String>,
+    /// The application deadline, if the posting states one.
+    pub apply_by: Option<NaiveDate>,
+    /// The region a remote job is restricted to, if the location states one, e.g. "US only".
+    pub remote_region: Option<String>,
+    /// The broad regions a remote job is restricted to, parsed from its location. Empty if the job
+    /// is unrestricted or not remote.
+    pub regions: Vec<Region>,
+    /// The location parsed into its parts.
+    pub location_info: LocationInfo,
+    /// Employment benefits mentioned by the posting, e.g. "unlimited PTO" (see [`BENEFITS`]).
+    pub benefits: Vec<String>,
+    /// The URL of the company's logo, if company metadata was fetched.
+    pub company_logo: Option<String>,
+    /// The company's website, if company metadata was fetched.
+    pub company_website: Option<String>,
 }
 
 /// Helper methods for Job instances. These can be customised to fit the relevant jobs type.
@@ -35,6 +83,10 @@ impl Job {
         self.title.to_lowercase().contains(pat)
     }
 
+    fn tags_contain(&self, pat: &str) -> bool {
+        self.tags.iter().any(|tag| tag.to_lowercase().contains(pat))
+    }
+
     fn title_contains_any(&self, v: Vec<&str>) -> bool {
         for pat in v {
             if self.title.to_lowercase().contains(pat) {
@@ -44,14 +96,239 @@ impl Job {
         false
     }
 
-    fn location_contains(&self, pat: &str) -> bool {
-        self.location.to_lowercase().contains(pat)
+    /// Returns true if the job is of interest to the repository - its title contains any of the
+    /// keywords, by default engineering terms. Every job is of interest if there are no keywords.
+    pub(crate) fn is_of_interest(&self, keywords: &[String]) -> bool {
+        keywords.is_empty()
+            || self.title_contains_any(keywords.iter().map(String::as_str).collect())
     }
-}
 
-/// Pretty print Job for debug.
-impl Debug for Job {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    /// Returns true if the job is of interest, using the first site filter whose site is part of the
+    /// job's site URL in place of `is_of_interest`. A site filter accepts jobs whose title contains
+    /// any of its keywords, or every job if it has none.
+    pub(crate) fn is_of_interest_with(
+        &self,
+        site_filters: &[SiteFilter],
+        keywords: &[String],
+    ) -> bool {
+        match site_filters
+            .iter()
+            .find(|(site, _)| self.site.contains(site.as_str()))
+        {
+            Some((_, keywords)) => {
+                keywords.is_empty()
+                    || self.title_contains_any(keywords.iter().map(String::as_str).collect())
+            }
+            None => self.is_of_interest(keywords),
+        }
+    }
+
+    /// Normalizes the location (see [`normalize_location`]) and fills in fields derived from the
+    /// scraped fields, e.g. the application deadline, unless the site already provided them.
+    pub(crate) fn derive_fields(&mut self, today: NaiveDate) {
+        self.location = normalize_location(&self.location);
+        if self.apply_by.is_none() {
+            let text = format!("{} {}", self.title, self.tags.join(" "));
+            self.apply_by = parse_apply_by(&text, today);
+        }
+        if self.remote_region.is_none() {
+            self.remote_region = remote_region(&self.location);
+        }
+        if self.regions.is_empty() && self.is_remote() {
+            self.regions = parse_regions(&self.location);
+        }
+        if self.location_info == LocationInfo::default() {
+            self.location_info = LocationInfo::parse(&self.location);
+        }
+        if self.benefits.is_empty() {
+            let texts = self.tags.iter().chain([&self.remuneration]);
+            self.benefits = parse_benefits(texts.map(String::as_str));
+        }
+        if self.remuneration_min.is_none() && self.remuneration_max.is_none() {
+            if let Some((min, max)) = parse_salary(&self.remuneration) {
+                self.remuneration_min = Some(min);
+                self.remuneration_max = Some(max);
+            }
+        }
+    }
+
+    /// Returns the skills a job requires, based on its title.
+    fn skills(&self) -> Vec<Skill> {
+        let mut skills = vec![];
+        if self.title_contains("backend") {
+            skills.push(Skill::Backend);
+        }
+        if self.title_contains("frontend") {
+            skills.push(Skill::Frontend);
+        }
+        if self.title_contains("fullstack") {
+            skills.push(Skill::Fullstack);
+        }
+        if self.title_contains_any(vec!["devops", "platform", "infra"]) {
+            skills.push(Skill::DevOps);
+        }
+        if self.title_contains_any(vec!["blockchain", "smart contract"]) {
+            skills.push(Skill::Blockchain);
+        }
+        skills
+    }
+
+    /// Returns the levels of a job, based on its title. A title can match more than one level, e.g.
+    /// "Senior Staff Engineer".
+    fn levels(&self) -> Vec<Level> {
+        let mut levels = vec![];
+        if self.title_contains("junior") {
+            levels.push(Level::Junior);
+        }
+        if self.title_contains("intermediate") {
+            levels.push(Level::Intermediate);
+        }
+        if self.title_contains_any(vec!["senior", "snr", "sr"]) {
+            levels.push(Level::Senior);
+        }
+        if self.title_contains("staff") {
+            levels.push(Level::Staff);
+        }
+        if self.title_contains("lead") {
+            levels.push(Level::Lead);
+        }
+        // "principle" is a common misspelling
+        if self.title_contains_any(vec!["principal", "principle"]) {
+            levels.push(Level::Principal);
+        }
+        if self.title_contains("manager") {
+            levels.push(Level::Manager);
+        }
+        levels
+    }
+
+    /// Returns the levels a job is indexed by under the given policy.
+    fn indexed_levels(&self, policy: LevelPolicy) -> Vec<Level> {
+        let levels = self.levels();
+        match policy {
+            LevelPolicy::All => levels,
+            LevelPolicy::Highest => levels.into_iter().max().into_iter().collect(),
+        }
+    }
+
+    fn is_remote(&self) -> bool {
+        is_remote(&self.location)
+    }
+
+    /// Returns true if the job is fully remote, rather than hybrid (see [`is_fully_remote`]).
+    pub fn is_fully_remote(&self) -> bool {
+        is_fully_remote(&self.location)
+    }
+
+    /// Returns the key used to detect the same job posted on more than one site - its normalized
+    /// title and company.
+    pub fn dedup_key(&self) -> String {
+        format!(
+            "{}|{}",
+            normalize_name(&self.title),
+            normalize_name(&self.company)
+        )
+    }
+
+    /// Returns an id for the job that is stable across runs, derived from its site, normalized
+    /// company and title, and date posted. This is an FNV-1a hash as the std hashers are not
+    /// guaranteed to be stable between Rust releases.
+    pub fn id(&self) -> String {
+        let key = format!(
+            "{}|{}|{}",
+            self.site,
+            self.dedup_key(),
+            self.date_posted.trim()
+        );
+        let hash = key.bytes().fold(0xcbf29ce484222325_u64, |hash, b| {
+            (hash ^ b as u64).wrapping_mul(0x100000001b3)
+        });
+        format!("{:016x}", hash)
+    }
+
+    /// Merges a duplicate of this job from another site into it. Tags are unioned (ignoring case),
+    /// any field this job is missing is taken from the duplicate, and the duplicate's sites are
+    /// recorded in `also_on`.
+    fn merge(&mut self, other: &Job) {
+        for site in std::iter::once(other.site).chain(other.also_on.iter().map(String::as_str)) {
+            if site != self.site && !self.also_on.iter().any(|s| s == site) {
+                self.also_on.push(site.to_string());
+            }
+        }
+        for tag in &other.tags {
+            if !self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                self.tags.push(tag.clone());
+            }
+        }
+        for (field, other_field) in [
+            (&mut self.location, &other.location),
+            (&mut self.remuneration, &other.remuneration),
+            (&mut self.apply, &other.apply),
+        ] {
+            if field.is_empty() {
+                field.clone_from(other_field);
+            }
+        }
+    }
+
+    /// Parses the date posted, which is stored in the format "%Y-%m-%d".
+    pub fn parsed_date(&self) -> Option<NaiveDate> {
+        NaiveDate::parse_from_str(&self.date_posted, DATE_FORMAT).ok()
+    }
+
+    /// Returns how complete the job's data is, from 0 to 100. Each of a salary, a valid date posted,
+    /// an apply link, tags and a location adds 20.
+    pub fn confidence(&self) -> u8 {
+        let filled = |field: &str| !field.trim().is_empty() && field != NOT_AVAILABLE;
+        let present = [
+            filled(&self.remuneration),
+            self.parsed_date().is_some(),
+            filled(&self.apply),
+            self.tags.iter().any(|tag| filled(tag)),
+            filled(&self.location),
+        ]
+        .into_iter()
+        .filter(|&present| present)
+        .count() as u8;
+        present * 20
+    }
+
+    /// Returns the job's salary range in whole currency units, as parsed from its remuneration by
+    /// `derive_fields`. A job with only one bound known is a range of one.
+    pub fn salary(&self) -> Option<(u32, u32)> {
+        match (self.remuneration_min, self.remuneration_max) {
+            (Some(min), Some(max)) => Some((min, max)),
+            (Some(amount), None) | (None, Some(amount)) => Some((amount, amount)),
+            (None, None) => None,
+        }
+    }
+
+    /// Returns whether the job pays at least the given amount, comparing its highest salary, or its
+    /// lowest if it only has one, or None if its salary is unknown.
+    pub fn pays_at_least(&self, amount: u32) -> Option<bool> {
+        self.remuneration_max
+            .or(self.remuneration_min)
+            .map(|salary| salary >= amount)
+    }
+
+    /// Returns the number of days since the job was posted, if the date posted can be parsed.
+    pub fn age_days(&self) -> Option<i64> {
+        self.parsed_date()
+            .map(|date| (Local::now().date_naive() - date).num_days())
+    }
+
+    /// A job posted more than `threshold_days` ago has likely been filled. Jobs with an unknown
+    /// date posted are never flagged.
+    pub fn is_likely_expired(&self, threshold_days: i64) -> bool {
+        self.age_days().is_some_and(|age| age > threshold_days)
+    }
+
+    /// Writes the pretty printed job, with an optional note shown after the date posted.
+    fn write_pretty(
+        &self,
+        f: &mut Formatter<'_>,
+        date_note: Option<ColoredString>,
+    ) -> std::fmt::Result {
         let remuneration = if self.remuneration.is_empty() {
             NOT_AVAILABLE
         } else {
@@ -67,30 +344,56 @@ impl Debug for Job {
         } else {
             NOT_AVAILABLE.into()
         };
-        let apply = if self.apply.is_empty() {
-            NOT_AVAILABLE.green()
+        let mut apply = if self.apply.is_empty() {
+            NOT_AVAILABLE.green().to_string()
+        } else {
+            self.apply.bright_blue().to_string()
+        };
+        if let Some(date) = self.apply_by {
+            apply = format!("{} {}", apply, format!("(apply by {})", date).green());
+        }
+        let site = if self.also_on.is_empty() {
+            self.site.bright_blue().to_string()
+        } else {
+            let names = self.also_on.iter().map(|site| site_name(site)).join(", ");
+            format!(
+                "{} {}",
+                self.site.bright_blue(),
+                format!("(also on {names})").green()
+            )
+        };
+        let benefits = if self.benefits.is_empty() {
+            "".to_string()
         } else {
-            self.apply.bright_blue()
+            format!(
+                "{} {}\n",
+                "Benefits:".bold().bright_green(),
+                self.benefits.join(", ").green()
+            )
         };
         write!(
             f,
-            "{} {}\n{} {}\n{} {}\n{} {}\n{} {}\n{} {}\n{} {}\n{} {}\n\n{}",
+            "{} {}\n{} {}\n{} {}\n{} {}\n{} {}\n{} {}\n{}{} {}\n{} {}\n\n{}",
             "Position:".bold().bright_green(),
             self.title.green(),
             "Company:".bold().bright_green(),
             self.company.green(),
             "Date Posted:".bold().bright_green(),
-            self.date_posted.green(),
+            match date_note {
+                Some(note) => format!("{} {}", self.date_posted.green(), note),
+                None => self.date_posted.green().to_string(),
+            },
             "Location:".bold().bright_green(),
             location.green(),
             "Remuneration:".bold().bright_green(),
             remuneration.green(),
             "Tags:".bold().bright_green(),
             tags.green(),
+            benefits,
             "Apply:".bold().bright_green(),
             apply,
             "Site:".bold().bright_green(),
-            self.site.bright_blue(),
+            site,
             "+-----------------------------------------------------------------------------------\
             ---------------------------------+\n"
                 .green()
@@ -98,6 +401,54 @@ impl Debug for Job {
     }
 }
 
+/// Pretty print Job for debug.
+impl Debug for Job {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.write_pretty(f, None)
+    }
+}
+
+/// A Job as shown in REPL listings, along with any notes that depend on the session config.
+pub struct Listing<'a> {
+    pub job: &'a Job,
+    pub expired: bool,
+    /// The job's application status, e.g. "applied 2022-07-27", if one has been set.
+    pub status: Option<String>,
+}
+
+impl<'a> Listing<'a> {
+    /// Creates a listing for a job, flagging it if it is older than `expired_after_days`.
+    pub fn new(job: &'a Job, expired_after_days: i64) -> Self {
+        Self {
+            job,
+            expired: job.is_likely_expired(expired_after_days),
+            status: None,
+        }
+    }
+
+    /// Shows the job's application status in the listing.
+    pub fn with_status(mut self, status: Option<String>) -> Self {
+        self.status = status;
+        self
+    }
+}
+
+/// Pretty print Listing for debug.
+impl Debug for Listing<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let expired = self.expired.then(|| EXPIRED_NOTE.dimmed());
+        let status = self
+            .status
+            .as_ref()
+            .map(|status| format!("[{}]", status).bright_yellow());
+        let note = match (expired, status) {
+            (Some(expired), Some(status)) => Some(format!("{} {}", expired, status).normal()),
+            (expired, status) => expired.or(status),
+        };
+        self.job.write_pretty(f, note)
+    }
+}
+
 /// All repository builder structs must implement the Builder trait for some repository
 /// type Output. This provides the basic ETL operations.
 pub trait Builder {
@@ -114,6 +465,19 @@ pub trait Builder {
     /// An optional filter to include only jobs of interest.
     fn filter<F: Fn(&Job) -> bool>(self, condition: F) -> Self;
 
+    /// An optional step that collapses jobs with the same normalized title and company, i.e. the
+    /// same job posted on more than one site, into a single job listing the sites it was posted on.
+    fn dedup_by_title_company(self) -> Self;
+
+    /// An optional step that merges duplicate jobs posted on more than one site into a single job.
+    #[deprecated(note = "use `dedup_by_title_company`")]
+    fn merge_duplicates(self) -> Self
+    where
+        Self: Sized,
+    {
+        self.dedup_by_title_company()
+    }
+
     /// Indexes Job instances for quick searching. This will depend on the structure of your
     /// repository, and how you choose to index the jobs it holds. The index method is the
     /// completing method for the repository builder and must return the repository type Output.
@@ -130,18 +494,158 @@ pub enum Skill {
     Blockchain,
 }
 
-/// Represents skill levels for Software jobs.
-#[derive(Debug, Eq, Hash, Clone, PartialEq)]
+/// Represents skill levels for Software jobs. Levels are ordered by seniority, with Manager last as
+/// the most senior.
+#[derive(Debug, Eq, Hash, Clone, PartialEq, PartialOrd, Ord)]
 pub enum Level {
     Junior,
     Intermediate,
     Senior,
     Staff,
     Lead,
-    Principle,
+    Principal,
     Manager,
 }
 
+impl Skill {
+    /// All Skill variants, in display order.
+    pub const ALL: [Skill; 5] = [
+        Skill::Backend,
+        Skill::Frontend,
+        Skill::Fullstack,
+        Skill::DevOps,
+        Skill::Blockchain,
+    ];
+}
+
+/// Parses a skill name case-insensitively, e.g. "backend" or "DevOps".
+impl FromStr for Skill {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|skill| format!("{:?}", skill).eq_ignore_ascii_case(s.trim()))
+            .ok_or(format!("Unknown skill \"{s}\""))
+    }
+}
+
+impl Level {
+    /// All Level variants, in display order.
+    pub const ALL: [Level; 7] = [
+        Level::Junior,
+        Level::Intermediate,
+        Level::Senior,
+        Level::Staff,
+        Level::Lead,
+        Level::Principal,
+        Level::Manager,
+    ];
+}
+
+/// Parses a level name case-insensitively, e.g. "senior" or "Staff", including the aliases matched
+/// when indexing, i.e. "snr" and "sr" for Senior and "principle" for Principal.
+impl FromStr for Level {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if ["snr", "sr"]
+            .iter()
+            .any(|alias| alias.eq_ignore_ascii_case(s))
+        {
+            return Ok(Level::Senior);
+        }
+        if "principle".eq_ignore_ascii_case(s) {
+            return Ok(Level::Principal);
+        }
+        Self::ALL
+            .into_iter()
+            .find(|level| format!("{:?}", level).eq_ignore_ascii_case(s))
+            .ok_or(format!("Unknown level \"{s}\""))
+    }
+}
+
+/// Represents how a job whose title matches more than one level is indexed, e.g. "Senior/Staff
+/// Engineer".
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum LevelPolicy {
+    /// The job is indexed under every level matched.
+    All,
+    /// The job is indexed under the most senior level matched only, so that it is counted once.
+    #[default]
+    Highest,
+}
+
+impl Display for LevelPolicy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", format!("{:?}", self).to_lowercase())
+    }
+}
+
+/// Parses a level policy name case-insensitively, i.e. "all" or "highest".
+impl FromStr for LevelPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [LevelPolicy::All, LevelPolicy::Highest]
+            .into_iter()
+            .find(|policy| policy.to_string().eq_ignore_ascii_case(s.trim()))
+            .ok_or(format!("Unknown level policy \"{s}\""))
+    }
+}
+
+/// Represents why a job is indexed as it is, as shown by the REPL's `explain` command.
+#[derive(Debug, PartialEq)]
+pub struct Explanation {
+    pub skills: Vec<Skill>,
+    /// Every level the job's title matches.
+    pub levels: Vec<Level>,
+    /// The levels the job is indexed by, under the repository's level policy.
+    pub indexed_levels: Vec<Level>,
+    pub location: Location,
+    pub country: Option<String>,
+}
+
+impl Explanation {
+    /// Returns true if the job's title matches more than one level.
+    pub fn has_level_conflict(&self) -> bool {
+        self.levels.len() > 1
+    }
+}
+
+/// Prints one `key: values` line per index, followed by a warning if the levels conflict.
+impl Display for Explanation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "skills: {}", debug_list(&self.skills))?;
+        writeln!(f, "levels: {}", debug_list(&self.indexed_levels))?;
+        writeln!(f, "location: {:?}", self.location)?;
+        writeln!(
+            f,
+            "country: {}",
+            self.country.as_deref().unwrap_or(NOT_AVAILABLE)
+        )?;
+        if self.has_level_conflict() {
+            writeln!(
+                f,
+                "warning: the title matches several levels ({}), indexed as {}",
+                debug_list(&self.levels),
+                debug_list(&self.indexed_levels)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the Debug names of items separated by commas, or "Not available" if there are none.
+fn debug_list<T: Debug>(items: &[T]) -> String {
+    if items.is_empty() {
+        NOT_AVAILABLE.to_string()
+    } else {
+        items.iter().map(|item| format!("{:?}", item)).join(", ")
+    }
+}
+
 /// Represents locations for Software jobs.
 #[derive(Debug, Eq, Hash, Clone, PartialEq)]
 pub enum Location {
@@ -149,207 +653,2155 @@ pub enum Location {
     Onsite,
 }
 
-/// Represents a reference counter for the Job type.
-type JobRef = Rc<Job>;
+/// Parses a location name case-insensitively, i.e. "remote" or "onsite" (also "on-site").
+impl FromStr for Location {
+    type Err = String;
 
-/// Represents a repository for Software jobs. A repository for any job type can be created.
-#[derive(Debug, Default)]
-pub struct SoftwareJobs {
-    pub all: Vec<JobRef>,
-    pub date: HashMap<String, Vec<JobRef>>,
-    pub company: HashMap<String, Vec<JobRef>>,
-    pub location: HashMap<Location, Vec<JobRef>>,
-    pub skill: HashMap<Skill, Vec<JobRef>>,
-    pub level: HashMap<Level, Vec<JobRef>>,
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "remote" => Ok(Location::Remote),
+            "onsite" | "on-site" => Ok(Location::Onsite),
+            _ => Err(format!("Unknown location \"{s}\"")),
+        }
+    }
 }
 
-impl SoftwareJobs {
-    /// Initialises a repository for Software jobs.
-    pub fn init_repo() -> Self {
-        let web3_careers = thread::spawn(|| Web3Careers::new().scrape());
-        let use_web3 = thread::spawn(|| UseWeb3::new().scrape());
-        let crypto_jobs_list = thread::spawn(|| CryptoJobsList::new().scrape());
-        let solana_jobs = thread::spawn(|| SolanaJobs::new().scrape());
-        let substrate_jobs = thread::spawn(|| SubstrateJobs::new().scrape());
-        let near_jobs = thread::spawn(|| NearJobs::new().scrape());
-
-        SoftwareJobsBuilder::new()
-            .import(vec![
-                web3_careers
-                    .join()
-                    .expect(THREAD_ERROR)
-                    .unwrap_or_else(Web3Careers::default_if_scrape_error)
-                    .jobs,
-                use_web3
-                    .join()
-                    .expect(THREAD_ERROR)
-                    .unwrap_or_else(UseWeb3::default_if_scrape_error)
-                    .jobs,
-                crypto_jobs_list
-                    .join()
-                    .expect(THREAD_ERROR)
-                    .unwrap_or_else(CryptoJobsList::default_if_scrape_error)
-                    .jobs,
-                solana_jobs
-                    .join()
-                    .expect(THREAD_ERROR)
-                    .unwrap_or_else(SolanaJobs::default_if_scrape_error)
-                    .jobs,
-                substrate_jobs
-                    .join()
-                    .expect(THREAD_ERROR)
-                    .unwrap_or_else(SubstrateJobs::default_if_scrape_error)
-                    .jobs,
-                near_jobs
-                    .join()
-                    .expect(THREAD_ERROR)
-                    .unwrap_or_else(NearJobs::default_if_scrape_error)
-                    .jobs,
-            ])
-            .filter(|job| {
-                job.title_contains_any(vec!["developer", "engineer", "engineering", "technical"])
-            }) // optional filter - in this case filter on engineering jobs
-            .index()
-    }
+/// The URL of the site a job was scraped from. This alias also stops serde treating the field as
+/// borrowed from the input, which would restrict deserializing jobs to `'static` input.
+pub type SiteUrl = &'static str;
+
+/// Deserializes a job's site. Sites are static strings as they come from the site definitions, so
+/// a site read from a file is replaced by the URL of the site it matches. A site that matches none,
+/// e.g. one since removed or a URL override no longer set, is left empty rather than failing the
+/// whole file.
+fn deserialize_site<'de, D: Deserializer<'de>>(deserializer: D) -> Result<SiteUrl, D::Error> {
+    let url = String::deserialize(deserializer)?;
+    Ok(known_site_url(&url).unwrap_or_else(|| {
+        warn!("unknown site \"{url}\" read from a file");
+        ""
+    }))
 }
 
-/// Provides an indexer function to the JobRef type.
-trait SoftwareJobsIndexer {
-    /// Adds a job reference to an index map for type T.
-    fn index_by<T>(&self, t: T, map: &mut HashMap<T, Vec<JobRef>>)
-    where
-        T: Sized + Eq + Hash;
+/// Represents a location parsed into its parts, e.g. "San Francisco, CA, USA" has the city "San
+/// Francisco", region "CA" and country "United States". Parts that can't be identified are None.
+#[derive(Debug, Clone, Default, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub struct LocationInfo {
+    pub city: Option<String>,
+    pub region: Option<String>,
+    pub country: Option<String>,
+    pub remote: bool,
 }
 
-impl SoftwareJobsIndexer for JobRef {
-    fn index_by<T>(&self, t: T, map: &mut HashMap<T, Vec<JobRef>>)
-    where
-        T: Sized + Eq + Hash,
-    {
-        map.entry(t)
-            .and_modify(|vec| vec.push(self.clone()))
-            .or_insert(vec![self.clone()]);
+/// Countries recognised in locations, with their common aliases. The first name is canonical.
+const COUNTRIES: [&[&str]; 36] = [
+    &[
+        "United States",
+        "US",
+        "USA",
+        "U.S.",
+        "U.S.A.",
+        "United States of America",
+        "America",
+    ],
+    &[
+        "United Kingdom",
+        "UK",
+        "U.K.",
+        "Great Britain",
+        "England",
+        "Scotland",
+        "Wales",
+    ],
+    &["Canada"],
+    &["Mexico"],
+    &["Brazil"],
+    &["Argentina"],
+    &["Colombia"],
+    &["Germany", "Deutschland"],
+    &["France"],
+    &["Spain"],
+    &["Portugal"],
+    &["Italy"],
+    &["Netherlands", "The Netherlands", "Holland"],
+    &["Belgium"],
+    &["Switzerland"],
+    &["Austria"],
+    &["Ireland"],
+    &["Poland"],
+    &["Sweden"],
+    &["Norway"],
+    &["Denmark"],
+    &["Finland"],
+    &["Estonia"],
+    &["Ukraine"],
+    &["Israel"],
+    &["United Arab Emirates", "UAE"],
+    &["Nigeria"],
+    &["South Africa"],
+    &["India"],
+    &["Singapore"],
+    &["Hong Kong"],
+    &["China"],
+    &["Japan"],
+    &["South Korea", "Korea"],
+    &["Australia"],
+    &["New Zealand"],
+];
+
+/// US state and Canadian province abbreviations, used to infer the country when it's omitted.
+const US_STATES: [&str; 51] = [
+    "AL", "AK", "AZ", "AR", "CA", "CO", "CT", "DE", "DC", "FL", "GA", "HI", "ID", "IL", "IN", "IA",
+    "KS", "KY", "LA", "ME", "MD", "MA", "MI", "MN", "MS", "MO", "MT", "NE", "NV", "NH", "NJ", "NM",
+    "NY", "NC", "ND", "OH", "OK", "OR", "PA", "RI", "SC", "SD", "TN", "TX", "UT", "VT", "VA", "WA",
+    "WV", "WI", "WY",
+];
+const CANADIAN_PROVINCES: [&str; 10] = ["AB", "BC", "MB", "NB", "NL", "NS", "ON", "PE", "QC", "SK"];
+
+impl LocationInfo {
+    /// Parses a location such as "Berlin, Germany", "Remote, US" or "San Francisco, CA, USA".
+    /// Parenthesised notes and remote markers are set aside, the last part is matched against known
+    /// countries and state abbreviations, and whatever is left is taken as the city then region.
+    /// Messy strings produce whatever parts can be identified, rather than an error.
+    pub fn parse(location: &str) -> Self {
+        let remote = is_remote(location);
+        static NOTES: OnceLock<Regex> = OnceLock::new();
+        let without_notes = cached_regex(&NOTES, r"\([^)]*\)")
+            .replace_all(location, ",")
+            .to_string();
+        let mut parts = without_notes
+            .split([',', '|', '/', ';', '·'])
+            .map(|part| part.trim().trim_matches(['-', '–']).trim())
+            .filter(|part| !part.is_empty() && !is_remote(part))
+            .map(String::from)
+            .collect::<Vec<String>>();
+
+        let mut info = Self {
+            remote,
+            ..Default::default()
+        };
+        if let Some(country) = parts.last().and_then(|part| country_name(part)) {
+            info.country = Some(country.to_string());
+            parts.pop();
+        }
+        // a trailing state or province abbreviation, e.g. "CA", but not a lone one
+        let abbreviation = parts
+            .last()
+            .map(|part| part.to_uppercase())
+            .filter(|_| parts.len() > 1 || info.country.is_some());
+        if let Some(abbreviation) = abbreviation {
+            let country = if US_STATES.contains(&abbreviation.as_str()) {
+                Some("United States")
+            } else if CANADIAN_PROVINCES.contains(&abbreviation.as_str()) {
+                Some("Canada")
+            } else {
+                None
+            };
+            if let Some(country) = country {
+                info.country = info.country.or(Some(country.to_string()));
+                info.region = Some(abbreviation);
+                parts.pop();
+            }
+        }
+        match parts.as_slice() {
+            [] => {}
+            [city] => info.city = Some(city.clone()),
+            [city, region, ..] => {
+                info.city = Some(city.clone());
+                info.region = info.region.or(Some(region.clone()));
+            }
+        }
+        info
     }
 }
 
-/// Represents a repository builder for Software jobs. A repository builder for any job type can be
-/// created.
-struct SoftwareJobsBuilder(SoftwareJobs);
+/// Returns the canonical name of a country from its name or an alias, ignoring case.
+fn country_name(s: &str) -> Option<&'static str> {
+    COUNTRIES
+        .iter()
+        .find(|names| names.iter().any(|name| name.eq_ignore_ascii_case(s.trim())))
+        .map(|names| names[0])
+}
 
-impl Builder for SoftwareJobsBuilder {
-    type Output = SoftwareJobs;
+/// Represents broad regions (including their timezones) that remote jobs can be restricted to.
+#[derive(Debug, Eq, Hash, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Region {
+    Americas,
+    Emea,
+    Apac,
+}
 
-    fn new() -> Self {
-        Self(Default::default())
-    }
+impl Region {
+    /// All Region variants, in display order.
+    pub const ALL: [Region; 3] = [Region::Americas, Region::Emea, Region::Apac];
 
-    fn import(mut self, jobs: Vec<Vec<Job>>) -> Self {
-        // allow duplicate job posts if they are from different sites - user can choose which site
-        // to apply from
-        for vec in jobs {
-            for job in vec {
-                self.0.all.push(Rc::new(job))
-            }
+    /// Words and phrases in a location that indicate the region, including common timezones.
+    fn keywords(&self) -> &'static [&'static str] {
+        match self {
+            Region::Americas => &[
+                "americas",
+                "america",
+                "usa",
+                "united states",
+                "canada",
+                "latam",
+                "mexico",
+                "brazil",
+                "argentina",
+                "est",
+                "edt",
+                "cst",
+                "cdt",
+                "pst",
+                "pdt",
+            ],
+            Region::Emea => &[
+                "emea",
+                "europe",
+                "european",
+                "eu",
+                "uk",
+                "united kingdom",
+                "africa",
+                "middle east",
+                "germany",
+                "france",
+                "spain",
+                "portugal",
+                "netherlands",
+                "poland",
+                "cet",
+                "cest",
+                "gmt",
+                "bst",
+                "eet",
+                "wet",
+            ],
+            Region::Apac => &[
+                "apac",
+                "asia",
+                "australia",
+                "new zealand",
+                "india",
+                "singapore",
+                "japan",
+                "china",
+                "philippines",
+                "indonesia",
+                "sgt",
+                "aest",
+                "aedt",
+                "jst",
+            ],
         }
-        self
     }
+}
 
-    fn filter<F>(mut self, condition: F) -> Self
-    where
-        F: Fn(&Job) -> bool,
-    {
-        self.0.all.retain(|job| condition(job));
-        self
+/// Parses a region name case-insensitively, e.g. "emea", or a word that indicates one, e.g.
+/// "europe".
+impl FromStr for Region {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = normalize_name(s);
+        Self::ALL
+            .into_iter()
+            .find(|region| region.keywords().contains(&s.as_str()))
+            .ok_or(format!("Unknown region \"{s}\""))
     }
+}
 
-    fn index(mut self) -> Self::Output {
-        self.0.all.iter().for_each(|job| {
-            // index by attribute
-            job.index_by(job.date_posted.clone(), &mut self.0.date);
-            job.index_by(job.company.clone(), &mut self.0.company);
+/// Parses the regions a remote location is restricted to from the region it states (see
+/// [`remote_region`]), e.g. [Emea] from "Remote - EMEA" or [Americas] from "Remote (US timezones
+/// only)". A region is named, or implied by a timezone or a country (see [`LocationInfo`]); a
+/// country abbreviation counts only in capitals, so "us" in "Remote, work with us" isn't the United
+/// States. Returns no regions if the location isn't remote or states none.
+pub fn parse_regions(location: &str) -> Vec<Region> {
+    let Some(stated) = remote_region(location) else {
+        return vec![];
+    };
+    let text = format!(" {} ", normalize_name(&stated));
+    let countries = stated
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.len() > 1 && word.chars().all(|c| c.is_ascii_uppercase()))
+        .chain([stated.as_str()])
+        .filter_map(|part| LocationInfo::parse(part).country)
+        .collect::<Vec<String>>();
+    Region::ALL
+        .into_iter()
+        .filter(|region| {
+            region
+                .keywords()
+                .iter()
+                .any(|keyword| text.contains(&format!(" {} ", keyword)))
+                || countries
+                    .iter()
+                    .any(|country| country.parse::<Region>().as_ref() == Ok(region))
+        })
+        .collect()
+}
 
-            // index by location
-            let locations_map = &mut self.0.location;
-            if job.location_contains("remote") {
-                job.index_by(Location::Remote, locations_map);
-            } else {
-                job.index_by(Location::Onsite, locations_map);
-            }
+/// Normalizes a name for loose matching, e.g. "Acme, Inc." and "acme inc" both become "acme inc".
+pub fn normalize_name(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|s| !s.is_empty())
+        .join(" ")
+}
 
-            // index by skill
-            let skills_map = &mut self.0.skill;
-            if job.title_contains("backend") {
-                job.index_by(Skill::Backend, skills_map);
-            }
-            if job.title_contains("frontend") {
-                job.index_by(Skill::Frontend, skills_map);
-            }
-            if job.title_contains("fullstack") {
-                job.index_by(Skill::Fullstack, skills_map);
-            }
-            if job.title_contains_any(vec!["devops", "platform", "infra"]) {
-                job.index_by(Skill::DevOps, skills_map);
-            }
-            if job.title_contains_any(vec!["blockchain", "smart contract"]) {
-                job.index_by(Skill::Blockchain, skills_map);
-            }
+/// Checks whether a location describes a remote job, e.g. "Remote", "100% Remote", "Fully remote",
+/// "Remote (US only)", "Remote-first" or "Work from home". Negations such as "No remote" or
+/// "Non-remote" are not remote.
+pub fn is_remote(location: &str) -> bool {
+    let words = normalize_name(location);
+    let words = words.split(' ').collect::<Vec<&str>>();
+    let remote = words
+        .iter()
+        .any(|w| matches!(*w, "remote" | "remotely" | "wfh" | "anywhere"))
+        || words.windows(3).any(|w| w == ["work", "from", "home"]);
+    let negated = words
+        .windows(2)
+        .any(|w| matches!(w[0], "no" | "non" | "not") && w[1] == "remote");
+    remote && !negated
+}
 
-            // index by level
-            let levels_map = &mut self.0.level;
-            if job.title_contains("junior") {
-                job.index_by(Level::Junior, levels_map);
-            }
-            if job.title_contains("intermediate") {
-                job.index_by(Level::Intermediate, levels_map);
-            }
-            if job.title_contains_any(vec!["senior", "snr", "sr"]) {
-                job.index_by(Level::Senior, levels_map);
-            }
-            if job.title_contains("staff") {
-                job.index_by(Level::Staff, levels_map);
-            }
-            if job.title_contains("lead") {
-                job.index_by(Level::Lead, levels_map);
-            }
-            if job.title_contains("principle") {
-                job.index_by(Level::Principle, levels_map);
-            }
-            if job.title_contains("manager") {
-                job.index_by(Level::Manager, levels_map);
-            }
+/// Checks whether a location describes a fully remote job: one that is remote (see [`is_remote`])
+/// with nothing suggesting time in an office. The heuristic looks for hybrid and onsite markers -
+/// "hybrid", "onsite", "on-site", "in office" or "office based" - and remote work that is only
+/// partial, e.g. "some remote", "partially remote" or "partly remote". So "Remote (US only)" is
+/// fully remote, but "Berlin (hybrid, some remote)" and "Remote / On-site" are not.
+pub fn is_fully_remote(location: &str) -> bool {
+    let words = normalize_name(location);
+    let words = words.split(' ').collect::<Vec<&str>>();
+    let hybrid = words.iter().any(|w| matches!(*w, "hybrid" | "onsite"))
+        || words.windows(2).any(|w| {
+            matches!(w, ["on", "site"] | ["in", "office"] | ["office", "based"])
+                || (matches!(w[0], "some" | "partially" | "partly") && w[1] == "remote")
         });
-        self.0
+    is_remote(location) && !hybrid
+}
+
+/// Returns the regex compiled from `pattern`, compiling it into `cell` the first time, so that
+/// functions run for every job don't compile their regexes on each call.
+fn cached_regex(cell: &'static OnceLock<Regex>, pattern: &str) -> &'static Regex {
+    cell.get_or_init(|| Regex::new(pattern).expect("a valid regex"))
+}
+
+/// Extracts the region a remote job is restricted to, e.g. "US only" from "Remote (US only)" or
+/// "Europe" from "Remote - Europe". Qualifiers such as "100%" or "first" are not regions, so
+/// "100% Remote" and "Remote-first" have none.
+pub fn remote_region(location: &str) -> Option<String> {
+    if !is_remote(location) {
+        return None;
     }
+    static AROUND_REMOTE: OnceLock<Regex> = OnceLock::new();
+    static NOTE: OnceLock<Regex> = OnceLock::new();
+    let region = match cached_regex(&NOTE, r"\(([^)]*)\)").captures(location) {
+        Some(caps) => caps[1].to_string(),
+        None => {
+            let re = cached_regex(&AROUND_REMOTE, r"(?i)^(.*?)\bremote(?:ly)?\b(.*)$");
+            let caps = re.captures(location)?;
+            format!("{} {}", &caps[1], &caps[2])
+        }
+    };
+    let region = region
+        .split(|c: char| c.is_whitespace() || "-–:/|,;".contains(c))
+        .filter(|word| {
+            !word.is_empty()
+                && !matches!(
+                    word.to_lowercase().as_str(),
+                    "100%"
+                        | "fully"
+                        | "full"
+                        | "fully-remote"
+                        | "remote"
+                        | "remotely"
+                        | "first"
+                        | "friendly"
+                        | "ok"
+                        | "possible"
+                        | "optional"
+                        | "available"
+                        | "role"
+                )
+        })
+        .join(" ");
+    (!region.is_empty()).then_some(region)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::{Builder, Job, Level, Location, Skill, SoftwareJobsBuilder};
+/// Common employment benefits, each with the phrases that mention it. Matching is best-effort, on
+/// whole words ignoring case (see [`parse_benefits`]).
+pub const BENEFITS: &[(&str, &[&str])] = &[
+    (
+        "4-day week",
+        &[
+            "4-day week",
+            "4 day week",
+            "four-day week",
+            "four day week",
+            "32-hour week",
+        ],
+    ),
+    (
+        "unlimited PTO",
+        &[
+            "unlimited pto",
+            "unlimited vacation",
+            "unlimited holiday",
+            "unlimited time off",
+        ],
+    ),
+    (
+        "health insurance",
+        &[
+            "health insurance",
+            "medical insurance",
+            "healthcare",
+            "health care",
+            "medical cover",
+        ],
+    ),
+    (
+        "remote stipend",
+        &[
+            "remote stipend",
+            "home office stipend",
+            "wfh stipend",
+            "coworking stipend",
+        ],
+    ),
+    (
+        "equity",
+        &["equity", "stock options", "esop", "token allocation"],
+    ),
+    (
+        "parental leave",
+        &["parental leave", "maternity leave", "paternity leave"],
+    ),
+];
 
-    #[test]
-    fn test_software_jobs_repository() {
-        let repo = SoftwareJobsBuilder::new()
-            .import(vec![
-                vec![
-                    Job {
-                        title: "Engineering Manager".into(),
-                        company: "Company_2".into(),
-                        date_posted: "2022-07-28".into(),
-                        location: "Remote".into(),
-                        remuneration: "$165k - $200k".into(),
-                        tags: vec!["tag1".into(), "tag2".into()],
-                        apply: "https://site1.com".into(),
-                        site: "https://site1.com",
-                    },
-                    Job {
-                        title: "Senior Marketer".into(),
-                        company: "Company_3".into(),
-                        date_posted: "2022-07-29".into(),
-                        location: "Remote".into(),
-                        remuneration: "$165k - $200k".into(),
-                        tags: vec!["tag1".into(), "tag2".into()],
-                        apply: "https://site1.com".into(),
-                        site: "https://site1.com",
+/// Returns the benefits (see [`BENEFITS`]) mentioned in any of the texts, e.g. a job's tags and
+/// remuneration, in the order they are listed. A phrase must appear as whole words within one text,
+/// ignoring case and punctuation, so "equity" isn't found in "inequity" and "4-day week" is found
+/// in "4 Day Week".
+pub fn parse_benefits<'a, I>(texts: I) -> Vec<String>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let texts = texts
+        .into_iter()
+        .map(|text| format!(" {} ", normalize_name(text)))
+        .collect::<Vec<String>>();
+    BENEFITS
+        .iter()
+        .filter(|(_, phrases)| {
+            phrases.iter().any(|phrase| {
+                let phrase = format!(" {} ", normalize_name(phrase));
+                texts.iter().any(|text| text.contains(&phrase))
+            })
+        })
+        .map(|(name, _)| name.to_string())
+        .collect()
+}
+
+/// Returns the name of the benefit in [`BENEFITS`] that a user's input refers to, either by its
+/// name or one of its phrases, ignoring case.
+pub fn benefit_name(input: &str) -> Option<&'static str> {
+    let input = input.trim().to_lowercase();
+    BENEFITS
+        .iter()
+        .find(|(name, phrases)| name.to_lowercase() == input || phrases.contains(&input.as_str()))
+        .map(|(name, _)| *name)
+}
+
+/// Searches text for an application deadline such as "Apply by 2025-06-30", "Deadline: 30 June
+/// 2025" or "Applications close June 30, 2025". This is best-effort given how varied the phrasing
+/// is. Deadlines before `today` are ignored as they're either stale or misparsed.
+pub fn parse_apply_by(text: &str, today: NaiveDate) -> Option<NaiveDate> {
+    static DEADLINE: OnceLock<Regex> = OnceLock::new();
+    static ORDINAL: OnceLock<Regex> = OnceLock::new();
+    let re = cached_regex(
+        &DEADLINE,
+        concat!(
+            r"(?i)(?:apply by|apply before|deadline|closing date|applications close(?:s)?(?: on)?)\W*",
+            r"(\d{4}-\d{1,2}-\d{1,2}|\d{1,2}/\d{1,2}/\d{4}",
+            r"|[a-z]+\.? \d{1,2}(?:st|nd|rd|th)?,? \d{4}",
+            r"|\d{1,2}(?:st|nd|rd|th)? [a-z]+\.?,? \d{4})",
+        ),
+    );
+    let date = re.captures_iter(text).find_map(|caps| {
+        let date = caps[1].replace([',', '.'], "");
+        let date = cached_regex(&ORDINAL, r"(\d)(?:st|nd|rd|th)").replace(&date, "$1");
+        [
+            "%Y-%m-%d", "%d/%m/%Y", "%B %d %Y", "%b %d %Y", "%d %B %Y", "%d %b %Y",
+        ]
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(&date, format).ok())
+    })?;
+    (date >= today).then_some(date)
+}
+
+/// Scores how similar two jobs are: 3 points per shared tag (ignoring case), 2 per shared skill and
+/// 1 per shared title word, ignoring generic words such as "engineer". Unrelated jobs score zero.
+pub fn similarity(a: &Job, b: &Job) -> usize {
+    const GENERIC_WORDS: [&str; 8] = [
+        "engineer",
+        "engineering",
+        "developer",
+        "software",
+        "and",
+        "of",
+        "the",
+        "a",
+    ];
+    let tags = |job: &Job| {
+        job.tags
+            .iter()
+            .map(|tag| tag.trim().to_lowercase())
+            .filter(|tag| !tag.is_empty())
+            .unique()
+            .collect::<Vec<String>>()
+    };
+    let words = |job: &Job| {
+        normalize_name(&job.title)
+            .split(' ')
+            .filter(|word| !word.is_empty() && !GENERIC_WORDS.contains(word))
+            .map(String::from)
+            .unique()
+            .collect::<Vec<String>>()
+    };
+    fn shared<T: PartialEq>(a: &[T], b: &[T]) -> usize {
+        a.iter().filter(|x| b.contains(x)).count()
+    }
+
+    3 * shared(&tags(a), &tags(b))
+        + 2 * shared(&a.skills(), &b.skills())
+        + shared(&words(a), &words(b))
+}
+
+/// Scores a job by summing the weight of each boost keyword found in its title or tags (ignoring
+/// case). A job matching no keywords scores zero.
+pub fn score(job: &Job, boosts: &[(String, i64)]) -> i64 {
+    let title = job.title.to_lowercase();
+    let tags = job
+        .tags
+        .iter()
+        .map(|tag| tag.to_lowercase())
+        .collect::<Vec<String>>();
+    boosts
+        .iter()
+        .filter(|(keyword, _)| {
+            let keyword = keyword.to_lowercase();
+            title.contains(&keyword) || tags.iter().any(|tag| tag.contains(&keyword))
+        })
+        .map(|(_, weight)| weight)
+        .sum()
+}
+
+/// The most points a job scores for recency in [`recommendation_score`], if posted today. A job
+/// scores one point less for each day since it was posted.
+const RECENCY_POINTS: i64 = 30;
+/// The points a job scores in [`recommendation_score`] for being known to pay the salary floor.
+const SALARY_POINTS: i64 = 20;
+
+/// Scores how well a job suits the profile in the config (its boosts, required skills and salary
+/// floor) for `recommend`, as the sum of:
+/// - the job's boost score (see [`score`])
+/// - up to [`RECENCY_POINTS`] for being recently posted, as of `today`
+/// - [`SALARY_POINTS`] if the job is known to pay at least the salary floor
+///
+/// Returns None for a job that should not be recommended, i.e. one missing a required skill or
+/// known to pay less than the salary floor. Scores below zero are raised to zero.
+pub fn recommendation_score(job: &Job, config: &Config, today: NaiveDate) -> Option<u32> {
+    let skills = job.skills();
+    if !config
+        .required_skills
+        .iter()
+        .all(|skill| skills.contains(skill))
+    {
+        return None;
+    }
+    let salary = match config.min_salary {
+        Some(floor) => match job.pays_at_least(floor) {
+            Some(false) => return None,
+            Some(true) => SALARY_POINTS,
+            None => 0,
+        },
+        None => 0,
+    };
+    let recency = job.parsed_date().map_or(0, |date| {
+        (RECENCY_POINTS - (today - date).num_days()).clamp(0, RECENCY_POINTS)
+    });
+    Some((score(job, &config.boosts) + recency + salary).max(0) as u32)
+}
+
+/// The weight of a search term found in a job's title.
+const TITLE_WEIGHT: u32 = 5;
+/// The weight of a search term found in a job's tags.
+const TAG_WEIGHT: u32 = 3;
+/// The weight of a search term found in a job's company name.
+const COMPANY_WEIGHT: u32 = 2;
+/// The weight of a search term found in a job's location.
+const LOCATION_WEIGHT: u32 = 1;
+
+/// Splits text into lowercase words, e.g. "Rust/Go Engineer" into "rust", "go" and "engineer".
+fn words(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Scores how relevant a job is to a search query. Each query word found as a word of the title,
+/// tags, company name or location adds that field's weight, with the title weighted highest. A job
+/// matching no words scores zero.
+pub fn relevance(job: &Job, query: &str) -> u32 {
+    let fields = [
+        (words(&job.title), TITLE_WEIGHT),
+        (words(&job.tags.join(" ")), TAG_WEIGHT),
+        (words(&job.company), COMPANY_WEIGHT),
+        (words(&job.location), LOCATION_WEIGHT),
+    ];
+    words(query)
+        .into_iter()
+        .unique()
+        .map(|term| {
+            fields
+                .iter()
+                .filter(|(words, _)| words.contains(&term))
+                .map(|(_, weight)| weight)
+                .sum::<u32>()
+        })
+        .sum()
+}
+
+/// Represents a side by side comparison of job counts for two subjects, e.g. two companies.
+#[derive(Debug, PartialEq)]
+pub struct Comparison {
+    pub left: String,
+    pub right: String,
+    /// Rows of (label, left count, right count).
+    pub rows: Vec<(String, usize, usize)>,
+}
+
+impl Comparison {
+    /// Returns the (left, right) counts for the row with the given label.
+    pub fn get(&self, label: &str) -> Option<(usize, usize)> {
+        self.rows
+            .iter()
+            .find(|(l, _, _)| l == label)
+            .map(|(_, left, right)| (*left, *right))
+    }
+}
+
+/// Prints the comparison as an aligned table.
+impl Display for Comparison {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let label_width = self
+            .rows
+            .iter()
+            .map(|(label, _, _)| label.len())
+            .max()
+            .unwrap_or(0);
+        let left_width = self.left.len().max(5);
+        writeln!(
+            f,
+            "{:label_width$}  {:left_width$}  {}",
+            "", self.left, self.right
+        )?;
+        for (label, left, right) in &self.rows {
+            writeln!(f, "{label:label_width$}  {left:<left_width$}  {right}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Represents a side by side comparison of the job markets for two skills: job counts by level and
+/// location, and the median salary of the jobs with a parsable salary.
+#[derive(Debug, PartialEq)]
+pub struct SkillComparison {
+    pub counts: Comparison,
+    /// The (left, right) median salaries, None if a skill has no jobs with a salary.
+    pub median_salary: (Option<u32>, Option<u32>),
+}
+
+/// Prints the counts table with the median salaries as its last row.
+impl Display for SkillComparison {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let label = "Median salary";
+        let salary = |median: Option<u32>| {
+            median.map_or(NOT_AVAILABLE.to_string(), |s| format!("${}k", s / 1000))
+        };
+        let (left, right) = (salary(self.median_salary.0), salary(self.median_salary.1));
+        let label_width = self
+            .counts
+            .rows
+            .iter()
+            .map(|(label, _, _)| label.len())
+            .max()
+            .unwrap_or(0)
+            .max(label.len());
+        let left_width = self.counts.left.len().max(left.len()).max(5);
+        writeln!(
+            f,
+            "{:label_width$}  {:left_width$}  {}",
+            "", self.counts.left, self.counts.right
+        )?;
+        for (label, l, r) in &self.counts.rows {
+            writeln!(f, "{label:label_width$}  {l:<left_width$}  {r}")?;
+        }
+        writeln!(f, "{label:label_width$}  {left:left_width$}  {right}")
+    }
+}
+
+/// Parses a salary range from remuneration text, e.g. "$120k - $150k", "$120,000" or "€90.000". A
+/// single amount is a range of one. Amounts under 1000 without a "k", e.g. hourly rates, are
+/// ignored, and text with an amount too large to be a salary has no salary.
+pub fn parse_salary(text: &str) -> Option<(u32, u32)> {
+    static AMOUNT: OnceLock<Regex> = OnceLock::new();
+    static THOUSANDS: OnceLock<Regex> = OnceLock::new();
+    let amount_regex = cached_regex(&AMOUNT, r"(?i)(\d(?:[\d,.]*\d)?)\s*(k)?");
+    // dots separating thousands, e.g. "90.000", rather than a decimal point
+    let thousands_regex = cached_regex(&THOUSANDS, r"^\d{1,3}(?:\.\d{3})+$");
+    let amounts = amount_regex
+        .captures_iter(text)
+        .filter_map(|caps| {
+            let mut amount = caps[1].replace(',', "");
+            if caps.get(2).is_none() && thousands_regex.is_match(&amount) {
+                amount = amount.replace('.', "");
+            }
+            let amount = amount.parse::<f64>().ok()?;
+            let amount = if caps.get(2).is_some() {
+                amount * 1000.0
+            } else {
+                amount
+            };
+            (amount >= 1000.0).then_some((amount <= u32::MAX as f64).then_some(amount as u32))
+        })
+        .take(2)
+        .collect::<Option<Vec<u32>>>()?;
+    match amounts[..] {
+        [amount] => Some((amount, amount)),
+        [a, b] => Some((a.min(b), a.max(b))),
+        _ => None,
+    }
+}
+
+/// Returns the median of the midpoints of the jobs' salaries, or None if no job has a salary.
+fn median_salary<'a, I: Iterator<Item = &'a JobRef>>(jobs: I) -> Option<u32> {
+    let midpoints = jobs
+        .filter_map(|job| job.salary())
+        .map(|(min, max)| min.midpoint(max))
+        .sorted()
+        .collect::<Vec<u32>>();
+    match midpoints.len() {
+        0 => None,
+        n if n % 2 == 0 => Some(midpoints[n / 2 - 1].midpoint(midpoints[n / 2])),
+        n => Some(midpoints[n / 2]),
+    }
+}
+
+/// Represents the orders companies can be listed in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CompanyOrder {
+    /// Most jobs first.
+    Count,
+    /// Most recent posting first. Companies with only unknown dates posted are last.
+    Recent,
+}
+
+/// Represents the orders `fetch jobs sort` can list jobs in.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum JobOrder {
+    /// Oldest posting first. Jobs with an unknown date posted are first.
+    DateAsc,
+    /// Most recent posting first. Jobs with an unknown date posted are last.
+    #[default]
+    DateDesc,
+    /// Alphabetically by company name.
+    Company,
+    /// Highest salary midpoint first. Jobs with no parseable salary are last.
+    Remuneration,
+    /// Most complete job first (see [`Job::confidence`]).
+    Confidence,
+}
+
+impl JobOrder {
+    /// Sorts jobs in this order. The sort is stable, so equal jobs keep their current order.
+    pub fn sort(self, jobs: &mut [JobRef]) {
+        match self {
+            Self::DateAsc => jobs.sort_by(|a, b| a.date_posted.cmp(&b.date_posted)),
+            Self::DateDesc => jobs.sort_by(|a, b| b.date_posted.cmp(&a.date_posted)),
+            Self::Company => jobs.sort_by_cached_key(|job| job.company.to_lowercase()),
+            Self::Remuneration => jobs.sort_by_cached_key(|job| {
+                Reverse(job.salary().map(|(min, max)| min.midpoint(max)))
+            }),
+            Self::Confidence => jobs.sort_by_key(|job| Reverse(job.confidence())),
+        }
+    }
+}
+
+/// Parses the words following `fetch jobs sort`, e.g. "date asc". No words, or "date" alone, is
+/// date descending.
+impl FromStr for JobOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_whitespace().collect::<Vec<&str>>().as_slice() {
+            [] | ["date"] | ["date", "desc"] => Ok(Self::DateDesc),
+            ["date", "asc"] => Ok(Self::DateAsc),
+            ["company"] => Ok(Self::Company),
+            ["remuneration"] => Ok(Self::Remuneration),
+            ["confidence"] => Ok(Self::Confidence),
+            _ => Err(format!("Unknown sort order \"{}\"", s.trim())),
+        }
+    }
+}
+
+/// Represents companies with their number of jobs and most recent date posted.
+#[derive(Debug, PartialEq)]
+pub struct Companies {
+    /// Rows of (company, job count, most recent date posted).
+    pub rows: Vec<(String, usize, Option<NaiveDate>)>,
+}
+
+/// Prints the companies as an aligned table.
+impl Display for Companies {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let width = self
+            .rows
+            .iter()
+            .map(|(company, _, _)| company.len())
+            .max()
+            .unwrap_or(0)
+            .max(7);
+        writeln!(f, "{:width$}  {:4}  Latest", "Company", "Jobs")?;
+        for (company, count, latest) in &self.rows {
+            let latest = latest.map_or(NOT_AVAILABLE.to_string(), |date| date.to_string());
+            writeln!(f, "{company:width$}  {count:<4}  {latest}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The bucket for jobs whose country or city couldn't be parsed from their location.
+const UNKNOWN: &str = "unknown";
+/// The most countries and cities listed by `stats locations`.
+const TOP_LOCATIONS: usize = 10;
+
+/// Represents a geographic overview of the jobs in a repository.
+#[derive(Debug, Default, PartialEq)]
+pub struct LocationStats {
+    pub remote: usize,
+    pub onsite: usize,
+    /// Jobs split between remote and onsite work. These are counted as hybrid only.
+    pub hybrid: usize,
+    /// The countries with the most jobs, most first, with unparsed countries counted as "unknown".
+    pub countries: Vec<(String, usize)>,
+    /// The cities with the most jobs, most first, with unparsed cities counted as "unknown".
+    pub cities: Vec<(String, usize)>,
+}
+
+/// Prints the remote/onsite/hybrid split followed by the top countries and cities.
+impl Display for LocationStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Remote  {}", self.remote)?;
+        writeln!(f, "Onsite  {}", self.onsite)?;
+        writeln!(f, "Hybrid  {}", self.hybrid)?;
+        for (heading, rows) in [("Country", &self.countries), ("City", &self.cities)] {
+            let width = rows
+                .iter()
+                .map(|(name, _)| name.len())
+                .max()
+                .unwrap_or(0)
+                .max(heading.len());
+            writeln!(f, "\n{heading:width$}  Jobs")?;
+            for (name, count) in rows {
+                writeln!(f, "{name:width$}  {count}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Represents the size of each index in a repository, as printed by `count`.
+#[derive(Debug, Default, PartialEq)]
+pub struct Counts {
+    pub jobs: usize,
+    pub companies: usize,
+    pub skills: Vec<(Skill, usize)>,
+    pub levels: Vec<(Level, usize)>,
+    pub locations: Vec<(Location, usize)>,
+}
+
+/// Prints the job and company totals, then the jobs per skill, level and location, one aligned
+/// table row each.
+impl Display for Counts {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let rows = [
+            ("Jobs".to_string(), self.jobs),
+            ("Companies".into(), self.companies),
+        ]
+        .into_iter()
+        .chain(
+            self.skills
+                .iter()
+                .map(|(skill, n)| (format!("{skill:?}"), *n)),
+        )
+        .chain(
+            self.levels
+                .iter()
+                .map(|(level, n)| (format!("{level:?}"), *n)),
+        )
+        .chain(
+            self.locations
+                .iter()
+                .map(|(location, n)| (format!("{location:?}"), *n)),
+        )
+        .collect::<Vec<(String, usize)>>();
+        let width = rows.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+        for (i, (name, count)) in rows.iter().enumerate() {
+            // a blank line before each group of index counts
+            if [
+                2,
+                2 + self.skills.len(),
+                2 + self.skills.len() + self.levels.len(),
+            ]
+            .contains(&i)
+            {
+                writeln!(f)?;
+            }
+            writeln!(f, "{name:width$}  {count}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Represents groups of jobs that are likely the same role posted more than once, e.g. on several
+/// sites.
+#[derive(Debug, Default)]
+pub struct Duplicates {
+    pub groups: Vec<Vec<JobRef>>,
+}
+
+/// Prints each group's title and company, followed by the site and date of each post.
+impl Display for Duplicates {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.groups.is_empty() {
+            return writeln!(f, "No duplicate jobs found.");
+        }
+        for group in &self.groups {
+            writeln!(
+                f,
+                "{} at {} ({} posts)",
+                group[0].title,
+                group[0].company,
+                group.len()
+            )?;
+            for job in group {
+                writeln!(f, "  {} {}", job.date_posted, job.site)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Represents the jobs missing a field, grouped by the site they were scraped from, as shown by the
+/// REPL's `missing` command.
+#[derive(Debug)]
+pub struct Missing {
+    pub field: Field,
+    /// Each site's name and its jobs missing the field, the site missing the most first.
+    pub sites: Vec<(String, Vec<JobRef>)>,
+}
+
+/// Prints each site's count of jobs missing the field, followed by the title and company of each.
+impl Display for Missing {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.sites.is_empty() {
+            return writeln!(f, "No jobs are missing {}.", self.field.name());
+        }
+        for (site, jobs) in &self.sites {
+            writeln!(f, "{site}: {} missing {}", jobs.len(), self.field.name())?;
+            for job in jobs {
+                writeln!(f, "  {} at {}", job.title, job.company)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Represents the number of jobs posted each ISO week, as shown by the REPL's `timeline` command.
+#[derive(Debug, Default, PartialEq)]
+pub struct Timeline {
+    /// Each week with jobs, e.g. "2022-W30", and its job count, in date order.
+    pub weeks: Vec<(String, usize)>,
+    /// The number of jobs with a date posted that can't be parsed.
+    pub unknown: usize,
+}
+
+/// Prints a histogram with one bar per week, followed by the count of jobs with an unknown date.
+impl Display for Timeline {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (week, count) in &self.weeks {
+            writeln!(f, "{week}  {:4}  {}", count, "#".repeat(*count))?;
+        }
+        if self.unknown > 0 {
+            writeln!(f, "Unknown date  {}", self.unknown)?;
+        }
+        Ok(())
+    }
+}
+
+/// Represents a site and the title keywords its jobs must contain, any of which will do. No keywords
+/// accepts every job from the site.
+pub type SiteFilter = (String, Vec<String>);
+
+/// Represents a reference counter for the Job type.
+pub type JobRef = Rc<Job>;
+
+/// Represents how long each site took to scrape when a repository was initialised.
+#[derive(Debug, Default)]
+pub struct ScrapeTimings {
+    /// The URL of each site and how long it took to scrape, in the order the sites were joined.
+    pub sites: Vec<(String, Duration)>,
+    /// The wall-clock time taken to scrape all sites concurrently.
+    pub total: Duration,
+}
+
+/// Prints the timings as an aligned table, slowest sites first.
+impl Display for ScrapeTimings {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let width = self
+            .sites
+            .iter()
+            .map(|(url, _)| site_name(url).len())
+            .max()
+            .unwrap_or(0)
+            .max(5);
+        for (url, elapsed) in self.sites.iter().sorted_by_key(|(_, e)| Reverse(*e)) {
+            writeln!(
+                f,
+                "{:width$}  {:.2}s",
+                site_name(url),
+                elapsed.as_secs_f64()
+            )?;
+        }
+        writeln!(f, "{:width$}  {:.2}s", "Total", self.total.as_secs_f64())
+    }
+}
+
+/// Represents a repository for Software jobs. A repository for any job type can be created.
+#[derive(Debug, Default)]
+pub struct SoftwareJobs {
+    pub all: Vec<JobRef>,
+    pub date: HashMap<String, Vec<JobRef>>,
+    pub company: HashMap<String, Vec<JobRef>>,
+    pub location: HashMap<Location, Vec<JobRef>>,
+    pub skill: HashMap<Skill, Vec<JobRef>>,
+    pub level: HashMap<Level, Vec<JobRef>>,
+    pub tag: HashMap<String, Vec<JobRef>>,
+    pub country: HashMap<String, Vec<JobRef>>,
+    pub timings: ScrapeTimings,
+    /// When the jobs were scraped, or None for a repository built from imported jobs.
+    pub scraped_at: Option<DateTime<Local>>,
+    /// The URL and problem of each site that scraped without error but looks broken, e.g. one that
+    /// returned no jobs.
+    pub warnings: Vec<(String, Error)>,
+    /// How jobs matching more than one level were indexed.
+    pub level_policy: LevelPolicy,
+    /// Every job imported, before filtering and merging, so that the repository can be rebuilt with
+    /// other settings, e.g. other title keywords or duplicates unmerged, without scraping again.
+    pub scraped: Vec<Job>,
+}
+
+impl SoftwareJobs {
+    /// Initialises a repository for Software jobs. Sites that fail to scrape are reported to the
+    /// user (one message per site, or a single summary line in compact mode) and left out of the
+    /// repository.
+    pub fn init_repo(config: &Config) -> Self {
+        let (repo, failures) = Self::try_init_repo(config);
+        if config.compact_errors {
+            if let Some(summary) = summarize_scrape_errors(&failures) {
+                println!("{}", summary.bold().green());
+            }
+        } else {
+            failures
+                .iter()
+                .for_each(|(url, err)| print_scrape_error(url, err));
+        }
+        repo.warnings
+            .iter()
+            .for_each(|(url, err)| print_scrape_warning(url, err));
+        repo
+    }
+
+    /// Initialises a repository for Software jobs, returning it along with the URL and error of
+    /// each site that failed to scrape, so that callers can tell which sites succeeded. Unlike
+    /// [`SoftwareJobs::init_repo`], nothing is printed.
+    pub fn try_init_repo(config: &Config) -> (Self, Vec<(String, Error)>) {
+        let start = Instant::now();
+        let scraped_at = Local::now();
+        let mut failures = vec![];
+        let mut timings = ScrapeTimings::default();
+        let mut jobs = scrape_sites(config, &mut failures, &mut timings);
+        // the cache holds the last scrape, so shows which sites had jobs before this one
+        let had_jobs = cache::sites_with_jobs(config.output_path(CACHE_FILE)).unwrap_or_default();
+        let warnings = empty_sites(&timings.sites, &jobs, &failures, &had_jobs);
+        enrich_company_meta(jobs.iter_mut().flatten(), config);
+        let mut repo = Self::build(jobs, config);
+        timings.total = start.elapsed();
+        repo.timings = timings;
+        repo.scraped_at = Some(scraped_at);
+        repo.warnings = warnings;
+        (repo, failures)
+    }
+
+    /// Scrapes the site named `name` (see [`SITES`]) on its own, replacing its jobs with those
+    /// found. Returns the site's URL with the number of its jobs now in the repository, or the error
+    /// if the scrape failed, in which case its jobs are kept. Returns None if no site has the name.
+    pub fn refresh_site(
+        &mut self,
+        name: &str,
+        config: &Config,
+    ) -> Option<(String, Result<usize, Error>)> {
+        let ((url, result), elapsed) = scrape_timed(find_site(name)?, config.scrape_options());
+        let result = result.and_then(|jobs| {
+            if config.strict {
+                check_site_jobs(&jobs)?;
+            }
+            Ok(jobs)
+        });
+        match result {
+            Ok(mut jobs) => {
+                info!("Found {} jobs on {url}", jobs.len());
+                enrich_company_meta(&mut jobs, config);
+                self.replace_site_jobs(&url, jobs, config);
+                match self.timings.sites.iter_mut().find(|(site, _)| *site == url) {
+                    Some((_, time)) => *time = elapsed,
+                    None => self.timings.sites.push((url.clone(), elapsed)),
+                }
+                let count = self.all.iter().filter(|job| job.site == url).count();
+                Some((url, Ok(count)))
+            }
+            Err(err) => {
+                error!("Failed to scrape {url}: {err}");
+                Some((url, Err(err)))
+            }
+        }
+    }
+
+    /// Replaces the jobs from the site with the URL `url` with `jobs`, and rebuilds the repository
+    /// (see [`SoftwareJobs::rebuild`]).
+    pub(crate) fn replace_site_jobs(&mut self, url: &str, jobs: Vec<Job>, config: &Config) {
+        self.scraped.retain(|job| job.site != url);
+        self.scraped.extend(jobs);
+        self.rebuild(config);
+    }
+
+    /// Rebuilds the repository from its scraped jobs, filtered and merged as configured, keeping its
+    /// timings, scrape time and warnings. Nothing is scraped, so e.g. merged duplicates are split
+    /// again straight away when merging is turned off.
+    pub fn rebuild(&mut self, config: &Config) {
+        let repo = Self::build(vec![std::mem::take(&mut self.scraped)], config);
+        *self = Self {
+            timings: std::mem::take(&mut self.timings),
+            scraped_at: self.scraped_at,
+            warnings: std::mem::take(&mut self.warnings),
+            ..repo
+        };
+    }
+
+    /// Creates a repository from jobs cached by an earlier session (see [`crate::cache`]). The jobs
+    /// are cached as scraped, so are filtered and merged as configured now rather than as they were
+    /// when cached.
+    pub fn from_cache(jobs: Vec<Job>, scraped_at: DateTime<Local>, config: &Config) -> Self {
+        let mut repo = Self::build(vec![jobs], config);
+        repo.scraped_at = Some(scraped_at);
+        repo
+    }
+
+    /// Builds a repository from scraped jobs, one vector per site, keeping the jobs of interest and
+    /// merging duplicates if configured.
+    fn build(jobs: Vec<Vec<Job>>, config: &Config) -> Self {
+        let mut builder = SoftwareJobsBuilder::new()
+            .import(jobs)
+            // optional filter - in this case filter on engineering jobs, or per site if configured
+            .filter(|job| job.is_of_interest_with(&config.site_filters, &config.title_keywords))
+            .level_policy(config.level_policy);
+        if config.merge_duplicates {
+            builder = builder.dedup_by_title_company();
+        }
+        #[cfg(feature = "parallel")]
+        return builder.index_parallel();
+        #[cfg(not(feature = "parallel"))]
+        builder.index()
+    }
+
+    /// Explains how a job is indexed in the repository.
+    pub fn explain(&self, job: &Job) -> Explanation {
+        Explanation {
+            skills: job.skills(),
+            levels: job.levels(),
+            indexed_levels: job.indexed_levels(self.level_policy),
+            location: if job.is_remote() {
+                Location::Remote
+            } else {
+                Location::Onsite
+            },
+            country: job.location_info.country.clone(),
+        }
+    }
+
+    /// Returns true if a job's company matches the given normalized company name.
+    fn is_company(job: &Job, normalized_company: &str) -> bool {
+        normalize_name(&job.company) == normalized_company
+    }
+
+    /// Compares two companies' openings, counting their jobs by level and skill from the index maps.
+    /// Company names are matched loosely, and companies with no jobs are given zero counts.
+    pub fn compare_companies(&self, left: &str, right: &str) -> Comparison {
+        let (l, r) = (normalize_name(left), normalize_name(right));
+        let count = |jobs: Option<&Vec<JobRef>>, company: &str| {
+            jobs.map_or(0, |jobs| {
+                jobs.iter()
+                    .filter(|job| Self::is_company(job, company))
+                    .count()
+            })
+        };
+        let mut rows = vec![(
+            "Total".to_string(),
+            count(Some(&self.all), &l),
+            count(Some(&self.all), &r),
+        )];
+        for level in Level::ALL {
+            let jobs = self.level.get(&level);
+            rows.push((format!("{:?}", level), count(jobs, &l), count(jobs, &r)));
+        }
+        for skill in Skill::ALL {
+            let jobs = self.skill.get(&skill);
+            rows.push((format!("{:?}", skill), count(jobs, &l), count(jobs, &r)));
+        }
+        Comparison {
+            left: left.to_string(),
+            right: right.to_string(),
+            rows,
+        }
+    }
+
+    /// Compares the job markets for two skills from the skill index, counting their jobs by level and
+    /// location and finding their median salaries. A skill with no jobs is given zero counts.
+    pub fn compare_skills(&self, left: &Skill, right: &Skill) -> SkillComparison {
+        let empty = vec![];
+        let (l, r) = (
+            self.skill.get(left).unwrap_or(&empty),
+            self.skill.get(right).unwrap_or(&empty),
+        );
+        let count = |jobs: &[JobRef], matches: &dyn Fn(&JobRef) -> bool| {
+            jobs.iter().filter(|job| matches(job)).count()
+        };
+        let mut rows = vec![("Total".to_string(), l.len(), r.len())];
+        for level in Level::ALL {
+            let in_level = |job: &JobRef| job.levels().contains(&level);
+            rows.push((
+                format!("{:?}", level),
+                count(l, &in_level),
+                count(r, &in_level),
+            ));
+        }
+        let remote = |job: &JobRef| job.is_remote();
+        let onsite = |job: &JobRef| !job.is_remote();
+        rows.push(("Remote".to_string(), count(l, &remote), count(r, &remote)));
+        rows.push(("Onsite".to_string(), count(l, &onsite), count(r, &onsite)));
+        SkillComparison {
+            counts: Comparison {
+                left: format!("{:?}", left),
+                right: format!("{:?}", right),
+                rows,
+            },
+            median_salary: (median_salary(l.iter()), median_salary(r.iter())),
+        }
+    }
+
+    /// Lists the companies in the company index in the given order, with ties broken by job count
+    /// and then name.
+    pub fn companies(&self, order: CompanyOrder) -> Companies {
+        let rows = self
+            .company
+            .iter()
+            .map(|(company, jobs)| {
+                let latest = jobs.iter().filter_map(|job| job.parsed_date()).max();
+                (company.clone(), jobs.len(), latest)
+            })
+            .sorted_by(|a, b| match order {
+                CompanyOrder::Count => b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)),
+                CompanyOrder::Recent => Reverse(a.2)
+                    .cmp(&Reverse(b.2))
+                    .then_with(|| b.1.cmp(&a.1))
+                    .then_with(|| a.0.cmp(&b.0)),
+            })
+            .collect();
+        Companies { rows }
+    }
+
+    /// Groups the jobs sharing a dedup key, i.e. the same title and company, keeping only groups of
+    /// two or more. Largest groups are first, with ties broken by title. The repository is left as
+    /// is, so this also lists duplicates when they aren't merged.
+    pub fn duplicates(&self) -> Duplicates {
+        let groups = self
+            .all
+            .iter()
+            .into_group_map_by(|job| job.dedup_key())
+            .into_values()
+            .filter(|group| group.len() > 1)
+            .map(|group| group.into_iter().cloned().collect::<Vec<JobRef>>())
+            .sorted_by(|a, b| {
+                b.len()
+                    .cmp(&a.len())
+                    .then_with(|| a[0].dedup_key().cmp(&b[0].dedup_key()))
+            })
+            .collect();
+        Duplicates { groups }
+    }
+
+    /// Returns the jobs with an empty field, e.g. no remuneration, grouped by site, to help find
+    /// scrapers that drop a field.
+    pub fn missing(&self, field: Field) -> Missing {
+        let sites = self
+            .all
+            .iter()
+            .filter(|job| field.text(job).trim().is_empty())
+            .cloned()
+            .into_group_map_by(|job| site_name(job.site))
+            .into_iter()
+            .sorted_by(|a, b| b.1.len().cmp(&a.1.len()).then_with(|| a.0.cmp(&b.0)))
+            .collect();
+        Missing { field, sites }
+    }
+
+    /// Counts the jobs posted each ISO week. Jobs with an unknown date posted are counted separately.
+    pub fn timeline(&self) -> Timeline {
+        let (dated, unknown): (Vec<_>, Vec<_>) = self
+            .all
+            .iter()
+            .map(|job| job.parsed_date())
+            .partition(Option::is_some);
+        let weeks = dated
+            .into_iter()
+            .flatten()
+            .map(|date| (date.iso_week().year(), date.iso_week().week()))
+            .counts()
+            .into_iter()
+            .sorted()
+            .map(|((year, week), count)| (format!("{year}-W{week:02}"), count))
+            .collect();
+        Timeline {
+            weeks,
+            unknown: unknown.len(),
+        }
+    }
+
+    /// Counts the jobs in each index: the total, the number of companies, and the jobs per skill,
+    /// level and location. Every variant is listed, including those with no jobs.
+    pub fn counts(&self) -> Counts {
+        fn count<K: Eq + Hash>(index: &HashMap<K, Vec<JobRef>>, key: &K) -> usize {
+            index.get(key).map_or(0, |jobs| jobs.len())
+        }
+        Counts {
+            jobs: self.all.len(),
+            companies: self.company.len(),
+            skills: Skill::ALL
+                .into_iter()
+                .map(|skill| (skill.clone(), count(&self.skill, &skill)))
+                .collect(),
+            levels: Level::ALL
+                .into_iter()
+                .map(|level| (level.clone(), count(&self.level, &level)))
+                .collect(),
+            locations: [Location::Remote, Location::Onsite]
+                .into_iter()
+                .map(|location| (location.clone(), count(&self.location, &location)))
+                .collect(),
+        }
+    }
+
+    /// Summarizes where the jobs are, from the location index and each job's parsed location.
+    pub fn location_stats(&self) -> LocationStats {
+        let is_hybrid = |job: &&JobRef| job.location.to_lowercase().contains("hybrid");
+        let count = |location: Location| {
+            self.location
+                .get(&location)
+                .map_or(0, |jobs| jobs.iter().filter(|job| !is_hybrid(job)).count())
+        };
+        let top = |names: Vec<&str>| {
+            names
+                .into_iter()
+                .counts()
+                .into_iter()
+                .map(|(name, count)| (name.to_string(), count))
+                .sorted_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)))
+                .take(TOP_LOCATIONS)
+                .collect()
+        };
+        LocationStats {
+            remote: count(Location::Remote),
+            onsite: count(Location::Onsite),
+            hybrid: self.all.iter().filter(is_hybrid).count(),
+            countries: top(self
+                .all
+                .iter()
+                .map(|job| job.location_info.country.as_deref().unwrap_or(UNKNOWN))
+                .collect()),
+            cities: top(self
+                .all
+                .iter()
+                .map(|job| job.location_info.city.as_deref().unwrap_or(UNKNOWN))
+                .collect()),
+        }
+    }
+
+    /// Returns the jobs in the given location bucket, most recently posted first.
+    pub fn fetch_location(&self, location: &Location) -> Vec<JobRef> {
+        self.location
+            .get(location)
+            .map(|jobs| {
+                jobs.iter()
+                    .sorted_by(|a, b| b.date_posted.cmp(&a.date_posted))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Returns the jobs offering the named benefit (see [`benefit_name`]), most recently posted
+    /// first.
+    pub fn fetch_benefit(&self, benefit: &str) -> Vec<JobRef> {
+        self.all
+            .iter()
+            .filter(|job| job.benefits.iter().any(|b| b == benefit))
+            .sorted_by(|a, b| b.date_posted.cmp(&a.date_posted))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the `limit` jobs that best suit the profile in the config, highest scoring first (see
+    /// [`recommendation_score`]), with their scores. Ties are broken by the most recently posted.
+    pub fn recommend(&self, config: &Config, limit: usize) -> Vec<(u32, JobRef)> {
+        let today = Local::now().date_naive();
+        self.all
+            .iter()
+            .filter_map(|job| Some((recommendation_score(job, config, today)?, job.clone())))
+            .sorted_by(|(a_score, a), (b_score, b)| {
+                b_score
+                    .cmp(a_score)
+                    .then_with(|| b.date_posted.cmp(&a.date_posted))
+            })
+            .take(limit)
+            .collect()
+    }
+
+    /// Returns the jobs posted from `since` until `until`, inclusive, or from `since` on if `until`
+    /// is None, newest first. Jobs whose date posted can't be parsed are omitted.
+    pub fn fetch_posted_between(&self, since: NaiveDate, until: Option<NaiveDate>) -> Vec<JobRef> {
+        self.all
+            .iter()
+            .filter(|job| {
+                job.parsed_date()
+                    .is_some_and(|date| date >= since && until.is_none_or(|until| date <= until))
+            })
+            .sorted_by(|a, b| b.date_posted.cmp(&a.date_posted))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the fully remote jobs (see [`is_fully_remote`]), most recently posted first.
+    pub fn fetch_fully_remote(&self) -> Vec<JobRef> {
+        self.all
+            .iter()
+            .filter(|job| job.is_fully_remote())
+            .sorted_by(|a, b| b.date_posted.cmp(&a.date_posted))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the jobs that disclose their pay, i.e. with a remuneration given or parsed, most
+    /// recently posted first.
+    pub fn fetch_has_salary(&self) -> Vec<JobRef> {
+        self.all
+            .iter()
+            .filter(|job| {
+                let given = job.remuneration.trim();
+                (!given.is_empty() && given != NOT_AVAILABLE)
+                    || job.remuneration_min.is_some()
+                    || job.remuneration_max.is_some()
+            })
+            .sorted_by(|a, b| b.date_posted.cmp(&a.date_posted))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the jobs paying at least the given amount (see [`Job::pays_at_least`]), most recently
+    /// posted first. Jobs with an unknown salary are included only if `include_unknown` is set.
+    pub fn fetch_salary_above(&self, amount: u32, include_unknown: bool) -> Vec<JobRef> {
+        self.all
+            .iter()
+            .filter(|job| job.pays_at_least(amount).unwrap_or(include_unknown))
+            .sorted_by(|a, b| b.date_posted.cmp(&a.date_posted))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the jobs of every company in the company index whose name matches the given name,
+    /// case-insensitively.
+    pub fn fetch_company(&self, name: &str) -> Vec<JobRef> {
+        let name = name.trim().to_lowercase();
+        self.company
+            .iter()
+            .filter(|(company, _)| company.to_lowercase() == name)
+            .flat_map(|(_, jobs)| jobs)
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the jobs in the given level bucket.
+    pub fn fetch_level(&self, level: &Level) -> Vec<JobRef> {
+        self.level.get(level).cloned().unwrap_or_default()
+    }
+
+    /// Returns the union of the given skill buckets, without duplicates for jobs in more than one.
+    pub fn fetch_skills(&self, skills: &[Skill]) -> Vec<JobRef> {
+        skills
+            .iter()
+            .filter_map(|skill| self.skill.get(skill))
+            .flatten()
+            .unique_by(|job| Rc::as_ptr(job))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the jobs tagged with the given tag, from the tag index. Tags are matched
+    /// case-insensitively.
+    pub fn fetch_tag(&self, tag: &str) -> Vec<JobRef> {
+        self.tag
+            .get(&tag.trim().to_lowercase())
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Returns the jobs in the given skill bucket that are also tagged with every one of the given
+    /// tags, e.g. backend jobs tagged "rust". Tags are matched case-insensitively.
+    pub fn fetch_skill_with_tags(&self, skill: &Skill, tags: &[&str]) -> Vec<JobRef> {
+        let tagged = |job: &JobRef, tag: &str| {
+            self.tag
+                .get(&tag.trim().to_lowercase())
+                .is_some_and(|jobs| jobs.iter().any(|j| Rc::ptr_eq(j, job)))
+        };
+        self.skill
+            .get(skill)
+            .map(|jobs| {
+                jobs.iter()
+                    .filter(|job| tags.iter().all(|tag| tagged(job, tag)))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Returns the jobs relevant to a search query with their scores (see [`relevance`]), most
+    /// relevant first. Jobs scoring zero are omitted.
+    pub fn search(&self, query: &str) -> Vec<(u32, JobRef)> {
+        self.all
+            .iter()
+            .map(|job| (relevance(job, query), job.clone()))
+            .filter(|(score, _)| *score > 0)
+            .sorted_by_key(|(score, _)| Reverse(*score))
+            .collect()
+    }
+
+    /// Returns the jobs whose title or any tag contains the keyword, ignoring case, most recently
+    /// posted first. Unlike [`SoftwareJobs::search`], a keyword of several words is matched as one
+    /// phrase, and may match part of a word.
+    pub fn search_keyword(&self, keyword: &str) -> Vec<JobRef> {
+        let keyword = keyword.trim().to_lowercase();
+        self.all
+            .iter()
+            .filter(|job| job.title_contains(&keyword) || job.tags_contain(&keyword))
+            .sorted_by(|a, b| b.date_posted.cmp(&a.date_posted))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the jobs matching every one of the given stack tokens, e.g. "rust" and "tokio". A token
+    /// matches a job tagged with it or with it as a word in its title, case-insensitively.
+    pub fn fetch_stack(&self, tokens: &[&str]) -> Vec<JobRef> {
+        let has = |job: &JobRef, token: &str| {
+            job.tags
+                .iter()
+                .any(|tag| tag.trim().eq_ignore_ascii_case(token))
+                || job
+                    .title
+                    .to_lowercase()
+                    .split(|c: char| !c.is_alphanumeric())
+                    .any(|word| word == token)
+        };
+        let tokens = tokens
+            .iter()
+            .map(|token| token.trim().to_lowercase())
+            .filter(|token| !token.is_empty())
+            .collect::<Vec<String>>();
+        self.all
+            .iter()
+            .filter(|job| !tokens.is_empty() && tokens.iter().all(|token| has(job, token)))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns jobs similar to the given job, most similar first. The job itself and its duplicates
+    /// on other sites are excluded, as are jobs with nothing in common with it.
+    pub fn fetch_similar(&self, job: &Job) -> Vec<JobRef> {
+        self.all
+            .iter()
+            .filter(|other| other.dedup_key() != job.dedup_key())
+            .map(|other| (similarity(job, other), other))
+            .filter(|(score, _)| *score > 0)
+            .sorted_by_key(|(score, _)| Reverse(*score))
+            .map(|(_, other)| other.clone())
+            .collect()
+    }
+
+    /// Returns the remote jobs that can be worked from the given region, i.e. those restricted to it
+    /// and those with no stated restriction.
+    pub fn fetch_remote_region(&self, region: Region) -> Vec<JobRef> {
+        self.location
+            .get(&Location::Remote)
+            .map(|jobs| {
+                jobs.iter()
+                    .filter(|job| job.regions.is_empty() || job.regions.contains(&region))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Returns the jobs in the given country, which can be given by name or alias, e.g. "USA".
+    pub fn fetch_country(&self, country: &str) -> Vec<JobRef> {
+        let country = country_name(country).unwrap_or(country.trim());
+        self.country
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(country))
+            .map(|(_, jobs)| jobs.clone())
+            .unwrap_or_default()
+    }
+
+    /// Returns the jobs with an application deadline, soonest closing first.
+    pub fn fetch_closing_soon(&self) -> Vec<JobRef> {
+        self.all
+            .iter()
+            .filter(|job| job.apply_by.is_some())
+            .sorted_by_key(|job| job.apply_by)
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the jobs `fetch_jobs` returns in a random order determined by the seed, so that the
+    /// same seed always gives the same order.
+    pub fn fetch_jobs_shuffled(&mut self, config: &Config, seed: u64) -> Vec<JobRef> {
+        let mut jobs = self.fetch_jobs(config);
+        jobs.shuffle(&mut StdRng::seed_from_u64(seed));
+        jobs
+    }
+
+    /// Returns all jobs ordered by descending boost score (see [`score`]), then newest first by date
+    /// posted (jobs with an unknown date last) and by company name. Likely expired jobs are omitted
+    /// if the config excludes them.
+    pub fn fetch_jobs(&mut self, config: &Config) -> Vec<JobRef> {
+        self.all
+            .sort_by_key(|job| (Reverse(job.date_posted.clone()), job.company.clone()));
+        let exclude_expired_after = config.exclude_expired_after();
+        let mut jobs = self
+            .all
+            .iter()
+            .filter(|job| exclude_expired_after.is_none_or(|days| !job.is_likely_expired(days)))
+            .cloned()
+            .collect::<Vec<JobRef>>();
+        if !config.boosts.is_empty() {
+            jobs.sort_by_cached_key(|job| Reverse(score(job, &config.boosts)));
+        }
+        jobs
+    }
+}
+
+/// Represents a site's URL and its scraped jobs, or the error if the scrape failed.
+pub(crate) type SiteResult = (String, Result<Vec<Job>, Error>);
+
+/// Scrapes every site concurrently, sending each site's result on the returned channel as soon as
+/// that site finishes, so that jobs can be output before the slowest site is done. Requests are
+/// made, and the number of sites scraped at once limited, as the config says.
+pub(crate) fn scrape_streaming(config: &Config) -> Receiver<SiteResult> {
+    let (tx, rx) = mpsc::channel();
+    let options = config.scrape_options();
+    let limit = scrape_limit(config);
+    for site in SITES {
+        send_scrape(&tx, site, options, limit.clone());
+    }
+    rx
+}
+
+/// Returns the site named `name`, one of the names in [`SITES`] ignoring case, spaces, dashes and
+/// underscores, or None if no site has the name.
+fn find_site(name: &str) -> Option<SiteEntry> {
+    let name = name
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '-' | '_'))
+        .collect::<String>()
+        .to_lowercase();
+    SITES.into_iter().find(|site| site.name == name)
+}
+
+/// Scrapes a site, returning its result and how long the scrape took.
+fn scrape_timed(site: SiteEntry, options: ScrapeOptions) -> (SiteResult, Duration) {
+    let start = Instant::now();
+    let url = (site.url)();
+    info!("Scraping {url}");
+    let result = (site.scrape)(options);
+    ((url.to_string(), result), start.elapsed())
+}
+
+/// Returns the limit on how many scraper threads scrape at once shared by the threads of one
+/// scrape, or None if the config sets no limit.
+fn scrape_limit(config: &Config) -> Option<Arc<ScrapeLimit>> {
+    config
+        .max_concurrent_scrapes
+        .map(|limit| Arc::new(ScrapeLimit::new(limit)))
+}
+
+/// Scrapes a site in a new thread once the limit, if any, has a permit free, sending its result on
+/// the channel when done.
+fn send_scrape(
+    tx: &Sender<SiteResult>,
+    site: SiteEntry,
+    options: ScrapeOptions,
+    limit: Option<Arc<ScrapeLimit>>,
+) {
+    let tx = tx.clone();
+    thread::spawn(move || {
+        let _permit = limit.as_deref().map(ScrapeLimit::acquire);
+        let (result, _) = scrape_timed(site, options);
+        // the receiver may have hung up, in which case the result isn't needed
+        tx.send(result).ok();
+    });
+}
+
+/// Scrapes every site, each in a new thread, at most `config.max_concurrent_scrapes` at once,
+/// returning each site's jobs. Failures and timings are recorded as for `join_site`.
+#[cfg(not(feature = "async"))]
+fn scrape_sites(
+    config: &Config,
+    failures: &mut Vec<(String, Error)>,
+    timings: &mut ScrapeTimings,
+) -> Vec<Vec<Job>> {
+    let (options, limit) = (config.scrape_options(), scrape_limit(config));
+    SITES
+        .map(|site| spawn_scrape(site, options, limit.clone()))
+        .into_iter()
+        .map(|handle| join_site(handle, config.strict, failures, timings))
+        .collect()
+}
+
+/// Scrapes every site concurrently on an async runtime, at most `config.max_concurrent_scrapes` at
+/// once, returning each site's jobs. Failures and timings are recorded as for `join_site`. The
+/// runtime is created for the scrape; a runtime can't be blocked on from within another, so if this
+/// is called from a task the scrape runs its runtime in a new thread instead.
+#[cfg(feature = "async")]
+fn scrape_sites(
+    config: &Config,
+    failures: &mut Vec<(String, Error)>,
+    timings: &mut ScrapeTimings,
+) -> Vec<Vec<Job>> {
+    let options = config.scrape_options();
+    let limit = config
+        .max_concurrent_scrapes
+        .map(|limit| Arc::new(Semaphore::new(limit)));
+    let scrape = || {
+        let runtime = tokio::runtime::Runtime::new().expect("Error creating the async runtime");
+        runtime.block_on(async {
+            let handles =
+                SITES.map(|site| tokio::spawn(scrape_timed_async(site, options, limit.clone())));
+            let mut results = vec![];
+            for handle in handles {
+                results.push(handle.await.expect(THREAD_ERROR));
+            }
+            results
+        })
+    };
+    let results = match tokio::runtime::Handle::try_current() {
+        Ok(_) => thread::scope(|scope| scope.spawn(scrape).join().expect(THREAD_ERROR)),
+        Err(_) => scrape(),
+    };
+    results
+        .into_iter()
+        .map(|result| site_jobs(result, config.strict, failures, timings))
+        .collect()
+}
+
+/// Scrapes a site with async requests once the limit, if any, has a permit free, timing how long the
+/// scrape takes.
+#[cfg(feature = "async")]
+async fn scrape_timed_async(
+    site: SiteEntry,
+    options: ScrapeOptions,
+    limit: Option<Arc<Semaphore>>,
+) -> (SiteResult, Duration) {
+    let _permit = match &limit {
+        Some(limit) => Some(limit.acquire().await.expect(THREAD_ERROR)),
+        None => None,
+    };
+    let start = Instant::now();
+    let url = (site.url)();
+    info!("Scraping {url}");
+    let result = (site.scrape_async)(options).await;
+    ((url.to_string(), result), start.elapsed())
+}
+
+/// Scrapes a site in a new thread once the limit, if any, has a permit free, timing how long the
+/// scrape takes.
+#[cfg(any(test, not(feature = "async")))]
+fn spawn_scrape(
+    site: SiteEntry,
+    options: ScrapeOptions,
+    limit: Option<Arc<ScrapeLimit>>,
+) -> thread::JoinHandle<(SiteResult, Duration)> {
+    thread::spawn(move || {
+        // the time waiting for a permit isn't counted as time scraping
+        let _permit = limit.as_deref().map(ScrapeLimit::acquire);
+        scrape_timed(site, options)
+    })
+}
+
+/// Checks a site's scraped jobs for problems that are tolerated unless in strict mode: no jobs at
+/// all, which likely means the site's selectors no longer match, or a job with no title, company or
+/// date posted.
+pub(crate) fn check_site_jobs(jobs: &[Job]) -> Result<(), Error> {
+    if jobs.is_empty() {
+        Err(Error::EmptyResult)?;
+    }
+    for job in jobs {
+        if job.title.trim().is_empty() {
+            Err(Error::MissingField("title"))?;
+        } else if job.company.trim().is_empty() {
+            Err(Error::MissingField("company"))?;
+        } else if job.date_posted.is_empty() {
+            Err(Error::MissingField("date posted"))?;
+        }
+    }
+    Ok(())
+}
+
+/// Returns an [`Error::EmptyResult`] warning for each site that had jobs before, i.e. is one of
+/// `had_jobs`, and scraped without failing but returned none, which usually means its selectors no
+/// longer match. A site that never had jobs may just have none today, so isn't warned about.
+/// `sites` and `jobs` are in the order the sites were joined.
+fn empty_sites(
+    sites: &[(String, Duration)],
+    jobs: &[Vec<Job>],
+    failures: &[(String, Error)],
+    had_jobs: &HashSet<&str>,
+) -> Vec<(String, Error)> {
+    sites
+        .iter()
+        .zip(jobs)
+        .filter(|((url, _), jobs)| {
+            jobs.is_empty()
+                && had_jobs.contains(url.as_str())
+                && !failures.iter().any(|(u, _)| u == url)
+        })
+        .map(|((url, _), _)| {
+            warn!("{url} returned no jobs");
+            (url.clone(), Error::EmptyResult)
+        })
+        .collect()
+}
+
+/// Joins a scraper thread and records how long the scrape took, returning the site's jobs, or
+/// recording the site's URL and error and returning no jobs if the scrape failed. In strict mode, a
+/// site whose jobs fail `check_site_jobs` fails too.
+#[cfg(any(test, not(feature = "async")))]
+fn join_site(
+    handle: thread::JoinHandle<(SiteResult, Duration)>,
+    strict: bool,
+    failures: &mut Vec<(String, Error)>,
+    timings: &mut ScrapeTimings,
+) -> Vec<Job> {
+    site_jobs(
+        handle.join().expect(THREAD_ERROR),
+        strict,
+        failures,
+        timings,
+    )
+}
+
+/// Fills in the company logo and website of the jobs that will be kept in the repository, if
+/// configured (see [`crate::company`]). Jobs the repository filters out are not looked up. The
+/// metadata found is cached in the output directory, so each domain is only requested once across
+/// refreshes and sessions.
+fn enrich_company_meta<'a, I: IntoIterator<Item = &'a mut Job>>(jobs: I, config: &Config) {
+    if !config.fetch_company_meta {
+        return;
+    }
+    let path = config.output_path(COMPANY_META_FILE);
+    let mut cache = MetadataCache::load(&path).unwrap_or_else(|err| {
+        warn!("Could not read company metadata, looking it up again: {err}");
+        MetadataCache::default()
+    });
+    let options = config.scrape_options();
+    let jobs = jobs
+        .into_iter()
+        .filter(|job| job.is_of_interest_with(&config.site_filters, &config.title_keywords));
+    cache.enrich(jobs, |domain| fetch_company_meta(domain, options));
+    if let Err(err) = cache.save(&path) {
+        warn!("Could not cache company metadata: {err}");
+    }
+}
+
+/// Returns a site's jobs from the result of scraping it and how long that took, recording the
+/// time taken, and the error if the scrape failed, in which case there are no jobs.
+fn site_jobs(
+    ((url, result), elapsed): (SiteResult, Duration),
+    strict: bool,
+    failures: &mut Vec<(String, Error)>,
+    timings: &mut ScrapeTimings,
+) -> Vec<Job> {
+    timings.sites.push((url.clone(), elapsed));
+    let result = result.and_then(|jobs| {
+        if strict {
+            check_site_jobs(&jobs)?;
+        }
+        Ok(jobs)
+    });
+    match result {
+        Ok(jobs) => {
+            info!(
+                "Found {} jobs on {url} in {:.2}s",
+                jobs.len(),
+                elapsed.as_secs_f64()
+            );
+            jobs
+        }
+        Err(err) => {
+            error!("Failed to scrape {url}: {err}");
+            failures.push((url, err));
+            vec![]
+        }
+    }
+}
+
+/// Provides an indexer function to the JobRef type.
+trait SoftwareJobsIndexer {
+    /// Adds a job reference to an index map for type T.
+    fn index_by<T>(&self, t: T, map: &mut HashMap<T, Vec<JobRef>>)
+    where
+        T: Sized + Eq + Hash;
+}
+
+impl SoftwareJobsIndexer for JobRef {
+    fn index_by<T>(&self, t: T, map: &mut HashMap<T, Vec<JobRef>>)
+    where
+        T: Sized + Eq + Hash,
+    {
+        map.entry(t)
+            .and_modify(|vec| vec.push(self.clone()))
+            .or_insert(vec![self.clone()]);
+    }
+}
+
+/// Represents a repository builder for Software jobs. A repository builder for any job type can be
+/// created.
+pub struct SoftwareJobsBuilder(SoftwareJobs);
+
+impl Builder for SoftwareJobsBuilder {
+    type Output = SoftwareJobs;
+
+    fn new() -> Self {
+        Self(Default::default())
+    }
+
+    fn import(mut self, jobs: Vec<Vec<Job>>) -> Self {
+        // allow duplicate job posts if they are from different sites - user can choose which site
+        // to apply from
+        let today = Local::now().date_naive();
+        for vec in jobs {
+            for mut job in vec {
+                job.derive_fields(today);
+                self.0.scraped.push(job.clone());
+                self.0.all.push(Rc::new(job))
+            }
+        }
+        self
+    }
+
+    fn filter<F>(mut self, condition: F) -> Self
+    where
+        F: Fn(&Job) -> bool,
+    {
+        self.0.all.retain(|job| condition(job));
+        self
+    }
+
+    fn dedup_by_title_company(mut self) -> Self {
+        let mut merged: Vec<JobRef> = vec![];
+        let mut positions: HashMap<String, usize> = HashMap::new();
+        for job in self.0.all.drain(..) {
+            match positions.get(&job.dedup_key()) {
+                Some(&i) => Rc::make_mut(&mut merged[i]).merge(&job),
+                None => {
+                    positions.insert(job.dedup_key(), merged.len());
+                    merged.push(job);
+                }
+            }
+        }
+        self.0.all = merged;
+        self
+    }
+
+    fn index(self) -> Self::Output {
+        let policy = self.0.level_policy;
+        self.index_with(|jobs| jobs.iter().map(|job| IndexKeys::new(job, policy)).collect())
+    }
+}
+
+impl SoftwareJobsBuilder {
+    /// Sets how jobs matching more than one level are indexed. Defaults to the most senior level.
+    pub fn level_policy(mut self, policy: LevelPolicy) -> Self {
+        self.0.level_policy = policy;
+        self
+    }
+
+    /// Indexes the jobs like `index`, but computes each job's index keys in parallel. The keys are
+    /// collected in job order and merged sequentially, so the index buckets are identical to those
+    /// built by `index`.
+    #[cfg(feature = "parallel")]
+    pub fn index_parallel(self) -> SoftwareJobs {
+        let policy = self.0.level_policy;
+        self.index_with(|jobs| {
+            // Rc isn't Send, so the jobs are shared with the worker threads by reference
+            let jobs = jobs.iter().map(|job| job.as_ref()).collect::<Vec<&Job>>();
+            jobs.par_iter()
+                .map(|job| IndexKeys::new(job, policy))
+                .collect()
+        })
+    }
+
+    /// Indexes the jobs using the keys computed for each job by `keys`, which must return one
+    /// `IndexKeys` per job, in job order.
+    fn index_with<F>(mut self, keys: F) -> SoftwareJobs
+    where
+        F: FnOnce(&[JobRef]) -> Vec<IndexKeys>,
+    {
+        let keys = keys(&self.0.all);
+        for (job, keys) in self.0.all.iter().zip(keys) {
+            // index by attribute
+            job.index_by(keys.date, &mut self.0.date);
+            job.index_by(keys.company, &mut self.0.company);
+            for tag in keys.tags {
+                job.index_by(tag, &mut self.0.tag);
+            }
+            job.index_by(keys.location, &mut self.0.location);
+            if let Some(country) = keys.country {
+                job.index_by(country, &mut self.0.country);
+            }
+            for skill in keys.skills {
+                job.index_by(skill, &mut self.0.skill);
+            }
+            for level in keys.levels {
+                job.index_by(level, &mut self.0.level);
+            }
+        }
+        self.0
+    }
+}
+
+/// Represents the keys a job is indexed by. Computing these is the costly part of indexing, so it
+/// is kept separate from inserting into the index maps.
+struct IndexKeys {
+    date: String,
+    company: String,
+    tags: Vec<String>,
+    location: Location,
+    country: Option<String>,
+    skills: Vec<Skill>,
+    levels: Vec<Level>,
+}
+
+impl IndexKeys {
+    fn new(job: &Job, level_policy: LevelPolicy) -> Self {
+        Self {
+            date: job.date_posted.clone(),
+            company: job.company.clone(),
+            // tags are lowercased so that e.g. "Rust" and "rust" share a bucket
+            tags: job
+                .tags
+                .iter()
+                .map(|tag| tag.trim().to_lowercase())
+                .filter(|tag| !tag.is_empty())
+                .unique()
+                .collect(),
+            location: if job.is_remote() {
+                Location::Remote
+            } else {
+                Location::Onsite
+            },
+            country: job.location_info.country.clone(),
+            skills: job.skills(),
+            levels: job.indexed_levels(level_policy),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use chrono::{Duration, Local, NaiveDate};
+    use itertools::Itertools;
+
+    use crate::config::Config;
+    use crate::scraper::{Error, ScrapeOptions, Scraper, SiteEntry};
+    use crate::site::{DateSource, Site, REMOTE_OK_URL};
+
+    use super::{
+        benefit_name, empty_sites, find_site, is_fully_remote, is_remote, join_site, median_salary,
+        parse_apply_by, parse_regions, parse_salary, recommendation_score, relevance,
+        remote_region, similarity, spawn_scrape, Builder, Companies, CompanyOrder, Job, JobOrder,
+        JobRef, Level, LevelPolicy, Listing, Location, LocationInfo, LocationStats, Region,
+        ScrapeTimings, SiteResult, Skill, SoftwareJobs, SoftwareJobsBuilder, DATE_FORMAT,
+    };
+
+    #[test]
+    fn test_software_jobs_repository() {
+        let repo = SoftwareJobsBuilder::new()
+            .import(vec![
+                vec![
+                    Job {
+                        title: "Engineering Manager".into(),
+                        company: "Company_2".into(),
+                        date_posted: "2022-07-28".into(),
+                        location: "Remote".into(),
+                        remuneration: "$165k - $200k".into(),
+                        tags: vec!["tag1".into(), "tag2".into()],
+                        apply: "https://site1.com".into(),
+                        site: "https://site1.com",
+                        ..Default::default()
+                    },
+                    Job {
+                        title: "Senior Marketer".into(),
+                        company: "Company_3".into(),
+                        date_posted: "2022-07-29".into(),
+                        location: "Remote".into(),
+                        remuneration: "$165k - $200k".into(),
+                        tags: vec!["tag1".into(), "tag2".into()],
+                        apply: "https://site1.com".into(),
+                        site: "https://site1.com",
+                        ..Default::default()
                     },
                     Job {
                         title: "Platform Engineer".into(),
@@ -360,58 +2812,1536 @@ mod tests {
                         tags: vec!["tag1".into(), "tag2".into()],
                         apply: "https://site1.com".into(),
                         site: "https://site1.com",
+                        ..Default::default()
+                    },
+                ],
+                vec![
+                    Job {
+                        title: "Junior Fullstack Developer".into(),
+                        company: "Company_1".into(),
+                        date_posted: "2022-07-27".into(),
+                        location: "Remote".into(),
+                        remuneration: "$165k - $200k".into(),
+                        tags: vec!["tag1".into(), "tag2".into()],
+                        apply: "https://site2.com".into(),
+                        site: "https://site2.com",
+                        ..Default::default()
+                    },
+                    Job {
+                        title: "Senior Backend Engineer".into(),
+                        company: "Company_1".into(),
+                        date_posted: "2022-07-27".into(),
+                        location: "Onsite".into(),
+                        remuneration: "$165k - $200k".into(),
+                        tags: vec!["tag1".into(), "tag2".into()],
+                        apply: "https://site2.com".into(),
+                        site: "https://site2.com",
+                        ..Default::default()
+                    },
+                    Job {
+                        title: "Snr Backend Engineer".into(),
+                        company: "Company_1".into(),
+                        date_posted: "2022-07-27".into(),
+                        location: "Onsite".into(),
+                        remuneration: "$165k - $200k".into(),
+                        tags: vec!["tag1".into(), "tag2".into()],
+                        apply: "https://site2.com".into(),
+                        site: "https://site2.com",
+                        ..Default::default()
                     },
                 ],
+            ])
+            .filter(|job| {
+                job.title_contains_any(vec!["developer", "engineer", "engineering", "technical"])
+            }) // optional filter - in this case filter on software jobs
+            .index();
+
+        // check index map keys
+        assert_eq!(repo.all.len(), 5);
+        assert_eq!(repo.date.len(), 3);
+        assert_eq!(repo.company.len(), 3);
+        assert_eq!(repo.location.len(), 2);
+        assert_eq!(repo.skill.len(), 3);
+        assert_eq!(repo.level.len(), 3);
+
+        // check index map values
+        assert_eq!(repo.location.get(&Location::Remote).unwrap().len(), 3);
+        assert_eq!(repo.skill.get(&Skill::Backend).unwrap().len(), 2);
+        assert_eq!(repo.skill.get(&Skill::DevOps).unwrap().len(), 1);
+        assert_eq!(repo.level.get(&Level::Senior).unwrap().len(), 2);
+    }
+
+    /// Creates a remote test job with the given title, company and date posted.
+    fn job(title: &str, company: &str, date_posted: &str) -> Job {
+        Job {
+            title: title.into(),
+            company: company.into(),
+            date_posted: date_posted.into(),
+            location: "Remote".into(),
+            remuneration: "".into(),
+            tags: vec![],
+            apply: "https://site1.com".into(),
+            site: "https://site1.com",
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_likely_expired_jobs() {
+        let days_ago = |days| {
+            (Local::now() - Duration::days(days))
+                .format(DATE_FORMAT)
+                .to_string()
+        };
+        let mut repo = SoftwareJobsBuilder::new()
+            .import(vec![vec![
+                job("Old Backend Engineer", "Company_1", &days_ago(120)),
+                job("New Backend Engineer", "Company_1", &days_ago(3)),
+            ]])
+            .index();
+
+        let old = repo
+            .all
+            .iter()
+            .find(|j| j.title.starts_with("Old"))
+            .unwrap();
+        assert!(old.is_likely_expired(90));
+        assert!(Listing::new(old, 90).expired);
+        assert!(!repo
+            .all
+            .iter()
+            .find(|j| j.title.starts_with("New"))
+            .unwrap()
+            .is_likely_expired(90));
+
+        assert_eq!(repo.fetch_jobs(&Config::default()).len(), 2);
+        let jobs = repo.fetch_jobs(&Config {
+            exclude_expired: true,
+            ..Default::default()
+        });
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].title, "New Backend Engineer");
+    }
+
+    #[test]
+    fn test_compare_companies() {
+        let repo = SoftwareJobsBuilder::new()
+            .import(vec![vec![
+                job("Senior Backend Engineer", "Acme, Inc.", "2022-07-27"),
+                job("Junior Frontend Developer", "Acme Inc", "2022-07-28"),
+                job("Senior Frontend Engineer", "acme inc", "2022-07-28"),
+                job("Staff Platform Engineer", "Company_2", "2022-07-29"),
+            ]])
+            .index();
+
+        let comparison = repo.compare_companies("ACME inc", "Company_2");
+        assert_eq!(comparison.get("Total"), Some((3, 1)));
+        assert_eq!(comparison.get("Senior"), Some((2, 0)));
+        assert_eq!(comparison.get("Junior"), Some((1, 0)));
+        assert_eq!(comparison.get("Staff"), Some((0, 1)));
+        assert_eq!(comparison.get("Frontend"), Some((2, 0)));
+        assert_eq!(comparison.get("Backend"), Some((1, 0)));
+        assert_eq!(comparison.get("DevOps"), Some((0, 1)));
+
+        let comparison = repo.compare_companies("Acme Inc", "Missing Co");
+        assert_eq!(comparison.get("Total"), Some((3, 0)));
+        assert!(comparison.rows.iter().all(|(_, _, right)| *right == 0));
+    }
+
+    #[test]
+    fn test_fetch_skill_with_tags() {
+        let tagged = |title: &str, tags: &[&str]| Job {
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            ..job(title, "Company_1", "2022-07-27")
+        };
+        let repo = SoftwareJobsBuilder::new()
+            .import(vec![vec![
+                tagged("Backend Engineer", &["Rust", "Remote"]),
+                tagged("Senior Backend Engineer", &["Go"]),
+                tagged("Frontend Engineer", &["rust"]),
+            ]])
+            .index();
+
+        assert_eq!(repo.tag.get("rust").unwrap().len(), 2);
+        assert_eq!(repo.fetch_tag(" RUST ").len(), 2);
+        assert!(repo.fetch_tag("solidity").is_empty());
+        let jobs = repo.fetch_skill_with_tags(&"backend".parse().unwrap(), &["rust"]);
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].title, "Backend Engineer");
+        assert!(repo
+            .fetch_skill_with_tags(&Skill::Backend, &["rust", "go"])
+            .is_empty());
+    }
+
+    #[test]
+    fn test_merge_duplicates_unions_tags() {
+        let repo = SoftwareJobsBuilder::new()
+            .import(vec![
+                vec![Job {
+                    tags: vec!["Rust".into(), "DeFi".into()],
+                    remuneration: "".into(),
+                    ..job("Senior Backend Engineer", "Acme", "2022-07-27")
+                }],
+                vec![Job {
+                    tags: vec!["rust".into(), "Remote".into()],
+                    remuneration: "$120k - $150k".into(),
+                    site: "https://site2.com",
+                    ..job("Senior Backend  Engineer", "ACME", "2022-07-28")
+                }],
+                vec![job("Frontend Engineer", "Acme", "2022-07-28")],
+            ])
+            .dedup_by_title_company()
+            .index();
+
+        assert_eq!(repo.all.len(), 2);
+        let merged = &repo.all[0];
+        assert_eq!(merged.tags, vec!["Rust", "DeFi", "Remote"]);
+        assert_eq!(merged.remuneration, "$120k - $150k");
+        assert_eq!(merged.site, "https://site1.com");
+        assert_eq!(merged.also_on, vec!["https://site2.com"]);
+        assert!(repo.all[1].also_on.is_empty());
+        assert_eq!(repo.tag.get("remote").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_deserialize_site() {
+        let site = |json: &str| serde_json::from_str::<Job>(json).map(|job| job.site);
+        assert_eq!(
+            site(r#"{"site": "https://remoteok.com"}"#).unwrap(),
+            "https://remoteok.com"
+        );
+        assert_eq!(site("{}").unwrap(), "");
+        assert_eq!(site(r#"{"site": "https://nowhere.example"}"#).unwrap(), "");
+    }
+
+    #[test]
+    fn test_rebuild_merges_and_unmerges() {
+        let mut repo = SoftwareJobsBuilder::new()
+            .import(vec![
+                vec![job("Senior Backend Engineer", "Acme", "2022-07-27")],
+                vec![Job {
+                    site: "https://site2.com",
+                    ..job("Senior Backend Engineer", "Acme", "2022-07-28")
+                }],
+                vec![job("Frontend Engineer", "Acme", "2022-07-28")],
+            ])
+            .index();
+        assert_eq!(repo.all.len(), 3);
+
+        let mut config = Config {
+            merge_duplicates: true,
+            ..Default::default()
+        };
+        repo.rebuild(&config);
+        assert_eq!(repo.all.len(), 2);
+        assert_eq!(repo.company.get("Acme").unwrap().len(), 2);
+        let merged = repo.all.iter().find(|job| !job.also_on.is_empty()).unwrap();
+        assert_eq!(merged.also_on, vec!["https://site2.com"]);
+        assert!(format!("{merged:?}").contains("(also on site2)"));
+
+        config.merge_duplicates = false;
+        repo.rebuild(&config);
+        assert_eq!(repo.all.len(), 3);
+        assert!(repo.all.iter().all(|job| job.also_on.is_empty()));
+    }
+
+    #[test]
+    fn test_from_cache_filters_as_configured() {
+        let repo = SoftwareJobsBuilder::new()
+            .import(vec![vec![
+                job("Backend Engineer", "Acme", "2022-07-27"),
+                job("Product Designer", "Acme", "2022-07-28"),
+            ]])
+            .filter(|job| job.is_of_interest(&Config::default().title_keywords))
+            .index();
+        assert_eq!(repo.all.len(), 1);
+        assert_eq!(repo.scraped.len(), 2);
+
+        // the jobs are cached as scraped, so a job filtered out when cached is kept if the keywords
+        // now match it
+        let config = Config {
+            title_keywords: vec!["designer".into()],
+            ..Default::default()
+        };
+        let repo = SoftwareJobs::from_cache(repo.scraped, Local::now(), &config);
+        assert_eq!(repo.all.len(), 1);
+        assert_eq!(repo.all[0].title, "Product Designer");
+    }
+
+    #[test]
+    fn test_replace_site_jobs() {
+        let mut repo = SoftwareJobsBuilder::new()
+            .import(vec![
+                vec![
+                    job("Backend Engineer", "Acme", "2022-07-27"),
+                    job("Frontend Engineer", "Acme", "2022-07-27"),
+                ],
+                vec![Job {
+                    site: "https://site2.com",
+                    ..job("Solidity Developer", "Globex", "2022-07-28")
+                }],
+            ])
+            .index();
+
+        repo.replace_site_jobs(
+            "https://site1.com",
+            vec![
+                job("Rust Engineer", "Initech", "2022-07-29"),
+                job("Community Manager", "Initech", "2022-07-29"),
+            ],
+            &Config::default(),
+        );
+        let titles = repo
+            .all
+            .iter()
+            .map(|job| job.title.as_str())
+            .sorted()
+            .collect::<Vec<_>>();
+        assert_eq!(titles, vec!["Rust Engineer", "Solidity Developer"]);
+        assert!(!repo.company.contains_key("Acme"));
+        assert_eq!(repo.company.get("Initech").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_boosted_jobs_rank_first() {
+        let mut repo = SoftwareJobsBuilder::new()
+            .import(vec![vec![
+                job("Backend Engineer", "Company_1", "2022-07-27"),
+                job("Senior Backend Engineer", "Company_2", "2022-07-27"),
+                Job {
+                    tags: vec!["Rust".into()],
+                    ..job("Backend Engineer", "Company_3", "2022-07-27")
+                },
+            ]])
+            .index();
+        let config = Config {
+            boosts: vec![("rust".into(), 10), ("senior".into(), 5)],
+            ..Default::default()
+        };
+
+        let jobs = repo.fetch_jobs(&config);
+        assert_eq!(super::score(&jobs[0], &config.boosts), 10);
+        assert_eq!(jobs[0].company, "Company_3");
+        assert_eq!(jobs[1].company, "Company_2");
+        assert_eq!(jobs[2].company, "Company_1");
+    }
+
+    #[test]
+    fn test_parse_apply_by() {
+        let today = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let expected = NaiveDate::from_ymd_opt(2025, 6, 30);
+
+        assert_eq!(
+            parse_apply_by("Great team, remote friendly. Apply by 2025-06-30!", today),
+            expected
+        );
+        assert_eq!(parse_apply_by("Deadline: 30th June 2025", today), expected);
+        assert_eq!(
+            parse_apply_by("Applications close June 30, 2025", today),
+            expected
+        );
+        assert_eq!(parse_apply_by("Apply by 2024-06-30", today), None);
+        assert_eq!(parse_apply_by("Posted 2025-06-30", today), None);
+
+        let repo = SoftwareJobsBuilder::new()
+            .import(vec![vec![Job {
+                tags: vec!["Apply by 2999-06-30".into()],
+                ..job("Backend Engineer", "Company_1", "2022-07-27")
+            }]])
+            .index();
+        assert_eq!(
+            repo.fetch_closing_soon()[0].apply_by,
+            NaiveDate::from_ymd_opt(2999, 6, 30)
+        );
+    }
+
+    #[test]
+    fn test_fetch_skills_union() {
+        let repo = SoftwareJobsBuilder::new()
+            .import(vec![vec![
+                job("Backend Engineer", "Company_1", "2022-07-27"),
+                job("Frontend Engineer", "Company_1", "2022-07-27"),
+                job("Backend/Frontend Engineer", "Company_2", "2022-07-27"),
+                job("Platform Engineer", "Company_2", "2022-07-27"),
+            ]])
+            .index();
+
+        let jobs = repo.fetch_skills(&[Skill::Backend, Skill::Frontend]);
+        assert_eq!(jobs.len(), 3);
+        assert!(jobs.iter().all(|job| job.title != "Platform Engineer"));
+        assert_eq!(repo.fetch_skills(&[Skill::Blockchain]).len(), 0);
+    }
+
+    #[test]
+    fn test_remote_locations() {
+        let locations = [
+            "Remote",
+            "100% Remote",
+            "Fully Remote",
+            "Remote (US only)",
+            "Remote-first",
+            "REMOTE",
+            "Europe / Remote",
+            "Work from home",
+        ];
+        for location in locations {
+            assert!(is_remote(location), "{location}");
+        }
+        for location in ["London", "Remoteville", "No remote", "Non-remote", ""] {
+            assert!(!is_remote(location), "{location}");
+        }
+
+        let mut jobs = locations
+            .iter()
+            .map(|location| Job {
+                location: location.to_string(),
+                ..job("Backend Engineer", "Company_1", "2022-07-27")
+            })
+            .collect::<Vec<Job>>();
+        jobs.push(Job {
+            location: "London".into(),
+            ..job("Backend Engineer", "Company_2", "2022-07-27")
+        });
+        let repo = SoftwareJobsBuilder::new().import(vec![jobs]).index();
+        assert_eq!(
+            repo.location.get(&Location::Remote).unwrap().len(),
+            locations.len()
+        );
+        assert_eq!(repo.location.get(&Location::Onsite).unwrap().len(), 1);
+
+        for location in ["Remote", "Remote (US only)", "100% Remote", "Remote-first"] {
+            assert!(is_fully_remote(location), "{location}");
+        }
+        let hybrid = [
+            "Berlin (hybrid, some remote)",
+            "Remote / On-site",
+            "Hybrid Remote",
+            "London - partially remote",
+            "Remote, 2 days in office",
+            "Berlin",
+        ];
+        for location in hybrid {
+            assert!(!is_fully_remote(location), "{location}");
+        }
+        let jobs = repo.fetch_fully_remote();
+        assert_eq!(jobs.len(), locations.len());
+
+        assert_eq!(
+            remote_region("Remote (US only)").as_deref(),
+            Some("US only")
+        );
+        assert_eq!(remote_region("Remote - Europe").as_deref(), Some("Europe"));
+        assert_eq!(remote_region("Europe / Remote").as_deref(), Some("Europe"));
+        assert_eq!(remote_region("100% Remote"), None);
+        assert_eq!(remote_region("Remote-first"), None);
+        assert_eq!(remote_region("London (UK)"), None);
+    }
+
+    #[test]
+    fn test_fetch_similar() {
+        let tagged = |title, company, tags: &[&str]| Job {
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            ..job(title, company, "2022-07-27")
+        };
+        let source = tagged(
+            "Senior Rust Backend Engineer",
+            "Company_1",
+            &["rust", "defi"],
+        );
+        let repo = SoftwareJobsBuilder::new()
+            .import(vec![
+                vec![
+                    source.clone(),
+                    tagged("Frontend Engineer", "Company_2", &["react"]),
+                    tagged("Rust Backend Developer", "Company_3", &["Rust"]),
+                ],
+                vec![tagged(
+                    "Senior Rust Backend Engineer",
+                    "Company 1",
+                    &["rust"],
+                )],
+            ])
+            .index();
+
+        let similar = repo.fetch_similar(&source);
+        assert_eq!(similar.len(), 1);
+        assert_eq!(similar[0].company, "Company_3");
+        assert!(similarity(&source, &similar[0]) > 0);
+        assert_eq!(similarity(&source, &repo.all[1]), 0);
+    }
+
+    #[test]
+    fn test_companies_by_recent_posting() {
+        let repo = SoftwareJobsBuilder::new()
+            .import(vec![vec![
+                job("Backend Engineer", "Company_1", "2022-06-01"),
+                job("Frontend Engineer", "Company_1", "2022-06-02"),
+                job("Backend Engineer", "Company_2", "2022-07-27"),
+                job("Backend Engineer", "Company_3", "unknown"),
+            ]])
+            .index();
+        let names = |companies: Companies| {
+            companies
+                .rows
+                .into_iter()
+                .map(|(name, _, _)| name)
+                .collect::<Vec<String>>()
+        };
+
+        assert_eq!(
+            names(repo.companies(CompanyOrder::Recent)),
+            vec!["Company_2", "Company_1", "Company_3"]
+        );
+        assert_eq!(
+            names(repo.companies(CompanyOrder::Count)),
+            vec!["Company_1", "Company_2", "Company_3"]
+        );
+        assert!(repo
+            .companies(CompanyOrder::Recent)
+            .to_string()
+            .contains("Company_2  1     2022-07-27"));
+    }
+
+    #[test]
+    fn test_remote_regions() {
+        assert_eq!(parse_regions("Remote - EMEA"), vec![Region::Emea]);
+        assert_eq!(
+            parse_regions("Remote (US timezones only)"),
+            vec![Region::Americas]
+        );
+        assert_eq!(
+            parse_regions("Remote: Europe or APAC"),
+            vec![Region::Emea, Region::Apac]
+        );
+        assert_eq!(parse_regions("Remote-first"), vec![]);
+        assert_eq!(parse_regions("Remote, US"), vec![Region::Americas]);
+        // lowercase abbreviations are words, not countries or timezones
+        assert_eq!(parse_regions("Remote - work with us"), vec![]);
+        assert_eq!(
+            parse_regions("Remote (set up your own hours, pt or ft)"),
+            vec![]
+        );
+        // only the stated region counts, not the rest of the location
+        assert_eq!(parse_regions("London, UK"), vec![]);
+        assert_eq!("Europe".parse::<Region>(), Ok(Region::Emea));
+
+        let located = |company: &str, location: &str| Job {
+            location: location.into(),
+            ..job("Backend Engineer", company, "2022-07-27")
+        };
+        let repo = SoftwareJobsBuilder::new()
+            .import(vec![vec![
+                located("Company_1", "Remote - EMEA"),
+                located("Company_2", "Remote (US only)"),
+                located("Company_3", "100% Remote"),
+                located("Company_4", "London, UK"),
+            ]])
+            .index();
+
+        let companies = repo
+            .fetch_remote_region(Region::Emea)
+            .iter()
+            .map(|job| job.company.clone())
+            .sorted()
+            .collect::<Vec<String>>();
+        assert_eq!(companies, vec!["Company_1", "Company_3"]);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_parallel_index_matches_sequential() {
+        let jobs = (0..500)
+            .map(|i| Job {
+                tags: vec![format!("Tag{}", i % 7), "rust".into()],
+                location: ["Remote", "London", "Remote (EU)"][i % 3].into(),
+                ..job(
+                    [
+                        "Senior Backend Engineer",
+                        "Junior Frontend Developer",
+                        "Lead DevOps Engineer",
+                        "Staff Blockchain Engineer",
+                    ][i % 4],
+                    &format!("Company_{}", i % 11),
+                    &format!("2022-07-{:02}", i % 28 + 1),
+                )
+            })
+            .collect::<Vec<Job>>();
+        let sequential = SoftwareJobsBuilder::new()
+            .import(vec![jobs.clone()])
+            .index();
+        let parallel = SoftwareJobsBuilder::new()
+            .import(vec![jobs])
+            .index_parallel();
+
+        assert_eq!(sequential.all, parallel.all);
+        assert_eq!(sequential.date, parallel.date);
+        assert_eq!(sequential.company, parallel.company);
+        assert_eq!(sequential.location, parallel.location);
+        assert_eq!(sequential.skill, parallel.skill);
+        assert_eq!(sequential.level, parallel.level);
+        assert_eq!(sequential.tag, parallel.tag);
+    }
+
+    #[test]
+    fn test_parse_location_info() {
+        let info = |city: Option<&str>, region: Option<&str>, country: Option<&str>, remote| {
+            LocationInfo {
+                city: city.map(String::from),
+                region: region.map(String::from),
+                country: country.map(String::from),
+                remote,
+            }
+        };
+
+        assert_eq!(
+            LocationInfo::parse("Berlin, Germany"),
+            info(Some("Berlin"), None, Some("Germany"), false)
+        );
+        assert_eq!(
+            LocationInfo::parse("Remote, US"),
+            info(None, None, Some("United States"), true)
+        );
+        assert_eq!(
+            LocationInfo::parse("San Francisco, CA, USA"),
+            info(
+                Some("San Francisco"),
+                Some("CA"),
+                Some("United States"),
+                false
+            )
+        );
+        assert_eq!(
+            LocationInfo::parse("Austin, TX"),
+            info(Some("Austin"), Some("TX"), Some("United States"), false)
+        );
+        assert_eq!(
+            LocationInfo::parse("Toronto, Ontario, Canada (Hybrid)"),
+            info(Some("Toronto"), Some("Ontario"), Some("Canada"), false)
+        );
+        assert_eq!(
+            LocationInfo::parse("Remote (UK only)"),
+            info(None, None, None, true)
+        );
+        assert_eq!(
+            LocationInfo::parse("London"),
+            info(Some("London"), None, None, false)
+        );
+        assert_eq!(LocationInfo::parse(" , ,"), info(None, None, None, false));
+
+        let repo = SoftwareJobsBuilder::new()
+            .import(vec![vec![
+                Job {
+                    location: "Berlin, Germany".into(),
+                    ..job("Backend Engineer", "Company_1", "2022-07-27")
+                },
+                Job {
+                    location: "Munich, DE, Germany".into(),
+                    ..job("Backend Engineer", "Company_2", "2022-07-27")
+                },
+            ]])
+            .index();
+        assert_eq!(repo.country.get("Germany").unwrap().len(), 2);
+        assert_eq!(repo.fetch_country("deutschland").len(), 2);
+    }
+
+    /// Generates a mock site that scrapes the given jobs, or fails if given an error.
+    macro_rules! mock_site {
+        ($t:ident, $url:literal, $scrape:expr) => {
+            struct $t(Vec<Job>);
+
+            impl Site for $t {
+                const DATE_SOURCE: DateSource = DateSource::Iso;
+
+                fn new() -> Self {
+                    Self(vec![])
+                }
+
+                fn get_url(&self) -> &'static str {
+                    $url
+                }
+
+                fn into_jobs(self) -> Vec<Job> {
+                    self.0
+                }
+            }
+
+            impl Scraper for $t {
+                fn scrape(self, _options: ScrapeOptions) -> Result<Self, Error> {
+                    $scrape.map(Self)
+                }
+
+                #[cfg(feature = "async")]
+                async fn scrape_async(self, options: ScrapeOptions) -> Result<Self, Error> {
+                    self.scrape(options)
+                }
+            }
+        };
+    }
+
+    mock_site!(
+        MockSite1,
+        "https://site1.com",
+        Ok(vec![job("Backend Engineer", "Company_1", "2022-07-27")])
+    );
+    mock_site!(
+        MockSite2,
+        "https://jobs.site2.com",
+        Err(Error::Response(500))
+    );
+
+    /// Scrapes a mock site in a new thread with the default options and no limit.
+    fn spawn_mock<S>() -> std::thread::JoinHandle<(SiteResult, std::time::Duration)>
+    where
+        S: Site + Scraper + 'static,
+    {
+        let site = SiteEntry::new::<S>("mock", "", &[]);
+        spawn_scrape(site, Config::default().scrape_options(), None)
+    }
+
+    #[test]
+    fn test_find_site() {
+        let url = |name| find_site(name).map(|site| site.default_url);
+        assert_eq!(url("remoteok"), Some(REMOTE_OK_URL));
+        assert_eq!(url("Remote OK"), Some(REMOTE_OK_URL));
+        assert_eq!(url("remote_ok"), Some(REMOTE_OK_URL));
+        assert_eq!(url("remote"), None);
+    }
+
+    #[test]
+    fn test_scrape_timings_recorded() {
+        let (site1, site2) = (spawn_mock::<MockSite1>(), spawn_mock::<MockSite2>());
+        let (mut failures, mut timings) = (vec![], ScrapeTimings::default());
+
+        assert_eq!(
+            join_site(site1, false, &mut failures, &mut timings).len(),
+            1
+        );
+        assert!(join_site(site2, false, &mut failures, &mut timings).is_empty());
+
+        assert_eq!(failures.len(), 1);
+        let urls = timings
+            .sites
+            .iter()
+            .map(|(url, _)| url.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(urls, vec!["https://site1.com", "https://jobs.site2.com"]);
+        let table = timings.to_string();
+        assert!(table.contains("site1") && table.contains("site2") && table.contains("Total"));
+    }
+
+    mock_site!(MockSite3, "https://site3.com", Ok(vec![]));
+    mock_site!(
+        MockSite4,
+        "https://site4.com",
+        Ok(vec![job("Backend Engineer", "", "2022-07-27")])
+    );
+
+    #[test]
+    fn test_empty_site_warnings() {
+        let (mut failures, mut timings) = (vec![], ScrapeTimings::default());
+        let jobs = vec![
+            join_site(
+                spawn_mock::<MockSite1>(),
+                false,
+                &mut failures,
+                &mut timings,
+            ),
+            join_site(
+                spawn_mock::<MockSite2>(),
+                false,
+                &mut failures,
+                &mut timings,
+            ),
+            join_site(
+                spawn_mock::<MockSite3>(),
+                false,
+                &mut failures,
+                &mut timings,
+            ),
+        ];
+
+        // site2 failed, so only site3 is empty without an error
+        let had_jobs = HashSet::from([
+            "https://site1.com",
+            "https://jobs.site2.com",
+            "https://site3.com",
+        ]);
+        let warnings = empty_sites(&timings.sites, &jobs, &failures, &had_jobs);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].0, "https://site3.com");
+        assert!(matches!(warnings[0].1, Error::EmptyResult));
+
+        // a site that never had jobs may just have none today
+        let had_jobs = HashSet::from(["https://site1.com"]);
+        assert!(empty_sites(&timings.sites, &jobs, &failures, &had_jobs).is_empty());
+    }
+
+    #[test]
+    fn test_strict_site_checks() {
+        let mut timings = ScrapeTimings::default();
+
+        // lenient: a site with no jobs, or a job with missing fields, isn't a failure
+        let mut failures = vec![];
+        let site3 = spawn_mock::<MockSite3>();
+        assert!(join_site(site3, false, &mut failures, &mut timings).is_empty());
+        let site4 = spawn_mock::<MockSite4>();
+        assert_eq!(
+            join_site(site4, false, &mut failures, &mut timings).len(),
+            1
+        );
+        assert!(failures.is_empty());
+
+        // strict: both fail their site
+        let site1 = spawn_mock::<MockSite1>();
+        assert_eq!(join_site(site1, true, &mut failures, &mut timings).len(), 1);
+        let site3 = spawn_mock::<MockSite3>();
+        assert!(join_site(site3, true, &mut failures, &mut timings).is_empty());
+        let site4 = spawn_mock::<MockSite4>();
+        assert!(join_site(site4, true, &mut failures, &mut timings).is_empty());
+        let errors = failures
+            .iter()
+            .map(|(url, err)| format!("{url}: {err}"))
+            .collect::<Vec<String>>();
+        assert_eq!(
+            errors,
+            vec![
+                "https://site3.com: The site returned no jobs; its layout may have changed",
+                "https://site4.com: A job has no company"
+            ]
+        );
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_scrape_within_runtime() {
+        // blocking on a new runtime from within this one would panic
+        let config = Config {
+            retries: 0,
+            max_pages: 1,
+            ..Default::default()
+        };
+        let (repo, _) = SoftwareJobs::try_init_repo(&config);
+        assert_eq!(repo.timings.sites.len(), 7);
+    }
+
+    #[test]
+    fn test_job_order() {
+        let mut repo = SoftwareJobsBuilder::new()
+            .import(vec![vec![
+                Job {
+                    remuneration: "$100k - $120k".into(),
+                    ..job("Backend Engineer", "beta", "2022-07-26")
+                },
+                Job {
+                    remuneration: "$150k".into(),
+                    ..job("Frontend Engineer", "Alpha", "2022-07-28")
+                },
+                job("Rust Engineer", "Gamma", "2022-07-27"),
+            ]])
+            .index();
+        // unsorted, fetch jobs lists the newest first
+        let fetched = repo.fetch_jobs(&Config::default());
+        assert_eq!(fetched[0].title, "Frontend Engineer");
+        assert_eq!(fetched[2].title, "Backend Engineer");
+        let mut titles = |order: &str| {
+            let mut jobs = repo.fetch_jobs(&Config::default());
+            order.parse::<JobOrder>().unwrap().sort(&mut jobs);
+            jobs.iter()
+                .map(|job| job.title.clone())
+                .collect::<Vec<String>>()
+        };
+
+        assert_eq!(
+            titles("date asc"),
+            vec!["Backend Engineer", "Rust Engineer", "Frontend Engineer"]
+        );
+        let date_desc = vec!["Frontend Engineer", "Rust Engineer", "Backend Engineer"];
+        assert_eq!(titles("date desc"), date_desc);
+        assert_eq!(titles("date"), date_desc);
+        assert_eq!(titles(""), date_desc);
+        assert_eq!(
+            titles("company"),
+            vec!["Frontend Engineer", "Backend Engineer", "Rust Engineer"]
+        );
+        assert_eq!(
+            titles("remuneration"),
+            vec!["Frontend Engineer", "Backend Engineer", "Rust Engineer"]
+        );
+        assert!("salary".parse::<JobOrder>().is_err());
+        assert!("date up".parse::<JobOrder>().is_err());
+    }
+
+    #[test]
+    fn test_search_relevance() {
+        let title_match = job("Solidity Engineer", "Company_1", "2022-07-27");
+        let tags_match = Job {
+            tags: vec!["solidity".into(), "evm".into()],
+            ..job("Backend Engineer", "Company_2", "2022-07-28")
+        };
+        assert!(relevance(&title_match, "solidity") > relevance(&tags_match, "solidity"));
+        assert_eq!(relevance(&tags_match, "rust"), 0);
+        // every field matched adds its weight
+        assert!(relevance(&tags_match, "Solidity company_2") > relevance(&tags_match, "solidity"));
+
+        let repo = SoftwareJobsBuilder::new()
+            .import(vec![vec![
+                tags_match,
+                title_match,
+                job("Rust Engineer", "Company_3", "2022-07-27"),
+            ]])
+            .index();
+        let results = repo
+            .search("solidity")
+            .into_iter()
+            .map(|(_, job)| job.title.clone())
+            .collect::<Vec<String>>();
+        assert_eq!(results, vec!["Solidity Engineer", "Backend Engineer"]);
+        assert!(repo.search("").is_empty());
+    }
+
+    #[test]
+    fn test_shuffle_seeded() {
+        let mut repo = SoftwareJobsBuilder::new()
+            .import(vec![(1..=10)
+                .map(|i| job(&format!("Engineer {i}"), "Company_1", "2022-07-27"))
+                .collect()])
+            .index();
+        let config = Config::default();
+        let titles = |jobs: Vec<JobRef>| {
+            jobs.iter()
+                .map(|job| job.title.clone())
+                .collect::<Vec<String>>()
+        };
+
+        let sorted = titles(repo.fetch_jobs(&config));
+        let shuffled = titles(repo.fetch_jobs_shuffled(&config, 42));
+        assert_eq!(shuffled, titles(repo.fetch_jobs_shuffled(&config, 42)));
+        assert_ne!(shuffled, sorted);
+        assert_ne!(shuffled, titles(repo.fetch_jobs_shuffled(&config, 7)));
+        // the same jobs, in a different order
+        assert_eq!(
+            shuffled.iter().sorted().collect::<Vec<_>>(),
+            sorted.iter().sorted().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_principal_level() {
+        let repo = SoftwareJobsBuilder::new()
+            .import(vec![vec![
+                job("Principal Engineer", "Company_1", "2022-07-27"),
+                job("Principle Protocol Engineer", "Company_2", "2022-07-28"),
+            ]])
+            .index();
+        assert_eq!(repo.level.get(&Level::Principal).unwrap().len(), 2);
+        assert_eq!("principle".parse::<Level>(), Ok(Level::Principal));
+        assert_eq!("Principal".parse::<Level>(), Ok(Level::Principal));
+    }
+
+    #[test]
+    fn test_level_conflict_policy() {
+        let jobs = || {
+            vec![vec![
+                job("Senior Staff Engineer", "Company_1", "2022-07-27"),
+                job("Senior Backend Engineer", "Company_2", "2022-07-28"),
+            ]]
+        };
+        let repo = SoftwareJobsBuilder::new().import(jobs()).index();
+        assert_eq!(repo.level_policy, LevelPolicy::Highest);
+        assert_eq!(repo.level.get(&Level::Staff).unwrap().len(), 1);
+        assert_eq!(repo.level.get(&Level::Senior).unwrap().len(), 1);
+        assert_eq!(repo.level.values().map(Vec::len).sum::<usize>(), 2);
+        // the job is still found by other queries
+        assert!(repo
+            .all
+            .iter()
+            .any(|job| job.title == "Senior Staff Engineer"));
+
+        let explanation = repo.explain(&repo.all[0]);
+        assert!(explanation.has_level_conflict());
+        assert_eq!(explanation.indexed_levels, vec![Level::Staff]);
+        assert!(explanation.to_string().contains(
+            "warning: the title matches several levels (Senior, Staff), indexed as Staff"
+        ));
+        assert!(!repo.explain(&repo.all[1]).has_level_conflict());
+
+        let repo = SoftwareJobsBuilder::new()
+            .import(jobs())
+            .level_policy("all".parse().unwrap())
+            .index();
+        assert_eq!(repo.level.get(&Level::Senior).unwrap().len(), 2);
+        assert_eq!(repo.level.get(&Level::Staff).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_search_keyword() {
+        let repo = SoftwareJobsBuilder::new()
+            .import(vec![vec![
+                Job {
+                    tags: vec!["Smart Contracts".into()],
+                    ..job("Backend Engineer", "Company_1", "2022-07-27")
+                },
+                job(
+                    "Solidity Smart Contract Engineer",
+                    "Company_2",
+                    "2022-07-28",
+                ),
+                job("Rustacean Engineer", "Company_3", "2022-07-26"),
+                job("Frontend Engineer", "Company_4", "2022-07-27"),
+            ]])
+            .index();
+
+        let companies = |jobs: Vec<JobRef>| {
+            jobs.iter()
+                .map(|job| job.company.clone())
+                .collect::<Vec<String>>()
+        };
+        assert_eq!(
+            companies(repo.search_keyword("smart contract")),
+            vec!["Company_2", "Company_1"]
+        );
+        assert_eq!(companies(repo.search_keyword("RUST")), vec!["Company_3"]);
+        assert!(repo.search_keyword("contract smart").is_empty());
+    }
+
+    #[test]
+    fn test_counts() {
+        let repo = SoftwareJobsBuilder::new()
+            .import(vec![vec![
+                job("Senior Backend Engineer", "Company_1", "2022-07-27"),
+                job("Backend Engineer", "Company_1", "2022-07-27"),
+                job("Remote Frontend Engineer", "Company_2", "2022-07-27"),
+            ]])
+            .index();
+
+        let counts = repo.counts();
+        assert_eq!((counts.jobs, counts.companies), (3, 2));
+        assert!(counts.skills.contains(&(Skill::Backend, 2)));
+        assert!(counts.skills.contains(&(Skill::DevOps, 0)));
+        assert_eq!(counts.skills.len(), Skill::ALL.len());
+        assert!(counts.levels.contains(&(Level::Senior, 1)));
+        assert_eq!(
+            counts.locations.iter().map(|(_, n)| n).sum::<usize>(),
+            counts.jobs
+        );
+
+        let printed = counts.to_string();
+        // names are padded to the longest, "Intermediate"
+        assert!(printed.starts_with("Jobs          3\nCompanies     2\n\nBackend       2\n"));
+        assert_eq!(SoftwareJobsBuilder::new().index().counts().jobs, 0);
+    }
+
+    #[test]
+    fn test_location_stats() {
+        let at = |title: &str, location: &str| Job {
+            location: location.into(),
+            ..job(title, "Company_1", "2022-07-27")
+        };
+        let repo = SoftwareJobsBuilder::new()
+            .import(vec![vec![
+                at("Backend Engineer", "Remote"),
+                at("Frontend Engineer", "Berlin, Germany"),
+                at("DevOps Engineer", "Munich, Germany"),
+                at("Rust Engineer", "London, UK (Hybrid)"),
+                at("Go Engineer", "Somewhere"),
+            ]])
+            .index();
+
+        let stats = repo.location_stats();
+        assert_eq!((stats.remote, stats.onsite, stats.hybrid), (1, 3, 1));
+        assert_eq!(
+            stats.countries,
+            vec![
+                ("Germany".to_string(), 2),
+                ("unknown".to_string(), 2),
+                ("United Kingdom".to_string(), 1)
+            ]
+        );
+        assert_eq!(stats.cities.len(), 5);
+        assert_eq!(stats.cities[1], ("London".to_string(), 1));
+        assert!(stats.cities.contains(&("unknown".to_string(), 1)));
+        assert!(stats.to_string().contains("Hybrid  1\n"));
+        assert_eq!(
+            SoftwareJobsBuilder::new().index().location_stats(),
+            LocationStats::default()
+        );
+    }
+
+    #[test]
+    fn test_site_filter_applies_to_its_site_only() {
+        let from = |title: &str, site: &'static str| Job {
+            site,
+            ..job(title, "Company_1", "2022-07-27")
+        };
+        let jobs = [
+            from("Backend Engineer", "https://board.com"),
+            from("Solidity Developer", "https://board.com"),
+            from("Community Manager", "https://board.com"),
+            from("Community Manager", "https://web3.career"),
+            from("Marketing Lead", "https://cryptojobslist.com"),
+        ];
+        let filters = vec![
+            ("board.com".to_string(), vec!["engineer".to_string()]),
+            ("web3.career".to_string(), vec![]),
+        ];
+
+        let keywords = Config::default().title_keywords;
+        let kept = jobs
+            .iter()
+            .filter(|job| job.is_of_interest_with(&filters, &keywords))
+            .map(|job| (job.title.as_str(), job.site))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kept,
+            vec![
+                ("Backend Engineer", "https://board.com"),
+                ("Community Manager", "https://web3.career")
+            ]
+        );
+        // without site filters, the title keywords apply everywhere
+        assert_eq!(
+            jobs.iter()
+                .filter(|job| job.is_of_interest_with(&[], &keywords))
+                .count(),
+            2
+        );
+        let keywords = ["manager".to_string(), "lead".into()];
+        assert_eq!(
+            jobs.iter()
+                .filter(|job| job.is_of_interest_with(&[], &keywords))
+                .map(|job| job.title.as_str())
+                .collect::<Vec<_>>(),
+            vec!["Community Manager", "Community Manager", "Marketing Lead"]
+        );
+        assert_eq!(
+            jobs.iter()
+                .filter(|job| job.is_of_interest_with(&[], &[]))
+                .count(),
+            5
+        );
+    }
+
+    #[test]
+    fn test_missing_salary() {
+        let from = |title: &str, remuneration: &str, site: &'static str| Job {
+            remuneration: remuneration.into(),
+            site,
+            ..job(title, "Company_1", "2022-07-27")
+        };
+        let repo = SoftwareJobsBuilder::new()
+            .import(vec![
+                vec![
+                    from("Backend Engineer", "", "https://site1.com"),
+                    from("Frontend Engineer", "$120k", "https://site1.com"),
+                ],
                 vec![
-                    Job {
-                        title: "Junior Fullstack Developer".into(),
-                        company: "Company_1".into(),
-                        date_posted: "2022-07-27".into(),
-                        location: "Remote".into(),
-                        remuneration: "$165k - $200k".into(),
-                        tags: vec!["tag1".into(), "tag2".into()],
-                        apply: "https://site2.com".into(),
-                        site: "https://site2.com",
-                    },
-                    Job {
-                        title: "Senior Backend Engineer".into(),
-                        company: "Company_1".into(),
-                        date_posted: "2022-07-27".into(),
-                        location: "Onsite".into(),
-                        remuneration: "$165k - $200k".into(),
-                        tags: vec!["tag1".into(), "tag2".into()],
-                        apply: "https://site2.com".into(),
-                        site: "https://site2.com",
-                    },
-                    Job {
-                        title: "Snr Backend Engineer".into(),
-                        company: "Company_1".into(),
-                        date_posted: "2022-07-27".into(),
-                        location: "Onsite".into(),
-                        remuneration: "$165k - $200k".into(),
-                        tags: vec!["tag1".into(), "tag2".into()],
-                        apply: "https://site2.com".into(),
-                        site: "https://site2.com",
-                    },
+                    from("Rust Engineer", " ", "https://jobs.site2.com"),
+                    from("Solidity Engineer", "", "https://jobs.site2.com"),
                 ],
             ])
-            .filter(|job| {
-                job.title_contains_any(vec!["developer", "engineer", "engineering", "technical"])
-            }) // optional filter - in this case filter on software jobs
             .index();
 
-        // check index map keys
-        assert_eq!(repo.all.len(), 5);
-        assert_eq!(repo.date.len(), 3);
-        assert_eq!(repo.company.len(), 3);
-        assert_eq!(repo.location.len(), 2);
-        assert_eq!(repo.skill.len(), 3);
-        assert_eq!(repo.level.len(), 3);
+        let missing = repo.missing("salary".parse().unwrap());
+        let sites = missing
+            .sites
+            .iter()
+            .map(|(site, jobs)| {
+                let titles = jobs.iter().map(|job| job.title.as_str()).sorted();
+                (site.as_str(), titles.collect::<Vec<&str>>())
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            sites,
+            vec![
+                ("site2", vec!["Rust Engineer", "Solidity Engineer"]),
+                ("site1", vec!["Backend Engineer"]),
+            ]
+        );
+        assert!(missing
+            .to_string()
+            .starts_with("site2: 2 missing remuneration\n"));
+        assert_eq!(
+            repo.missing("apply".parse().unwrap()).to_string(),
+            "No jobs are missing apply.\n"
+        );
+    }
 
-        // check index map values
-        assert_eq!(repo.location.get(&Location::Remote).unwrap().len(), 3);
-        assert_eq!(repo.skill.get(&Skill::Backend).unwrap().len(), 2);
-        assert_eq!(repo.skill.get(&Skill::DevOps).unwrap().len(), 1);
-        assert_eq!(repo.level.get(&Level::Senior).unwrap().len(), 2);
+    #[test]
+    fn test_duplicates() {
+        let from = |title: &str, company: &str, site: &'static str| Job {
+            site,
+            ..job(title, company, "2022-07-27")
+        };
+        let repo = SoftwareJobsBuilder::new()
+            .import(vec![
+                vec![
+                    from("Backend Engineer", "Acme", "https://site1.com"),
+                    from("Rust Engineer", "Acme", "https://site1.com"),
+                    from("Frontend Engineer", "Other", "https://site1.com"),
+                ],
+                vec![
+                    from("Senior Backend Engineer", "Acme", "https://site2.com"),
+                    from("Backend  Engineer", "ACME", "https://site2.com"),
+                    from("Rust Engineer", "Acme", "https://site2.com"),
+                ],
+                vec![from("rust engineer", "acme", "https://site3.com")],
+            ])
+            .index();
+
+        let duplicates = repo.duplicates();
+        let groups = duplicates
+            .groups
+            .iter()
+            .map(|group| group.iter().map(|job| job.site).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            groups,
+            vec![
+                vec![
+                    "https://site1.com",
+                    "https://site2.com",
+                    "https://site3.com"
+                ],
+                vec!["https://site1.com", "https://site2.com"],
+            ]
+        );
+        assert_eq!(duplicates.groups[0][0].title, "Rust Engineer");
+        assert!(duplicates
+            .to_string()
+            .contains("Rust Engineer at Acme (3 posts)\n"));
+        assert_eq!(repo.all.len(), 7);
+    }
+
+    #[test]
+    fn test_remuneration_range() {
+        let repo = SoftwareJobsBuilder::new()
+            .import(vec![vec![
+                Job {
+                    remuneration: "$90k - $140k".into(),
+                    ..job("Backend Engineer", "Company_1", "2022-07-27")
+                },
+                Job {
+                    remuneration: "$6.5k - $7.5k".into(),
+                    ..job("Frontend Engineer", "Company_2", "2022-07-27")
+                },
+                Job {
+                    remuneration: "Competitive".into(),
+                    ..job("Rust Engineer", "Company_3", "2022-07-27")
+                },
+            ]])
+            .index();
+        let ranges = repo
+            .all
+            .iter()
+            .map(|job| (job.remuneration_min, job.remuneration_max))
+            .collect::<Vec<(Option<u32>, Option<u32>)>>();
+        assert_eq!(
+            ranges,
+            vec![
+                (Some(90_000), Some(140_000)),
+                (Some(6_500), Some(7_500)),
+                (None, None)
+            ]
+        );
+        // the original text is kept for display
+        assert_eq!(repo.all[0].remuneration, "$90k - $140k");
+
+        assert_eq!(parse_salary("$120k"), Some((120_000, 120_000)));
+        assert_eq!(parse_salary("€90.000"), Some((90_000, 90_000)));
+        assert_eq!(parse_salary("$120,000.50"), Some((120_000, 120_000)));
+        assert_eq!(parse_salary("$6.5k - $7.5k"), Some((6_500, 7_500)));
+        assert_eq!(parse_salary("$4294967295"), Some((u32::MAX, u32::MAX)));
+        assert_eq!(parse_salary("$99999999999999999999k"), None);
+        assert_eq!(parse_salary("$120k - $99999999999k"), None);
+
+        let jobs = ["$99999999999999999999k", "$4294967295"].map(|remuneration| {
+            let mut job = Job {
+                remuneration: remuneration.into(),
+                ..job("Backend Engineer", "Company_1", "2022-07-27")
+            };
+            job.derive_fields(Local::now().date_naive());
+            job
+        });
+        assert_eq!(jobs[0].salary(), None);
+        assert_eq!(jobs[0].pays_at_least(1), None);
+        let jobs = jobs.map(JobRef::from);
+        assert_eq!(median_salary(jobs.iter()), Some(u32::MAX));
+        let mut sorted = jobs.to_vec();
+        JobOrder::Remuneration.sort(&mut sorted);
+        assert_eq!(sorted[0].remuneration, "$4294967295");
+    }
+
+    #[test]
+    fn test_recommend() {
+        let recent = Local::now().date_naive().format(DATE_FORMAT).to_string();
+        let repo = SoftwareJobsBuilder::new()
+            .import(vec![vec![
+                Job {
+                    remuneration: "$150k - $180k".into(),
+                    ..job("Backend Engineer", "Company_1", "2022-07-27")
+                },
+                Job {
+                    remuneration: "$90k".into(),
+                    ..job("Rust Backend Engineer", "Company_2", &recent)
+                },
+                Job {
+                    tags: vec!["rust".into()],
+                    remuneration: "$160k".into(),
+                    ..job("Backend Engineer", "Company_3", &recent)
+                },
+                job("Backend Engineer", "Company_4", &recent),
+                job("Frontend Engineer", "Company_5", &recent),
+            ]])
+            .index();
+        let config = Config {
+            boosts: vec![("rust".into(), 10)],
+            required_skills: vec![Skill::Backend],
+            min_salary: Some(120_000),
+            ..Default::default()
+        };
+
+        let jobs = repo.recommend(&config, 10);
+        let companies = jobs
+            .iter()
+            .map(|(_, job)| job.company.as_str())
+            .collect::<Vec<&str>>();
+        // Company_2 pays below the floor and Company_5 lacks the required skill
+        assert_eq!(companies, vec!["Company_3", "Company_4", "Company_1"]);
+        assert_eq!(jobs[0].0, 10 + 30 + 20);
+        assert_eq!(jobs[1].0, 30);
+        assert_eq!(
+            recommendation_score(&repo.all[4], &config, Local::now().date_naive()),
+            None
+        );
+        assert_eq!(repo.recommend(&config, 1).len(), 1);
+    }
+
+    #[test]
+    fn test_benefits() {
+        let repo = SoftwareJobsBuilder::new()
+            .import(vec![vec![
+                Job {
+                    tags: vec!["Unlimited PTO".into(), "Health Insurance".into()],
+                    ..job("Backend Engineer", "Company_1", "2022-07-27")
+                },
+                Job {
+                    tags: vec!["4 Day Week".into()],
+                    remuneration: "$100k + token allocation".into(),
+                    ..job("Rust Engineer", "Company_2", "2022-07-27")
+                },
+                Job {
+                    tags: vec!["Inequity research".into()],
+                    ..job("Frontend Engineer (4 Day Week)", "Company_3", "2022-07-27")
+                },
+            ]])
+            .index();
+        assert_eq!(
+            repo.all[0].benefits,
+            vec!["unlimited PTO", "health insurance"]
+        );
+        assert_eq!(repo.all[1].benefits, vec!["4-day week", "equity"]);
+        // titles aren't searched, and phrases must be whole words
+        assert!(repo.all[2].benefits.is_empty());
+
+        let benefit = benefit_name("Unlimited PTO").unwrap();
+        let jobs = repo.fetch_benefit(benefit);
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].title, "Backend Engineer");
+        assert_eq!(benefit_name("four-day week"), Some("4-day week"));
+        assert_eq!(benefit_name("free lunch"), None);
+    }
+
+    #[test]
+    fn test_fetch_posted_between() {
+        let repo = SoftwareJobsBuilder::new()
+            .import(vec![vec![
+                job("Backend Engineer", "Company_1", "2024-01-01"),
+                job("Frontend Engineer", "Company_2", "2024-02-15"),
+                job("Rust Engineer", "Company_3", "2023-12-31"),
+                job("Solidity Engineer", "Company_4", "unknown"),
+            ]])
+            .index();
+        let date = |s: &str| NaiveDate::parse_from_str(s, DATE_FORMAT).unwrap();
+        let titles = |jobs: Vec<JobRef>| {
+            jobs.iter()
+                .map(|job| job.title.clone())
+                .collect::<Vec<String>>()
+        };
+
+        assert_eq!(
+            titles(repo.fetch_posted_between(date("2024-01-01"), None)),
+            vec!["Frontend Engineer", "Backend Engineer"]
+        );
+        assert_eq!(
+            titles(repo.fetch_posted_between(date("2023-12-31"), Some(date("2024-01-01")))),
+            vec!["Backend Engineer", "Rust Engineer"]
+        );
+        assert!(repo
+            .fetch_posted_between(date("2024-03-01"), None)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_fetch_salary_above() {
+        let paid = |title: &str, remuneration: &str| Job {
+            remuneration: remuneration.into(),
+            ..job(title, "Company_1", "2022-07-27")
+        };
+        let repo = SoftwareJobsBuilder::new()
+            .import(vec![vec![
+                paid("Backend Engineer", "$90k - $140k"),
+                paid("Frontend Engineer", "$80k - $100k"),
+                paid("Rust Engineer", "$120,000"),
+                paid("Solidity Engineer", ""),
+            ]])
+            .index();
+        let titles = |jobs: Vec<JobRef>| {
+            jobs.iter()
+                .map(|job| job.title.clone())
+                .sorted()
+                .collect::<Vec<String>>()
+        };
+
+        assert_eq!(
+            titles(repo.fetch_salary_above(120_000, false)),
+            vec!["Backend Engineer", "Rust Engineer"]
+        );
+        assert_eq!(
+            titles(repo.fetch_salary_above(120_000, true)),
+            vec!["Backend Engineer", "Rust Engineer", "Solidity Engineer"]
+        );
+        assert!(repo.fetch_salary_above(200_000, false).is_empty());
+        assert_eq!(
+            titles(repo.fetch_has_salary()),
+            vec!["Backend Engineer", "Frontend Engineer", "Rust Engineer"]
+        );
+    }
+
+    #[test]
+    fn test_confidence() {
+        let full = Job {
+            remuneration: "$120k - $150k".into(),
+            tags: vec!["rust".into()],
+            ..job("Backend Engineer", "Company_1", "2022-07-27")
+        };
+        let sparse = Job {
+            location: "".into(),
+            apply: "".into(),
+            ..job("Backend Engineer", "Company_1", "a few days ago")
+        };
+
+        assert_eq!(full.confidence(), 100);
+        assert_eq!(sparse.confidence(), 0);
+        assert!(
+            Job {
+                remuneration: "".into(),
+                ..full.clone()
+            }
+            .confidence()
+                > sparse.confidence()
+        );
+    }
+
+    #[test]
+    fn test_compare_skills() {
+        let paid = |title: &str, location: &str, remuneration: &str| Job {
+            location: location.into(),
+            remuneration: remuneration.into(),
+            ..job(title, "Company_1", "2022-07-27")
+        };
+        let repo = SoftwareJobsBuilder::new()
+            .import(vec![vec![
+                paid("Senior Backend Engineer", "Remote", "$120k - $160k"),
+                paid("Backend Engineer", "Berlin, Germany", "$100,000"),
+                paid("Staff Backend Engineer", "Remote", ""),
+                paid("Frontend Engineer", "Remote", "$40 - $60 per hour"),
+            ]])
+            .index();
+
+        let comparison = repo.compare_skills(&Skill::Backend, &Skill::Frontend);
+        assert_eq!(comparison.counts.get("Total"), Some((3, 1)));
+        assert_eq!(comparison.counts.get("Senior"), Some((1, 0)));
+        assert_eq!(comparison.counts.get("Staff"), Some((1, 0)));
+        assert_eq!(comparison.counts.get("Remote"), Some((2, 1)));
+        assert_eq!(comparison.counts.get("Onsite"), Some((1, 0)));
+        assert_eq!(comparison.median_salary, (Some(120_000), None));
+        assert!(comparison.to_string().contains("$120k"));
+
+        let empty = repo.compare_skills(&Skill::DevOps, &Skill::Blockchain);
+        assert_eq!(empty.counts.get("Total"), Some((0, 0)));
+        assert_eq!(empty.median_salary, (None, None));
+    }
+
+    #[test]
+    fn test_fetch_location() {
+        let at = |location: &str, date_posted: &str| Job {
+            location: location.into(),
+            ..job("Backend Engineer", "Company_1", date_posted)
+        };
+        let repo = SoftwareJobsBuilder::new()
+            .import(vec![vec![
+                at("Remote", "2022-07-27"),
+                at("Berlin, Germany", "2022-07-28"),
+                at("Remote - US", "2022-07-29"),
+            ]])
+            .index();
+
+        let remote = repo.fetch_location(&"REMOTE".parse().unwrap());
+        let dates = remote
+            .iter()
+            .map(|job| job.date_posted.as_str())
+            .collect_vec();
+        assert_eq!(dates, vec!["2022-07-29", "2022-07-27"]);
+        assert_eq!(repo.fetch_location(&Location::Onsite).len(), 1);
+        assert!("hybrid".parse::<Location>().is_err());
+    }
+
+    #[test]
+    fn test_fetch_stack() {
+        let tagged = |title: &str, tags: &[&str]| Job {
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            ..job(title, "Company_1", "2022-07-27")
+        };
+        let repo = SoftwareJobsBuilder::new()
+            .import(vec![vec![
+                tagged("Backend Engineer", &["Rust", "Tokio"]),
+                tagged("Rust Engineer", &["TOKIO"]),
+                tagged("Backend Engineer", &["rust"]),
+                tagged("Trust Engineer", &["tokio"]),
+            ]])
+            .index();
+
+        let titles = |jobs: Vec<JobRef>| jobs.iter().map(|job| job.title.clone()).collect_vec();
+        assert_eq!(
+            titles(repo.fetch_stack(&["rust", "tokio"])),
+            vec!["Backend Engineer", "Rust Engineer"]
+        );
+        assert_eq!(repo.fetch_stack(&["rust"]).len(), 3);
+        assert!(repo.fetch_stack(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_timeline() {
+        let repo = SoftwareJobsBuilder::new()
+            .import(vec![vec![
+                job("Backend Engineer", "Company_1", "2022-07-25"),
+                job("Backend Engineer", "Company_2", "2022-07-31"),
+                job("Backend Engineer", "Company_3", "2022-08-01"),
+                job("Backend Engineer", "Company_4", "2022-01-02"),
+                job("Backend Engineer", "Company_5", "recently"),
+            ]])
+            .index();
+
+        let timeline = repo.timeline();
+        assert_eq!(
+            timeline.weeks,
+            vec![
+                ("2021-W52".to_string(), 1),
+                ("2022-W30".to_string(), 2),
+                ("2022-W31".to_string(), 1)
+            ]
+        );
+        assert_eq!(timeline.unknown, 1);
+        assert!(timeline.to_string().contains("2022-W30     2  ##\n"));
     }
 }
@@ -1,20 +1,36 @@
 //! The scraper module contains all website scraper code.
 //! Websites often change, so the scrapers should be tested often and updated when needed.
-//! Currently most scrapers only scrape the first page of their site; this can be changed by creating
-//! a loop and adding a page number query string, e.g. `https://jobsite.com/engineering?page=1` for
-//! as many pages as required.
-
+//! Scrapers read as many pages of their site as it has, up to the config's `max_pages`, by adding a
+//! page number query string, e.g. `https://jobsite.com/engineering?page=2`.
+//! With the `async` feature, each scraper also has an async implementation, `scrape_async`, making
+//! the same requests with an async client so that sites needn't each be scraped in a thread.
+
+#[cfg(feature = "async")]
+use std::future::Future;
+use std::io::{self, Read};
+use std::ops::ControlFlow;
+#[cfg(feature = "async")]
+use std::pin::Pin;
+use std::sync::{Condvar, Mutex, OnceLock};
 use std::thread;
+use std::time::Duration;
 
 use itertools::Itertools;
+use log::{debug, warn};
 use regex::Regex;
+use reqwest::blocking::Client;
+use scraper::ElementRef;
 use scraper::Html;
 use scraper::Selector;
 use thiserror::Error;
+use url::Url;
 
+use crate::config::MAX_RETRY_DELAY_MS;
 use crate::repository::{Job, THREAD_ERROR};
 use crate::site::{
-    CryptoJobsList, Formatter, NearJobs, Site, SolanaJobs, SubstrateJobs, UseWeb3, Web3Careers,
+    add_location, normalize_location, parse_date, CryptoJobsList, Formatter, NearJobs, RemoteOk,
+    Site, SolanaJobs, SubstrateJobs, UseWeb3, Web3Careers, CRYPTO_JOBS_LIST_URL, NEAR_JOBS_URL,
+    REMOTE_OK_URL, SOLANA_JOBS_URL, SUBSTRATE_JOBS_URL, USE_WEB3_URL, WEB3_CAREERS_URL,
 };
 
 type BoxedError = Box<dyn std::error::Error + Send>;
@@ -32,6 +48,348 @@ pub enum Error {
     Parser(#[source] BoxedError),
     #[error("Could not get {0}")]
     Iterator(&'static str),
+    #[error("A job has no {0}")]
+    MissingField(&'static str),
+    #[error("The site returned no jobs; its layout may have changed")]
+    EmptyResult,
+}
+
+impl Error {
+    /// Returns whether a request that failed with this error may succeed if retried: the site
+    /// couldn't be reached, was rate limited, or had a server error.
+    fn is_transient(&self) -> bool {
+        match self {
+            Self::Request(_) => true,
+            Self::Response(code) => *code == 429 || *code >= 500,
+            _ => false,
+        }
+    }
+}
+
+/// Represents how requests that fail with a transient error are retried.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// The number of times a request is retried after the first attempt.
+    pub retries: u32,
+    /// The delay before the first retry, doubled for each retry after it.
+    pub delay: Duration,
+}
+
+impl RetryPolicy {
+    /// Returns the delay before retry number `attempt`, counting from 1: the policy's delay doubled
+    /// for each retry before it, but never longer than [`MAX_RETRY_DELAY_MS`].
+    pub fn delay_before(&self, attempt: u32) -> Duration {
+        let factor = 2_u32.saturating_pow(attempt.saturating_sub(1));
+        self.delay
+            .saturating_mul(factor)
+            .min(Duration::from_millis(MAX_RETRY_DELAY_MS))
+    }
+
+    /// Decides whether a request to `url` that failed with `err`, after `attempt` retries so far,
+    /// is retried. If so, counts the retry and returns the delay to wait before it. Shared by the
+    /// blocking and async requests.
+    fn next_retry(&self, url: &str, attempt: &mut u32, err: &Error) -> Option<Duration> {
+        if !err.is_transient() || *attempt >= self.retries {
+            return None;
+        }
+        *attempt += 1;
+        let delay = self.delay_before(*attempt);
+        warn!("Retrying {url} in {delay:?} (attempt {attempt}): {err}");
+        Some(delay)
+    }
+}
+
+/// Represents the settings a scraper makes its requests with, taken from the config by
+/// `Config::scrape_options`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScrapeOptions {
+    /// How requests that fail with a transient error are retried.
+    pub retry: RetryPolicy,
+    /// The most pages read from a site.
+    pub max_pages: usize,
+    /// The largest response body in bytes read; a larger one fails the request.
+    pub max_body_bytes: u64,
+    /// How long a request may take before it fails.
+    pub timeout: Duration,
+}
+
+/// A counting semaphore limiting how many scraper threads scrape at once.
+pub(crate) struct ScrapeLimit {
+    available: Mutex<usize>,
+    released: Condvar,
+}
+
+impl ScrapeLimit {
+    /// Creates a limit allowing `permits` scrapes at once.
+    pub(crate) fn new(permits: usize) -> Self {
+        Self {
+            available: Mutex::new(permits),
+            released: Condvar::new(),
+        }
+    }
+
+    /// Waits until fewer scrapes than the limit are running, returning a permit that lets another
+    /// scrape start when dropped.
+    pub(crate) fn acquire(&self) -> ScrapePermit<'_> {
+        let available = self.available.lock().expect(THREAD_ERROR);
+        let mut available = self
+            .released
+            .wait_while(available, |available| *available == 0)
+            .expect(THREAD_ERROR);
+        *available -= 1;
+        ScrapePermit(self)
+    }
+}
+
+/// A permit to scrape, returned to its limit when dropped.
+pub(crate) struct ScrapePermit<'a>(&'a ScrapeLimit);
+
+impl Drop for ScrapePermit<'_> {
+    fn drop(&mut self) {
+        // a poisoned lock means a scraper thread panicked, which is reported when it's joined
+        if let Ok(mut available) = self.0.available.lock() {
+            *available += 1;
+            self.0.released.notify_one();
+        }
+    }
+}
+
+/// The most cells a Web3Careers job row has: title, company, date posted, location, remuneration
+/// and tags.
+const WEB3_CAREERS_COLUMNS: usize = 6;
+
+/// Gets a page body, failing if the response is unsuccessful or the body is larger than the
+/// options' maximum body size. Transient failures are retried per the options' retry policy.
+pub(crate) fn get_body(url: &str, options: ScrapeOptions) -> Result<String, Error> {
+    get_with_retry(url, options.retry, |url| fetch_body(url, options))
+}
+
+/// Gets a page body with `get`, retrying transient failures as many times as the policy allows,
+/// waiting the policy's delay before each retry (see [`RetryPolicy::delay_before`]).
+fn get_with_retry<F>(url: &str, policy: RetryPolicy, mut get: F) -> Result<String, Error>
+where
+    F: FnMut(&str) -> Result<String, Error>,
+{
+    let mut attempt = 0;
+    loop {
+        match get(url) {
+            Err(err) => match policy.next_retry(url, &mut attempt, &err) {
+                Some(delay) => thread::sleep(delay),
+                None => return Err(err),
+            },
+            result => return result,
+        }
+    }
+}
+
+/// Returns the HTTP client shared by every scraper, building it on first use.
+fn client() -> Result<&'static Client, Error> {
+    static CLIENT: OnceLock<Client> = OnceLock::new();
+    if let Some(client) = CLIENT.get() {
+        return Ok(client);
+    }
+    let client = Client::builder()
+        .build()
+        .map_err(|err| Error::Request(Box::new(err)))?;
+    Ok(CLIENT.get_or_init(|| client))
+}
+
+/// Gets a page body with a single request, per the options' timeout and maximum body size.
+fn fetch_body(url: &str, options: ScrapeOptions) -> Result<String, Error> {
+    fetch_body_with(client()?, url, options)
+}
+
+/// Gets a page body with a single request made by the given client. The request fails with
+/// `Error::Request` if it takes longer than the options' timeout, so that a hung site can't block a
+/// scraper thread indefinitely.
+fn fetch_body_with(client: &Client, url: &str, options: ScrapeOptions) -> Result<String, Error> {
+    let response = client
+        .get(url)
+        .timeout(options.timeout)
+        .send()
+        .map_err(|err| Error::Request(Box::new(err)))?;
+    if !response.status().is_success() {
+        Err(Error::Response(response.status().as_u16()))?;
+    }
+    read_body(response, options.max_body_bytes)
+}
+
+/// Adds a page number query string to a URL.
+fn page_url(url: &str, page: usize) -> String {
+    let separator = if url.contains('?') { '&' } else { '?' };
+    format!("{url}{separator}page={page}")
+}
+
+/// Reads pages 1, 2, 3... with `fetch` and parses their jobs with `parse`, stopping at the first
+/// page with no jobs, or no jobs that weren't on an earlier page (for sites that ignore the page
+/// number), or at page `max_pages`. An error on the first page is returned; an error on a later
+/// page ends pagination with the jobs read so far.
+fn scrape_pages<F, P>(
+    url: &str,
+    max_pages: usize,
+    mut fetch: F,
+    parse: P,
+) -> Result<Vec<Job>, Error>
+where
+    F: FnMut(&str) -> Result<String, Error>,
+    P: Fn(&str) -> Result<Vec<Job>, Error>,
+{
+    let mut pages = Pages::new(url);
+    for page in 1..=max_pages {
+        let result = fetch(&page_url(url, page)).and_then(|body| parse(&body));
+        if pages.push(page, result)?.is_break() {
+            break;
+        }
+    }
+    Ok(pages.finish())
+}
+
+/// Accumulates the jobs read from a site's pages and decides when to stop reading, for both the
+/// blocking and async page loops.
+struct Pages<'a> {
+    url: &'a str,
+    jobs: Vec<Job>,
+}
+
+impl<'a> Pages<'a> {
+    fn new(url: &'a str) -> Self {
+        Self { url, jobs: vec![] }
+    }
+
+    /// Adds the jobs read from page `page`, or the error reading it. Returns whether to read the
+    /// next page: not after a page with no jobs that weren't on an earlier page, or after an error
+    /// on a later page. An error on the first page is returned.
+    fn push(
+        &mut self,
+        page: usize,
+        result: Result<Vec<Job>, Error>,
+    ) -> Result<ControlFlow<()>, Error> {
+        let url = self.url;
+        let page_jobs = match result {
+            Ok(page_jobs) => page_jobs,
+            Err(err) if page == 1 => Err(err)?,
+            Err(err) => {
+                warn!("Stopped reading {url} at page {page}: {err}");
+                return Ok(ControlFlow::Break(()));
+            }
+        };
+        if !page_jobs.iter().any(|job| !self.jobs.contains(job)) {
+            debug!("Page {page} of {url} has no new jobs");
+            return Ok(ControlFlow::Break(()));
+        }
+        debug!("Read {} jobs from page {page} of {url}", page_jobs.len());
+        self.jobs.extend(page_jobs);
+        Ok(ControlFlow::Continue(()))
+    }
+
+    /// Returns the jobs read, without duplicates.
+    fn finish(self) -> Vec<Job> {
+        self.jobs.into_iter().unique().collect()
+    }
+}
+
+/// Reads a body of at most `max` bytes, so that a huge or endless response can't exhaust memory.
+fn read_body<R: Read>(reader: R, max: u64) -> Result<String, Error> {
+    let mut body = vec![];
+    reader
+        .take(max + 1)
+        .read_to_end(&mut body)
+        .map_err(|err| Error::Parser(Box::new(err)))?;
+    if body.len() as u64 > max {
+        Err(body_too_large(max))?;
+    }
+    Ok(String::from_utf8_lossy(&body).into_owned())
+}
+
+/// Returns the error for a response body larger than `max` bytes.
+fn body_too_large(max: u64) -> Error {
+    Error::Parser(Box::new(io::Error::other(format!(
+        "response body exceeds the limit of {max} bytes"
+    ))))
+}
+
+/// Gets a page body as `get_body` does, with an async request.
+#[cfg(feature = "async")]
+pub(crate) async fn get_body_async(url: &str, options: ScrapeOptions) -> Result<String, Error> {
+    let mut attempt = 0;
+    loop {
+        match fetch_body_async(async_client()?, url, options).await {
+            Err(err) => match options.retry.next_retry(url, &mut attempt, &err) {
+                Some(delay) => tokio::time::sleep(delay).await,
+                None => return Err(err),
+            },
+            result => return result,
+        }
+    }
+}
+
+/// Returns the async HTTP client shared by every async scraper, building it on first use.
+#[cfg(feature = "async")]
+fn async_client() -> Result<&'static reqwest::Client, Error> {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    if let Some(client) = CLIENT.get() {
+        return Ok(client);
+    }
+    let client = reqwest::Client::builder()
+        .build()
+        .map_err(|err| Error::Request(Box::new(err)))?;
+    Ok(CLIENT.get_or_init(|| client))
+}
+
+/// Gets a page body with a single async request made by the given client, per the options' timeout,
+/// reading it a chunk at a time so that a body larger than the maximum body size fails without being
+/// read in full.
+#[cfg(feature = "async")]
+async fn fetch_body_async(
+    client: &reqwest::Client,
+    url: &str,
+    options: ScrapeOptions,
+) -> Result<String, Error> {
+    let max = options.max_body_bytes;
+    let mut response = client
+        .get(url)
+        .timeout(options.timeout)
+        .send()
+        .await
+        .map_err(|err| Error::Request(Box::new(err)))?;
+    if !response.status().is_success() {
+        Err(Error::Response(response.status().as_u16()))?;
+    }
+    let mut body = vec![];
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|err| Error::Request(Box::new(err)))?
+    {
+        body.extend_from_slice(&chunk);
+        if body.len() as u64 > max {
+            Err(body_too_large(max))?;
+        }
+    }
+    Ok(String::from_utf8_lossy(&body).into_owned())
+}
+
+/// Reads pages and parses their jobs as `scrape_pages` does, with async requests.
+#[cfg(feature = "async")]
+async fn scrape_pages_async<P>(
+    url: &str,
+    options: ScrapeOptions,
+    parse: P,
+) -> Result<Vec<Job>, Error>
+where
+    P: Fn(&str) -> Result<Vec<Job>, Error>,
+{
+    let mut pages = Pages::new(url);
+    for page in 1..=options.max_pages {
+        let body = get_body_async(&page_url(url, page), options).await;
+        if pages
+            .push(page, body.and_then(|body| parse(&body)))?
+            .is_break()
+        {
+            break;
+        }
+    }
+    Ok(pages.finish())
 }
 
 /// All website structs must implement the Scraper trait.
@@ -50,8 +408,17 @@ pub trait Scraper {
     ///     pub site: &'static str,
     /// }
     /// ```
-    /// as defined in repository module.
-    fn scrape(self) -> Result<Self, Error>
+    /// as defined in repository module. Requests are made with the given options.
+    fn scrape(self, options: ScrapeOptions) -> Result<Self, Error>
+    where
+        Self: Sized;
+
+    /// Scrapes the job website as `scrape` does, with async requests.
+    #[cfg(feature = "async")]
+    fn scrape_async(
+        self,
+        options: ScrapeOptions,
+    ) -> impl Future<Output = Result<Self, Error>> + Send
     where
         Self: Sized;
 
@@ -59,132 +426,269 @@ pub trait Scraper {
     fn get_selector(selectors: &str) -> Result<Selector, Error> {
         Selector::parse(selectors).map_err(|err| Error::Selector(err.to_string()))
     }
+
+    /// A default method. Gets a selector for each of a site's selector strings, in order.
+    fn get_selectors<const N: usize>(selectors: [&str; N]) -> Result<[Selector; N], Error> {
+        let selectors = selectors
+            .iter()
+            .map(|selectors| Self::get_selector(selectors))
+            .collect::<Result<Vec<Selector>, Error>>()?;
+        Ok(selectors
+            .try_into()
+            .expect("a selector is parsed for each string"))
+    }
+}
+
+/// The selectors Web3Careers pages are parsed with: the table row, cell, time, link, title and
+/// company.
+const WEB3_CAREERS_SELECTORS: [&str; 6] = ["tr.table_row", "td", "time", "a", "h2", "h3"];
+/// The selectors Use Web3 pages are parsed with.
+const USE_WEB3_SELECTORS: [&str; 5] = [
+    "div.panel_inner__YQLRW",
+    "div.panel_actions__T498Q>div>a",
+    "a",
+    "span",
+    "div.panel_border___58nj",
+];
+/// The selectors Crypto Jobs List pages are parsed with.
+const CRYPTO_JOBS_LIST_SELECTORS: [&str; 5] = [
+    "ul>li",
+    "span>h2>a",
+    "span",
+    "span>span>span",
+    "span>span>a",
+];
+/// The selectors Remote OK pages are parsed with: the row, title, company, location items, tags and
+/// time posted.
+const REMOTE_OK_SELECTORS: [&str; 6] = [
+    "tr.job",
+    "td.company_and_position h2",
+    "td.company_and_position h3",
+    "td.company_and_position div.location",
+    "td.tags h3",
+    "td.time time",
+];
+/// The selectors the sites sharing the `Common` HTML structure are parsed with.
+const COMMON_SELECTORS: [&str; 6] = [
+    "div.infinite-scroll-component__outerdiv>div>div",
+    r#"div[itemprop=title]"#,
+    r#"meta[itemprop=name]"#,
+    "span",
+    r#"meta[itemprop=datePosted]"#,
+    r#"a[data-testid=read-more]"#,
+];
+
+/// A site's jobs being scraped with async requests.
+#[cfg(feature = "async")]
+pub type ScrapeFuture = Pin<Box<dyn Future<Output = Result<Vec<Job>, Error>> + Send>>;
+
+/// Represents a site that can be scraped, with everything needed to scrape it, so that a site is
+/// added by adding it to [`SITES`].
+#[derive(Clone, Copy)]
+pub struct SiteEntry {
+    /// The site's name, e.g. for `refresh site <name>`.
+    pub name: &'static str,
+    /// The site's URL before any runtime override.
+    pub default_url: &'static str,
+    /// Returns the site's URL, including any runtime override.
+    pub url: fn() -> &'static str,
+    /// The selectors the site's pages are parsed with.
+    pub selectors: &'static [&'static str],
+    /// Scrapes the site, returning its jobs.
+    pub scrape: fn(ScrapeOptions) -> Result<Vec<Job>, Error>,
+    /// Scrapes the site as `scrape` does, with async requests.
+    #[cfg(feature = "async")]
+    pub scrape_async: fn(ScrapeOptions) -> ScrapeFuture,
+}
+
+impl SiteEntry {
+    /// Creates the entry for the site `S`.
+    pub const fn new<S>(
+        name: &'static str,
+        default_url: &'static str,
+        selectors: &'static [&'static str],
+    ) -> Self
+    where
+        S: Site + Scraper + 'static,
+    {
+        Self {
+            name,
+            default_url,
+            url: site_url::<S>,
+            selectors,
+            scrape: scrape_jobs::<S>,
+            #[cfg(feature = "async")]
+            scrape_async: scrape_jobs_async::<S>,
+        }
+    }
+}
+
+/// Returns the URL of the site `S`.
+fn site_url<S: Site>() -> &'static str {
+    S::new().get_url()
+}
+
+/// Scrapes the site `S`, returning its jobs.
+fn scrape_jobs<S: Site + Scraper>(options: ScrapeOptions) -> Result<Vec<Job>, Error> {
+    S::new().scrape(options).map(Site::into_jobs)
+}
+
+/// Scrapes the site `S` with async requests, returning its jobs.
+#[cfg(feature = "async")]
+fn scrape_jobs_async<S: Site + Scraper + 'static>(options: ScrapeOptions) -> ScrapeFuture {
+    Box::pin(async move { S::new().scrape_async(options).await.map(Site::into_jobs) })
+}
+
+/// Every site scraped, in the order their jobs are listed.
+pub const SITES: [SiteEntry; 7] = [
+    SiteEntry::new::<Web3Careers>("web3careers", WEB3_CAREERS_URL, &WEB3_CAREERS_SELECTORS),
+    SiteEntry::new::<UseWeb3>("useweb3", USE_WEB3_URL, &USE_WEB3_SELECTORS),
+    SiteEntry::new::<CryptoJobsList>(
+        "cryptojobslist",
+        CRYPTO_JOBS_LIST_URL,
+        &CRYPTO_JOBS_LIST_SELECTORS,
+    ),
+    SiteEntry::new::<SolanaJobs>("solanajobs", SOLANA_JOBS_URL, &COMMON_SELECTORS),
+    SiteEntry::new::<SubstrateJobs>("substratejobs", SUBSTRATE_JOBS_URL, &COMMON_SELECTORS),
+    SiteEntry::new::<NearJobs>("nearjobs", NEAR_JOBS_URL, &COMMON_SELECTORS),
+    SiteEntry::new::<RemoteOk>("remoteok", REMOTE_OK_URL, &REMOTE_OK_SELECTORS),
+];
+
+/// Parses every site's selectors, so that a broken selector fails Job Hunt at startup, naming the
+/// site, rather than part way through a scrape.
+pub fn check_selectors() -> Result<(), Error> {
+    for site in SITES {
+        for selector in site.selectors {
+            Selector::parse(selector).map_err(|err| {
+                Error::Selector(format!("{} selector \"{selector}\": {err}", site.name))
+            })?;
+        }
+    }
+    Ok(())
 }
 
 impl Web3Careers {
-    /// A stand alone scrape function for Web3Careers that can be moved into a new thread.
-    /// This function is used to scrape a specific page, e.g. .../?page=1.
-    fn _scrape(i: i32, site: &'static str) -> Result<Vec<Job>, Error> {
+    /// Parses the jobs table from a Web3Careers page body. Cells are identified by their markup
+    /// rather than their position where possible, so that reordered columns are still read
+    /// correctly: the title is the cell with an `h2`, the company the cell with an `h3`, the date
+    /// posted the cell with a `time`, and the tags the cell of links. Of the remaining cells, one
+    /// that looks like pay is the remuneration and the other the location. Only the title, company
+    /// and date posted are required. Rows that can't be read, e.g. with no date or too many cells,
+    /// are skipped; if every row is skipped the page layout has likely changed, which is an error.
+    fn parse_page(body: &str, site: &'static str) -> Result<Vec<Job>, Error> {
         let mut jobs = vec![];
-        let response = reqwest::blocking::get(format!("{}?page={}", site, i))
-            .map_err(|err| Error::Request(Box::new(err)))?;
-        if !response.status().is_success() {
-            Err(Error::Response(response.status().as_u16()))?;
-        }
-        let body = response
-            .text()
-            .map_err(|err| Error::Parser(Box::new(err)))?;
-        let document = Html::parse_document(&body);
+        let mut skipped = 0;
+        let document = Html::parse_document(body);
 
         // HTML selectors
-        let table_row_selector = Self::get_selector("tr.table_row")?;
-        let td_selector = Self::get_selector("td")?;
-        let time_selector = Self::get_selector("time")?;
-        let a_selector = Self::get_selector("a")?;
+        let [table_row_selector, td_selector, time_selector, a_selector, h2_selector, h3_selector] =
+            Self::get_selectors(WEB3_CAREERS_SELECTORS)?;
+
+        // pay, e.g. "$120k - $150k" or "80k"
+        let pay_regex = Regex::new(r"[$€£]|(?i)\d\s*k\b").unwrap();
+
+        let has = |cell: &ElementRef, selector: &Selector| cell.select(selector).next().is_some();
+        let text = |cell: &ElementRef| cell.text().collect::<String>().trim().replace('\n', " ");
 
         for el in document.select(&table_row_selector) {
+            let cells = el.select(&td_selector).collect::<Vec<ElementRef>>();
+            let find = |selector: &Selector| cells.iter().position(|cell| has(cell, selector));
+            let (Some(time), title, company) = (
+                find(&time_selector),
+                find(&h2_selector).unwrap_or(0),
+                find(&h3_selector).unwrap_or(1),
+            ) else {
+                skipped += 1;
+                continue;
+            };
+            if cells.len() > WEB3_CAREERS_COLUMNS
+                || company >= cells.len()
+                || [title, company, time].iter().unique().count() < 3
+            {
+                skipped += 1;
+                continue;
+            }
+
             let apply = format!(
                 "{}{}",
                 site,
                 Self::format_apply_link(el.value().attr("onclick").unwrap_or(""))
             );
-
-            let mut element_iterator = el.select(&td_selector);
-
-            let title_element = element_iterator
-                .next()
-                .ok_or(Error::Iterator("job title"))?;
-            let title = title_element.text().collect::<String>().trim().to_owned();
-
-            let company_element = element_iterator.next().ok_or(Error::Iterator("company"))?;
-            let company = company_element.text().collect::<String>().trim().to_owned();
-
-            let date_posted_element = element_iterator.next().ok_or(Error::Iterator("time"))?;
-            let date_posted_element = date_posted_element
+            let date_posted = cells[time]
                 .select(&time_selector)
                 .next()
-                .ok_or(Error::Iterator("time"))?;
-            let date_posted = date_posted_element
-                .value()
-                .attr("datetime")
-                .unwrap_or("")
-                .split(' ')
-                .next()
-                .unwrap_or("")
-                .to_owned();
-
-            let location_element = element_iterator.next().ok_or(Error::Iterator("location"))?;
-            let location = location_element
-                .text()
-                .collect::<String>()
-                .trim()
-                .replace('\n', " ");
-
-            let remuneration_element = element_iterator
-                .next()
-                .ok_or(Error::Iterator("remuneration"))?;
-            let remuneration = remuneration_element
-                .text()
-                .collect::<String>()
-                .trim()
-                .to_owned();
-
-            let mut tags = Vec::new();
-            let tag_element = element_iterator.next().ok_or(Error::Iterator("tags"))?;
-            tag_element
-                .select(&a_selector)
-                .for_each(|tag| tags.push(tag.text().collect::<String>().trim().to_owned()));
+                .and_then(|el| el.value().attr("datetime"))
+                .unwrap_or("");
+
+            let rest = cells
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| ![title, company, time].contains(i))
+                .map(|(_, cell)| cell);
+            let (tag_cells, text_cells): (Vec<&ElementRef>, Vec<&ElementRef>) =
+                rest.partition(|cell| has(cell, &a_selector));
+            let tags = tag_cells
+                .iter()
+                .flat_map(|cell| cell.select(&a_selector))
+                .map(|tag| tag.text().collect::<String>().trim().to_owned())
+                .collect();
+            let mut text_cells = text_cells.into_iter().map(text).collect::<Vec<String>>();
+            let remuneration = match text_cells.iter().position(|t| pay_regex.is_match(t)) {
+                Some(i) => text_cells.remove(i),
+                None if text_cells.len() > 1 => text_cells.remove(1),
+                None => "".to_string(),
+            };
+            let location = text_cells.into_iter().next().unwrap_or_default();
 
             jobs.push(Job {
-                title,
-                company,
-                date_posted,
+                title: text(&cells[title]),
+                company: text(&cells[company]),
+                date_posted: parse_date(Web3Careers::DATE_SOURCE, date_posted),
                 location,
                 remuneration,
                 tags,
                 apply,
                 site,
+                ..Default::default()
             });
         }
 
+        if jobs.is_empty() && skipped > 0 {
+            Err(Error::Iterator("any job rows"))?;
+        }
         Ok(jobs)
     }
 }
 
 impl Scraper for Web3Careers {
-    fn scrape(mut self) -> Result<Self, Error> {
-        let mut handles = vec![];
-        let url = self.get_url();
-
-        (1..6).for_each(|i| handles.push(thread::spawn(move || Self::_scrape(i, url))));
-        for h in handles {
-            self.jobs.extend(h.join().expect(THREAD_ERROR)?)
-        }
-
-        self.jobs = self.jobs.into_iter().unique().collect();
+    fn scrape(mut self, options: ScrapeOptions) -> Result<Self, Error> {
+        let site = self.get_url();
+        self.jobs = scrape_pages(
+            site,
+            options.max_pages,
+            |url| get_body(url, options),
+            |body| Self::parse_page(body, site),
+        )?;
+        Ok(self)
+    }
 
+    #[cfg(feature = "async")]
+    async fn scrape_async(mut self, options: ScrapeOptions) -> Result<Self, Error> {
+        let site = self.get_url();
+        self.jobs = scrape_pages_async(site, options, |body| Self::parse_page(body, site)).await?;
         Ok(self)
     }
 }
 
-impl Scraper for UseWeb3 {
-    fn scrape(mut self) -> Result<Self, Error> {
-        let response = reqwest::blocking::get(format!("{}{}", self.get_url(), "/t/engineering/"))
-            .map_err(|err| Error::Request(Box::new(err)))?;
-        if !response.status().is_success() {
-            Err(Error::Response(response.status().as_u16()))?;
-        }
-        let body = response
-            .text()
-            .map_err(|err| Error::Parser(Box::new(err)))?;
-        let document = Html::parse_document(&body);
+impl UseWeb3 {
+    /// Parses the jobs from a Use Web3 page body.
+    fn parse_page(body: &str, site: &'static str) -> Result<Vec<Job>, Error> {
+        let mut jobs = vec![];
+        let document = Html::parse_document(body);
 
         // HTML selectors
-        let panel_inner_selector = Self::get_selector("div.panel_inner__YQLRW")?;
-        let panel_actions_selector = Self::get_selector("div.panel_actions__T498Q>div>a")?;
-        let a_selector = Self::get_selector("a")?;
-        let span_selector = Self::get_selector("span")?;
-        let panel_border_selector = Self::get_selector("div.panel_border___58nj")?;
+        let [panel_inner_selector, panel_actions_selector, a_selector, span_selector, panel_border_selector] =
+            Self::get_selectors(USE_WEB3_SELECTORS)?;
 
         for el in document.select(&panel_inner_selector) {
             let mut element_iterator = el.select(&a_selector);
@@ -219,8 +723,8 @@ impl Scraper for UseWeb3 {
             let mut remuneration = "".to_string();
             el.select(&panel_border_selector).for_each(|item| {
                 let i = item.text().collect::<String>().trim().to_owned();
-                if i.contains('🌐') && !location.to_lowercase().contains("remote") {
-                    location = format!("{}, {}", location, i.replace("🌐 ", ""));
+                if i.contains('🌐') {
+                    location = add_location(&location, &i);
                 }
                 if i.contains('💰') {
                     remuneration = Self::format_remuneration(i);
@@ -231,7 +735,7 @@ impl Scraper for UseWeb3 {
             let apply_element = apply_iterator.next().ok_or(Error::Iterator("apply link"))?;
             let apply = apply_element.value().attr("href").unwrap_or("").to_owned();
 
-            self.jobs.push(Job {
+            jobs.push(Job {
                 title,
                 company,
                 date_posted,
@@ -239,35 +743,48 @@ impl Scraper for UseWeb3 {
                 remuneration,
                 tags: Vec::new(),
                 apply,
-                site: self.get_url(),
+                site,
+                ..Default::default()
             });
         }
 
-        self.jobs = self.jobs.into_iter().unique().collect();
+        Ok(jobs)
+    }
+}
+
+impl Scraper for UseWeb3 {
+    fn scrape(mut self, options: ScrapeOptions) -> Result<Self, Error> {
+        let site = self.get_url();
+        let url = format!("{site}/t/engineering/");
+        self.jobs = scrape_pages(
+            &url,
+            options.max_pages,
+            |url| get_body(url, options),
+            |body| Self::parse_page(body, site),
+        )?;
+        Ok(self)
+    }
 
+    #[cfg(feature = "async")]
+    async fn scrape_async(mut self, options: ScrapeOptions) -> Result<Self, Error> {
+        let site = self.get_url();
+        let url = format!("{site}/t/engineering/");
+        self.jobs = scrape_pages_async(&url, options, |body| Self::parse_page(body, site)).await?;
         Ok(self)
     }
 }
 
-impl Scraper for CryptoJobsList {
-    fn scrape(mut self) -> Result<Self, Error> {
-        let response =
-            reqwest::blocking::get(format!("{}{}", self.get_url(), "/engineering?sort=recent"))
-                .map_err(|err| Error::Request(Box::new(err)))?;
-        if !response.status().is_success() {
-            Err(Error::Response(response.status().as_u16()))?;
-        }
-        let body = response
-            .text()
-            .map_err(|err| Error::Parser(Box::new(err)))?;
-        let document = Html::parse_document(&body);
+impl CryptoJobsList {
+    /// Parses the jobs from a Crypto Jobs List page body.
+    fn parse_page(body: &str, site: &'static str) -> Result<Vec<Job>, Error> {
+        let mut jobs = vec![];
+        let document = Html::parse_document(body);
 
         // HTML selectors
-        let li_selector = Self::get_selector("ul>li")?;
-        let a_selector = Self::get_selector("span>h2>a")?;
-        let span_selector = Self::get_selector("span")?;
-        let span_class_selector = Self::get_selector("span>span>span")?;
-        let span_a_selector = Self::get_selector("span>span>a")?;
+        let [li_selector, a_selector, span_selector, span_class_selector, span_a_selector] =
+            Self::get_selectors(CRYPTO_JOBS_LIST_SELECTORS)?;
+
+        let digit_regex = Regex::new(r"[0-9]").unwrap();
 
         for el in document.select(&li_selector) {
             let mut a_select = el.select(&a_selector);
@@ -277,7 +794,7 @@ impl Scraper for CryptoJobsList {
 
                 let apply = format!(
                     "{}{}",
-                    self.get_url(),
+                    site,
                     title_element.value().attr("href").unwrap_or("")
                 );
 
@@ -327,9 +844,7 @@ impl Scraper for CryptoJobsList {
                 if !loc.is_empty() && loc.contains('$') {
                     remuneration = Self::format_remuneration(loc);
                     location = "".to_string()
-                } else if !Regex::new(r"[0-9]").unwrap().is_match(&loc)
-                    && loc != "Be the first to apply!"
-                {
+                } else if !digit_regex.is_match(&loc) && loc != "Be the first to apply!" {
                     location = loc;
                 }
                 if tags.contains(&remote_string) {
@@ -340,7 +855,7 @@ impl Scraper for CryptoJobsList {
                     }
                 }
 
-                self.jobs.push(Job {
+                jobs.push(Job {
                     title,
                     company,
                     date_posted,
@@ -348,13 +863,115 @@ impl Scraper for CryptoJobsList {
                     remuneration,
                     tags,
                     apply,
-                    site: self.get_url(),
+                    site,
+                    ..Default::default()
                 });
             }
         }
 
-        self.jobs = self.jobs.into_iter().unique().collect();
+        Ok(jobs)
+    }
+}
 
+impl Scraper for CryptoJobsList {
+    fn scrape(mut self, options: ScrapeOptions) -> Result<Self, Error> {
+        let site = self.get_url();
+        let url = format!("{site}/engineering?sort=recent");
+        self.jobs = scrape_pages(
+            &url,
+            options.max_pages,
+            |url| get_body(url, options),
+            |body| Self::parse_page(body, site),
+        )?;
+        Ok(self)
+    }
+
+    #[cfg(feature = "async")]
+    async fn scrape_async(mut self, options: ScrapeOptions) -> Result<Self, Error> {
+        let site = self.get_url();
+        let url = format!("{site}/engineering?sort=recent");
+        self.jobs = scrape_pages_async(&url, options, |body| Self::parse_page(body, site)).await?;
+        Ok(self)
+    }
+}
+
+impl RemoteOk {
+    /// Parses the jobs table from a Remote OK page body. Each job is a `tr.job` row, with the title
+    /// in an `h2` and the company in an `h3` of its position cell, followed by `div.location` items
+    /// for the location and pay, a cell of tags and a cell with the time posted. Rows missing a
+    /// title, e.g. ads, or that can't be read, e.g. with no company or date, are skipped.
+    fn parse_page(body: &str, site: &'static str) -> Result<Vec<Job>, Error> {
+        let mut jobs = vec![];
+        let document = Html::parse_document(body);
+
+        // HTML selectors
+        let [row_selector, title_selector, company_selector, location_selector, tag_selector, time_selector] =
+            Self::get_selectors(REMOTE_OK_SELECTORS)?;
+
+        let text = |el: ElementRef| el.text().collect::<String>().trim().to_owned();
+
+        for el in document.select(&row_selector) {
+            let Some(title) = el.select(&title_selector).next().map(text) else {
+                continue;
+            };
+            let Some(company) = el.select(&company_selector).next().map(text) else {
+                continue;
+            };
+
+            let mut locations = vec![];
+            let mut remuneration = "".to_string();
+            for item in el.select(&location_selector).map(text) {
+                if item.contains('💰') {
+                    remuneration = Self::format_remuneration(item);
+                } else {
+                    locations.push(normalize_location(&item));
+                }
+            }
+
+            let Some(date_posted) = el
+                .select(&time_selector)
+                .next()
+                .and_then(|time| time.value().attr("datetime"))
+            else {
+                continue;
+            };
+
+            let apply = match el.value().attr("data-href") {
+                Some(href) if href.starts_with('/') => format!("{site}{href}"),
+                Some(href) => valid_apply_link(href),
+                None => "".into(),
+            };
+
+            jobs.push(Job {
+                title,
+                company,
+                date_posted: parse_date(Self::DATE_SOURCE, date_posted),
+                location: locations.join(", "),
+                remuneration,
+                tags: el.select(&tag_selector).map(text).collect(),
+                apply,
+                site,
+                ..Default::default()
+            });
+        }
+
+        Ok(jobs.into_iter().unique().collect())
+    }
+}
+
+impl Scraper for RemoteOk {
+    fn scrape(mut self, options: ScrapeOptions) -> Result<Self, Error> {
+        let site = self.get_url();
+        let body = get_body(&format!("{site}/remote-engineer+crypto-jobs"), options)?;
+        self.jobs = Self::parse_page(&body, site)?;
+        Ok(self)
+    }
+
+    #[cfg(feature = "async")]
+    async fn scrape_async(mut self, options: ScrapeOptions) -> Result<Self, Error> {
+        let site = self.get_url();
+        let body = get_body_async(&format!("{site}/remote-engineer+crypto-jobs"), options).await?;
+        self.jobs = Self::parse_page(&body, site)?;
         Ok(self)
     }
 }
@@ -364,29 +981,35 @@ impl Scraper for CryptoJobsList {
 trait Common {
     type Input: Site + Scraper;
 
-    /// Returns a selector from the Input type's `get_selector` method.
-    fn _get_selector(selectors: &str) -> Result<Selector, Error>;
+    /// A common scrape implementation for a number of web3/blockchain job sites, reading every page.
+    fn _scrape(input: &Self::Input, options: ScrapeOptions) -> Result<Vec<Job>, Error> {
+        let site = input.get_url();
+        scrape_pages(
+            site,
+            options.max_pages,
+            |url| get_body(url, options),
+            |body| Self::parse_page(body, site),
+        )
+    }
+
+    /// The common scrape implementation, with async requests.
+    #[cfg(feature = "async")]
+    fn _scrape_async(
+        input: &Self::Input,
+        options: ScrapeOptions,
+    ) -> impl Future<Output = Result<Vec<Job>, Error>> + Send {
+        let site = input.get_url();
+        scrape_pages_async(site, options, move |body| Self::parse_page(body, site))
+    }
 
-    /// A common scrape implementation for a number of web3/blockchain job sites.
-    fn _scrape(input: &Self::Input) -> Result<Vec<Job>, Error> {
+    /// Parses the jobs from a page body of one of the sites.
+    fn parse_page(body: &str, site: &'static str) -> Result<Vec<Job>, Error> {
         let mut jobs = vec![];
-        let response =
-            reqwest::blocking::get(input.get_url()).map_err(|err| Error::Request(Box::new(err)))?;
-        if !response.status().is_success() {
-            Err(Error::Response(response.status().as_u16()))?;
-        }
-        let body = response
-            .text()
-            .map_err(|err| Error::Parser(Box::new(err)))?;
-        let document = Html::parse_document(&body);
+        let document = Html::parse_document(body);
 
         // HTML selectors
-        let div1_selector = Self::_get_selector("div.infinite-scroll-component__outerdiv>div>div")?;
-        let div2_selector = Self::_get_selector(r#"div[itemprop=title]"#)?;
-        let meta1_selector = Self::_get_selector(r#"meta[itemprop=name]"#)?;
-        let span_selector = Self::_get_selector("span")?;
-        let meta2_selector = Self::_get_selector(r#"meta[itemprop=datePosted]"#)?;
-        let a_selector = Self::_get_selector(r#"a[data-testid=read-more]"#)?;
+        let [div1_selector, div2_selector, meta1_selector, span_selector, meta2_selector, a_selector] =
+            Self::Input::get_selectors(COMMON_SELECTORS)?;
 
         for el in document.select(&div1_selector) {
             let mut div2_selector = el.select(&div2_selector);
@@ -419,20 +1042,14 @@ trait Common {
                 let mut meta2_element = el.select(&meta2_selector);
                 let date_posted_element =
                     meta2_element.next().ok_or(Error::Iterator("date posted"))?;
-                let date_posted = date_posted_element
-                    .value()
-                    .attr("content")
-                    .unwrap_or("")
-                    .to_owned();
+                let date_posted = parse_date(
+                    Self::Input::DATE_SOURCE,
+                    date_posted_element.value().attr("content").unwrap_or(""),
+                );
 
                 let mut a_element = el.select(&a_selector);
                 let apply_element = a_element.next().ok_or(Error::Iterator("apply link"))?;
-                let mut apply = apply_element.value().attr("href").unwrap_or("").to_owned();
-                apply = if apply.starts_with("https") {
-                    apply
-                } else {
-                    "".into()
-                };
+                let apply = valid_apply_link(apply_element.value().attr("href").unwrap_or(""));
 
                 jobs.push(Job {
                     title,
@@ -442,7 +1059,8 @@ trait Common {
                     remuneration,
                     tags: Vec::new(),
                     apply,
-                    site: input.get_url(),
+                    site,
+                    ..Default::default()
                 });
             }
         }
@@ -451,20 +1069,31 @@ trait Common {
     }
 }
 
+/// Returns the apply link if it is an absolute `https`, `http` or `mailto` URL, or an empty string
+/// otherwise.
+fn valid_apply_link(href: &str) -> String {
+    match Url::parse(href.trim()) {
+        Ok(url) if ["https", "http", "mailto"].contains(&url.scheme()) => href.trim().to_owned(),
+        _ => "".into(),
+    }
+}
+
 /// Implements the Common and Scraper traits for sites that share the same HTML structure.
 macro_rules! impl_scrapers {
     ($t:ty) => {
         impl Common for $t {
             type Input = $t;
-
-            fn _get_selector(selectors: &str) -> Result<Selector, Error> {
-                Self::Input::get_selector(selectors)
-            }
         }
 
         impl Scraper for $t {
-            fn scrape(mut self) -> Result<Self, Error> {
-                self.jobs = Self::_scrape(&self)?;
+            fn scrape(mut self, options: ScrapeOptions) -> Result<Self, Error> {
+                self.jobs = Self::_scrape(&self, options)?;
+                Ok(self)
+            }
+
+            #[cfg(feature = "async")]
+            async fn scrape_async(mut self, options: ScrapeOptions) -> Result<Self, Error> {
+                self.jobs = Self::_scrape_async(&self, options).await?;
                 Ok(self)
             }
         }
@@ -477,63 +1106,493 @@ impl_scrapers!(NearJobs);
 
 #[cfg(test)]
 mod tests {
+    use std::net::TcpListener;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    use itertools::Itertools;
     use regex::Regex;
 
+    use crate::config::{Config, DEFAULT_MAX_PAGES};
     use crate::repository::Job;
     use crate::site::{
-        CryptoJobsList, NearJobs, Site, SolanaJobs, SubstrateJobs, UseWeb3, Web3Careers,
-        CRYPTO_JOBS_LIST_URL, NEAR_JOBS_URL, SOLANA_JOBS_URL, SUBSTRATE_JOBS_URL, USE_WEB3_URL,
-        WEB3_CAREERS_URL,
+        CryptoJobsList, NearJobs, RemoteOk, Site, SolanaJobs, SubstrateJobs, UseWeb3, Web3Careers,
+        CRYPTO_JOBS_LIST_URL, NEAR_JOBS_URL, REMOTE_OK_URL, SOLANA_JOBS_URL, SUBSTRATE_JOBS_URL,
+        USE_WEB3_URL, WEB3_CAREERS_URL,
     };
 
-    use super::Scraper;
+    #[cfg(feature = "async")]
+    use super::fetch_body_async;
+    use super::{
+        check_selectors, client, fetch_body_with, get_with_retry, page_url, read_body,
+        scrape_pages, valid_apply_link, Common, Error, RetryPolicy, ScrapeLimit, ScrapeOptions,
+        Scraper, SITES,
+    };
 
     const DATE_REGEX: &str = r"(\d{4})-(\d{2})-(\d{2})( (\d{2}):(\d{2}):(\d{2}))?";
 
+    #[test]
+    fn test_check_selectors() {
+        assert!(check_selectors().is_ok());
+    }
+
+    #[test]
+    fn test_sites_registered_once() {
+        assert!(SITES.iter().map(|site| site.name).all_unique());
+        assert!(SITES.iter().map(|site| site.default_url).all_unique());
+        assert_eq!((SITES[0].url)(), Web3Careers::new().get_url());
+    }
+
+    #[test]
+    fn test_scrape_pages_until_empty() {
+        let page = |title: &str| format!("<title>{title}</title>");
+        let parse = |body: &str| -> Result<Vec<Job>, Error> {
+            Ok(Regex::new(r"<title>([^<]*)</title>")
+                .unwrap()
+                .captures_iter(body)
+                .map(|caps| Job {
+                    title: caps[1].to_string(),
+                    ..Default::default()
+                })
+                .collect())
+        };
+
+        // stops at the first empty page
+        let mut fetched = vec![];
+        let jobs = scrape_pages(
+            "https://site1.com/jobs?filter=x",
+            DEFAULT_MAX_PAGES,
+            |url| {
+                fetched.push(url.to_string());
+                Ok(match fetched.len() {
+                    1 => page("Backend Engineer") + &page("Frontend Engineer"),
+                    2 => page("Rust Engineer") + &page("Backend Engineer"),
+                    _ => "".into(),
+                })
+            },
+            parse,
+        )
+        .unwrap();
+        assert_eq!(jobs.len(), 3);
+        assert_eq!(
+            fetched,
+            vec![
+                "https://site1.com/jobs?filter=x&page=1",
+                "https://site1.com/jobs?filter=x&page=2",
+                "https://site1.com/jobs?filter=x&page=3",
+            ]
+        );
+
+        // a site ignoring the page number stops once a page has nothing new
+        let mut count = 0;
+        let jobs = scrape_pages(
+            "https://site1.com/jobs",
+            DEFAULT_MAX_PAGES,
+            |_| {
+                count += 1;
+                Ok(page("Backend Engineer"))
+            },
+            parse,
+        )
+        .unwrap();
+        assert_eq!((jobs.len(), count), (1, 2));
+
+        // a site with jobs on every page stops at the limit
+        let mut count = 0;
+        scrape_pages(
+            "https://site1.com/jobs",
+            DEFAULT_MAX_PAGES,
+            |_| {
+                count += 1;
+                Ok(page(&format!("Engineer {count}")))
+            },
+            parse,
+        )
+        .unwrap();
+        assert_eq!(count, DEFAULT_MAX_PAGES);
+
+        // only an error on the first page fails the scrape
+        assert!(scrape_pages(
+            "https://site1.com",
+            DEFAULT_MAX_PAGES,
+            |_| Err(Error::Response(500)),
+            parse
+        )
+        .is_err());
+        let mut count = 0;
+        let jobs = scrape_pages(
+            "https://site1.com",
+            DEFAULT_MAX_PAGES,
+            |_| {
+                count += 1;
+                match count {
+                    1 => Ok(page("Backend Engineer")),
+                    _ => Err(Error::Response(404)),
+                }
+            },
+            parse,
+        )
+        .unwrap();
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(
+            page_url("https://site1.com/jobs", 2),
+            "https://site1.com/jobs?page=2"
+        );
+    }
+
+    #[test]
+    fn test_request_timeout() {
+        // a server that accepts connections but never responds
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let _server = thread::spawn(move || {
+            let _connections = listener.incoming().collect::<Vec<_>>();
+        });
+
+        let options = ScrapeOptions {
+            timeout: Duration::from_millis(200),
+            ..Config::default().scrape_options()
+        };
+        let start = Instant::now();
+        let err = fetch_body_with(client().unwrap(), &url, options).unwrap_err();
+        assert!(matches!(err, Error::Request(_)));
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_fetch_body_async() {
+        use std::io::{Read, Write};
+
+        // a server that answers its first request with a page and its second with an error
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let _server = thread::spawn(move || {
+            let responses = [
+                "HTTP/1.1 200 OK\r\nContent-Length: 13\r\nConnection: close\r\n\r\n<html></html>",
+                "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            ];
+            for (stream, response) in listener.incoming().zip(responses) {
+                let mut stream = stream.unwrap();
+                let mut request = [0; 1024];
+                assert!(stream.read(&mut request).unwrap() > 0);
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+
+        let client = reqwest::Client::new();
+        let options = Config::default().scrape_options();
+        assert_eq!(
+            fetch_body_async(&client, &url, options).await.unwrap(),
+            "<html></html>"
+        );
+        let err = fetch_body_async(&client, &url, options).await.unwrap_err();
+        assert!(matches!(err, Error::Response(503)));
+    }
+
+    #[test]
+    fn test_scrape_limit() {
+        let limit = Arc::new(ScrapeLimit::new(2));
+        let running = Arc::new(AtomicUsize::new(0));
+        let most_running = Arc::new(AtomicUsize::new(0));
+        let handles = (0..6)
+            .map(|_| {
+                let (limit, running, most_running) =
+                    (limit.clone(), running.clone(), most_running.clone());
+                thread::spawn(move || {
+                    let _permit = limit.acquire();
+                    let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    most_running.fetch_max(now, Ordering::SeqCst);
+                    thread::sleep(Duration::from_millis(20));
+                    running.fetch_sub(1, Ordering::SeqCst);
+                })
+            })
+            .collect::<Vec<_>>();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(most_running.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_retries_honored() {
+        let policy = |retries| RetryPolicy {
+            retries,
+            delay: Duration::ZERO,
+        };
+        let attempts = |retries, err: fn() -> Error| {
+            let mut count = 0;
+            let result = get_with_retry("https://site1.com", policy(retries), |_| {
+                count += 1;
+                Err(err())
+            });
+            assert!(result.is_err());
+            count
+        };
+
+        assert_eq!(attempts(3, || Error::Response(503)), 4);
+        assert_eq!(attempts(0, || Error::Response(503)), 1);
+        // a client error won't succeed on retry
+        assert_eq!(attempts(3, || Error::Response(404)), 1);
+
+        let mut count = 0;
+        let body = get_with_retry("https://site1.com", policy(3), |_| {
+            count += 1;
+            match count {
+                1 => Err(Error::Response(502)),
+                _ => Ok("<html></html>".to_string()),
+            }
+        });
+        assert_eq!((body.unwrap().as_str(), count), ("<html></html>", 2));
+    }
+
+    #[test]
+    fn test_retry_delay_capped() {
+        let policy = RetryPolicy {
+            retries: 100,
+            delay: Duration::from_secs(1),
+        };
+        assert_eq!(policy.delay_before(1), Duration::from_secs(1));
+        assert_eq!(policy.delay_before(3), Duration::from_secs(4));
+        assert_eq!(policy.delay_before(20), Duration::from_secs(30));
+        assert_eq!(policy.delay_before(100), Duration::from_secs(30));
+        let policy = RetryPolicy {
+            delay: Duration::MAX,
+            ..policy
+        };
+        assert_eq!(policy.delay_before(2), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn test_body_size_limit() {
+        let body = "<html></html>".repeat(100);
+        assert_eq!(read_body(body.as_bytes(), 1300).unwrap(), body);
+
+        let err = read_body(body.as_bytes(), 1299).unwrap_err();
+        assert!(matches!(err, Error::Parser(_)));
+        assert!(err.to_string().contains("exceeds the limit of 1299 bytes"));
+    }
+
+    #[test]
+    fn test_parse_web3careers_row_missing_tags() {
+        let body = r#"
+            <table><tbody>
+            <tr class="table_row" onclick="tableTurboRowClick(event, '/senior-rust-engineer-acme/1')">
+                <td><h2>Senior Rust Engineer</h2></td>
+                <td><h3>Acme</h3></td>
+                <td><time datetime="2023-04-01 10:00:00+00:00">1d</time></td>
+                <td>Remote</td>
+                <td>$120k - $150k</td>
+            </tr>
+            <tr class="table_row" onclick="tableTurboRowClick(event, '/backend-engineer-acme/2')">
+                <td><h2>Backend Engineer</h2></td>
+                <td><h3>Acme</h3></td>
+                <td><time datetime="2023-04-02 10:00:00+00:00">1d</time></td>
+            </tr>
+            </tbody></table>"#;
+        let jobs = Web3Careers::parse_page(body, WEB3_CAREERS_URL).unwrap();
+
+        assert_eq!(jobs.len(), 2);
+        assert_eq!(jobs[0].title, "Senior Rust Engineer");
+        assert_eq!(jobs[0].remuneration, "$120k - $150k");
+        assert!(jobs[0].tags.is_empty());
+        assert_eq!(jobs[1].title, "Backend Engineer");
+        assert_eq!(jobs[1].date_posted, "2023-04-02");
+        assert!(jobs[1].location.is_empty());
+        assert!(jobs[1].remuneration.is_empty());
+        assert_eq!(
+            jobs[1].apply,
+            format!("{}/backend-engineer-acme/2", WEB3_CAREERS_URL)
+        );
+    }
+
+    #[test]
+    fn test_parse_web3careers_reordered_and_malformed_rows() {
+        let body = r#"
+            <table><tbody>
+            <tr class="table_row" onclick="tableTurboRowClick(event, '/rust-engineer-acme/1')">
+                <td><time datetime="2023-04-01 10:00:00+00:00">1d</time></td>
+                <td>$120k - $150k</td>
+                <td><h3>Acme</h3></td>
+                <td><a>rust</a><a>defi</a></td>
+                <td><h2>Rust Engineer</h2></td>
+                <td>Remote</td>
+            </tr>
+            <tr class="table_row" onclick="tableTurboRowClick(event, '/no-date/2')">
+                <td><h2>Backend Engineer</h2></td>
+                <td><h3>Acme</h3></td>
+            </tr>
+            <tr class="table_row" onclick="tableTurboRowClick(event, '/too-many/3')">
+                <td><h2>Frontend Engineer</h2></td>
+                <td><h3>Acme</h3></td>
+                <td><time datetime="2023-04-02 10:00:00+00:00">1d</time></td>
+                <td>Remote</td>
+                <td>$100k</td>
+                <td><a>react</a></td>
+                <td>extra</td>
+            </tr>
+            </tbody></table>"#;
+        let jobs = Web3Careers::parse_page(body, WEB3_CAREERS_URL).unwrap();
+
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].title, "Rust Engineer");
+        assert_eq!(jobs[0].company, "Acme");
+        assert_eq!(jobs[0].date_posted, "2023-04-01");
+        assert_eq!(jobs[0].location, "Remote");
+        assert_eq!(jobs[0].remuneration, "$120k - $150k");
+        assert_eq!(jobs[0].tags, vec!["rust", "defi"]);
+
+        // a page of only unreadable rows means the layout has changed
+        let body = r#"<table><tr class="table_row"><td>Rust Engineer</td></tr></table>"#;
+        assert!(matches!(
+            Web3Careers::parse_page(body, WEB3_CAREERS_URL),
+            Err(Error::Iterator(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_common_apply_links() {
+        let row = |title: &str, href: &str| {
+            format!(
+                r#"<div>
+                    <div itemprop="title">{title}</div>
+                    <meta itemprop="name" content="Acme">
+                    <span>Remote</span>
+                    <meta itemprop="datePosted" content="2023-04-01">
+                    <a data-testid="read-more" href="{href}">Read more</a>
+                </div>"#
+            )
+        };
+        let body = format!(
+            r#"<div class="infinite-scroll-component__outerdiv"><div>{}{}{}</div></div>"#,
+            row("Backend Engineer", "mailto:jobs@acme.com"),
+            row("Frontend Engineer", "http://acme.com/jobs/2"),
+            row("DevOps Engineer", "javascript:apply()"),
+        );
+        let jobs = SolanaJobs::parse_page(&body, SOLANA_JOBS_URL).unwrap();
+
+        assert_eq!(jobs.len(), 3);
+        assert_eq!(jobs[0].apply, "mailto:jobs@acme.com");
+        assert_eq!(jobs[1].apply, "http://acme.com/jobs/2");
+        assert!(jobs[2].apply.is_empty());
+        assert!(valid_apply_link("/jobs/1").is_empty());
+    }
+
+    #[test]
+    fn test_parse_remote_ok() {
+        let body = r#"
+            <table id="jobsboard"><tbody>
+            <tr class="job" data-href="/remote-jobs/remote-senior-rust-engineer-acme-123">
+                <td class="company_and_position">
+                    <h2 itemprop="title">Senior Rust Engineer</h2>
+                    <h3 itemprop="name">Acme</h3>
+                    <div class="location">🌏 Worldwide</div>
+                    <div class="location">💰 $120k - $150k *</div>
+                </td>
+                <td class="tags"><a class="tag"><h3>rust</h3></a><a class="tag"><h3>crypto</h3></a></td>
+                <td class="time"><time datetime="2023-04-01T10:00:00+00:00">1d</time></td>
+            </tr>
+            <tr class="job"><td class="company_and_position">Advertise here</td></tr>
+            <tr class="job">
+                <td class="company_and_position"><h2 itemprop="title">Rust Engineer</h2></td>
+                <td class="time"><time datetime="2023-04-01T10:00:00+00:00">1d</time></td>
+            </tr>
+            <tr class="job">
+                <td class="company_and_position">
+                    <h2 itemprop="title">Rust Engineer</h2>
+                    <h3 itemprop="name">Acme</h3>
+                </td>
+            </tr>
+            </tbody></table>"#;
+        let jobs = RemoteOk::parse_page(body, REMOTE_OK_URL).unwrap();
+
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].title, "Senior Rust Engineer");
+        assert_eq!(jobs[0].company, "Acme");
+        assert_eq!(jobs[0].date_posted, "2023-04-01");
+        assert_eq!(jobs[0].location, "Worldwide");
+        assert_eq!(jobs[0].remuneration, "$120k - $150k");
+        assert_eq!(jobs[0].tags, vec!["rust", "crypto"]);
+        assert_eq!(
+            jobs[0].apply,
+            format!("{REMOTE_OK_URL}/remote-jobs/remote-senior-rust-engineer-acme-123")
+        );
+    }
+
     #[test]
     fn test_scrape_web3careers() {
-        let jobs = Web3Careers::new().scrape().unwrap().jobs;
+        let jobs = Web3Careers::new()
+            .scrape(Config::default().scrape_options())
+            .unwrap()
+            .jobs;
         assert_eq!(jobs[0].site, WEB3_CAREERS_URL);
         job_assertions(jobs)
     }
 
     #[test]
     fn test_scrape_use_web3() {
-        let jobs = UseWeb3::new().scrape().unwrap().jobs;
+        let jobs = UseWeb3::new()
+            .scrape(Config::default().scrape_options())
+            .unwrap()
+            .jobs;
         assert_eq!(jobs[0].site, USE_WEB3_URL);
         job_assertions(jobs)
     }
 
     #[test]
     fn test_scrape_crypto_jobs_list() {
-        let jobs = CryptoJobsList::new().scrape().unwrap().jobs;
+        let jobs = CryptoJobsList::new()
+            .scrape(Config::default().scrape_options())
+            .unwrap()
+            .jobs;
         assert_eq!(jobs[0].site, CRYPTO_JOBS_LIST_URL);
         job_assertions(jobs)
     }
 
     #[test]
     fn test_scrape_solana_jobs() {
-        let jobs = SolanaJobs::new().scrape().unwrap().jobs;
+        let jobs = SolanaJobs::new()
+            .scrape(Config::default().scrape_options())
+            .unwrap()
+            .jobs;
         assert_eq!(jobs[0].site, SOLANA_JOBS_URL);
         job_assertions(jobs)
     }
 
     #[test]
     fn test_scrape_substrate_jobs() {
-        let jobs = SubstrateJobs::new().scrape().unwrap().jobs;
+        let jobs = SubstrateJobs::new()
+            .scrape(Config::default().scrape_options())
+            .unwrap()
+            .jobs;
         assert_eq!(jobs[0].site, SUBSTRATE_JOBS_URL);
         job_assertions(jobs)
     }
 
     #[test]
     fn test_scrape_near_jobs() {
-        let jobs = NearJobs::new().scrape().unwrap().jobs;
+        let jobs = NearJobs::new()
+            .scrape(Config::default().scrape_options())
+            .unwrap()
+            .jobs;
         assert_eq!(jobs[0].site, NEAR_JOBS_URL);
         job_assertions(jobs)
     }
 
+    #[test]
+    fn test_scrape_remote_ok() {
+        let jobs = RemoteOk::new()
+            .scrape(Config::default().scrape_options())
+            .unwrap()
+            .jobs;
+        assert_eq!(jobs[0].site, REMOTE_OK_URL);
+        job_assertions(jobs)
+    }
+
     fn job_assertions(jobs: Vec<Job>) {
-        assert!(jobs.len() > 0);
+        assert!(!jobs.is_empty());
         jobs.iter().for_each(|job| {
             assert!(!job.title.is_empty());
             assert!(!job.company.is_empty());
@@ -544,7 +1603,7 @@ mod tests {
                     || job.remuneration.is_empty()
             );
             assert!(
-                job.apply.starts_with("https")
+                job.apply.starts_with("http")
                     || job.apply.starts_with("mailto")
                     || job.apply.is_empty()
             )
@@ -0,0 +1,129 @@
+//! The shutdown module flushes state that is normally saved on exit, e.g. favorites and the
+//! lockfile, when Job Hunt is stopped by a signal instead, e.g. SIGTERM from a service manager.
+
+use std::io;
+use std::process;
+use std::sync::{Arc, Mutex, OnceLock};
+#[cfg(unix)]
+use std::thread;
+
+#[cfg(unix)]
+use signal_hook::consts::{SIGHUP, SIGINT, SIGTERM};
+#[cfg(unix)]
+use signal_hook::iterator::Signals;
+
+/// A callback that flushes some state, returning why it couldn't if it fails.
+type Callback = Box<dyn FnMut() -> Result<(), String> + Send>;
+
+/// Represents the callbacks to run when Job Hunt shuts down.
+#[derive(Clone, Default)]
+pub struct Shutdown {
+    callbacks: Arc<Mutex<Vec<(&'static str, Callback)>>>,
+}
+
+impl Shutdown {
+    /// Registers a named callback to run on shutdown.
+    pub fn register<F>(&self, name: &'static str, callback: F)
+    where
+        F: FnMut() -> Result<(), String> + Send + 'static,
+    {
+        self.lock().push((name, Box::new(callback)));
+    }
+
+    /// Runs the callbacks, most recently registered first so that state is released in the
+    /// reverse order it was acquired, returning the name and error of each that failed. Callbacks
+    /// run once; running again does nothing.
+    pub fn run(&self) -> Vec<(&'static str, String)> {
+        let callbacks = std::mem::take(&mut *self.lock());
+        callbacks
+            .into_iter()
+            .rev()
+            .filter_map(|(name, mut callback)| callback().err().map(|err| (name, err)))
+            .collect()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<(&'static str, Callback)>> {
+        // a callback that panicked leaves nothing half updated, so the list is still usable
+        self.callbacks
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Returns the callbacks run when Job Hunt is stopped by a signal.
+pub fn on_signal() -> &'static Shutdown {
+    static SHUTDOWN: OnceLock<Shutdown> = OnceLock::new();
+    SHUTDOWN.get_or_init(Shutdown::default)
+}
+
+/// Installs a handler for SIGINT, SIGTERM and SIGHUP that runs the `on_signal` callbacks, reports
+/// any that failed and exits with the conventional status for the signal, 128 plus its number, e.g.
+/// 130 for SIGINT and 143 for SIGTERM. Scrapes in progress are abandoned.
+#[cfg(unix)]
+pub fn install_signal_handler() -> io::Result<()> {
+    let mut signals = Signals::new([SIGINT, SIGTERM, SIGHUP])?;
+    thread::spawn(move || {
+        if let Some(signal) = signals.forever().next() {
+            shut_down(signal);
+        }
+    });
+    Ok(())
+}
+
+/// Installs a handler for Ctrl-C that runs the `on_signal` callbacks, reports any that failed and
+/// exits with the status for SIGINT, 130. Scrapes in progress are abandoned.
+#[cfg(not(unix))]
+pub fn install_signal_handler() -> io::Result<()> {
+    const SIGINT: i32 = 2;
+    ctrlc::set_handler(|| shut_down(SIGINT)).map_err(io::Error::other)
+}
+
+/// Runs the `on_signal` callbacks, reports any that failed and exits with the status for being
+/// stopped by `signal`.
+fn shut_down(signal: i32) -> ! {
+    for (name, err) in on_signal().run() {
+        eprintln!("Could not save {name} on shutdown: {err}");
+    }
+    process::exit(exit_code(signal));
+}
+
+/// Returns the exit status for a process stopped by `signal`, as shells report it.
+fn exit_code(signal: i32) -> i32 {
+    128 + signal
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::{exit_code, Shutdown};
+
+    #[test]
+    fn test_shutdown_runs_callbacks() {
+        let shutdown = Shutdown::default();
+        let flushed = Arc::new(Mutex::new(vec![]));
+        for name in ["lockfile", "favorites"] {
+            let flushed = flushed.clone();
+            shutdown.register(name, move || {
+                flushed.lock().unwrap().push(name);
+                Ok(())
+            });
+        }
+        shutdown.register("history", || Err("disk full".into()));
+
+        assert_eq!(shutdown.run(), vec![("history", "disk full".to_string())]);
+        assert_eq!(*flushed.lock().unwrap(), vec!["favorites", "lockfile"]);
+        // callbacks only run once
+        assert!(shutdown.run().is_empty());
+        assert_eq!(flushed.lock().unwrap().len(), 2);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_exit_code() {
+        use signal_hook::consts::{SIGINT, SIGTERM};
+
+        assert_eq!(exit_code(SIGINT), 130);
+        assert_eq!(exit_code(SIGTERM), 143);
+    }
+}
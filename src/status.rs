@@ -0,0 +1,134 @@
+//! The status module contains the application status tracker, which records the status of jobs the
+//! user has acted on (e.g. applied to) keyed by their stable id, persisted to a JSON file so that
+//! statuses survive between sessions.
+
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/// The default status file name, created in the output directory.
+pub const STATUS_FILE: &str = "status.json";
+
+/// Represents the status of an application for a job.
+#[derive(Debug, Clone, Copy, Eq, Hash, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Interested,
+    Applied,
+    Interviewing,
+    Rejected,
+}
+
+impl Status {
+    /// All Status variants, in the order an application usually progresses.
+    pub const ALL: [Status; 4] = [
+        Status::Interested,
+        Status::Applied,
+        Status::Interviewing,
+        Status::Rejected,
+    ];
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", format!("{:?}", self).to_lowercase())
+    }
+}
+
+/// Parses a status name case-insensitively, e.g. "applied" or "Interviewing".
+impl FromStr for Status {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|status| status.to_string().eq_ignore_ascii_case(s.trim()))
+            .ok_or(format!("Unknown status \"{s}\""))
+    }
+}
+
+/// Represents a job's status and when it was last set.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    pub status: Status,
+    pub updated: NaiveDateTime,
+}
+
+/// Represents the statuses of jobs, keyed by job id. A store without a path is kept in memory only.
+#[derive(Debug, Default)]
+pub struct StatusStore {
+    path: Option<PathBuf>,
+    entries: HashMap<String, Entry>,
+}
+
+impl StatusStore {
+    /// Loads the store from a JSON file. A missing file is an empty store, which is created when the
+    /// first status is set.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let entries = match fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str(&contents)?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => HashMap::new(),
+            Err(err) => Err(err)?,
+        };
+        Ok(Self {
+            path: Some(path),
+            entries,
+        })
+    }
+
+    /// Returns the status entry for a job id, if one has been set.
+    pub fn get(&self, id: &str) -> Option<&Entry> {
+        self.entries.get(id)
+    }
+
+    /// Sets the status of a job id at the given time, then saves the store.
+    pub fn set(&mut self, id: &str, status: Status, updated: NaiveDateTime) -> io::Result<()> {
+        self.entries
+            .insert(id.to_string(), Entry { status, updated });
+        self.save()
+    }
+
+    /// Writes the store to its file, if it has one.
+    fn save(&self) -> io::Result<()> {
+        match &self.path {
+            Some(path) => fs::write(path, serde_json::to_string_pretty(&self.entries)?),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::{Status, StatusStore};
+
+    #[test]
+    fn test_status_store_reload() {
+        let path = std::env::temp_dir().join(format!("jobhunt-{}-status.json", std::process::id()));
+        std::fs::remove_file(&path).ok();
+        let updated = NaiveDate::from_ymd_opt(2022, 7, 27)
+            .unwrap()
+            .and_hms_opt(9, 30, 0)
+            .unwrap();
+
+        let mut store = StatusStore::load(&path).unwrap();
+        assert!(store.get("abc").is_none());
+        store
+            .set("abc", "Applied".parse().unwrap(), updated)
+            .unwrap();
+
+        let store = StatusStore::load(&path).unwrap();
+        let entry = store.get("abc").unwrap();
+        assert_eq!((entry.status, entry.updated), (Status::Applied, updated));
+        assert!("hired".parse::<Status>().is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
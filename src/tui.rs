@@ -0,0 +1,266 @@
+//! The tui module contains the optional terminal UI for the application, enabled with the `tui`
+//! feature and launched with `--tui`. It shows a scrollable job list on the left, the selected job's
+//! details on the right, and a filter input below. The ratatui crate is used for rendering.
+//!
+//! Keys: Up/Down or k/j to move, PageUp/PageDown to jump, `/` to edit the filter, Enter/Esc to stop
+//! editing, and q or Esc to quit.
+
+use std::error::Error;
+
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+
+use crate::repository::{JobRef, NOT_AVAILABLE};
+
+/// The number of rows moved by PageUp/PageDown.
+const PAGE_SIZE: usize = 10;
+
+/// Represents the TUI state, independent of rendering.
+pub struct App {
+    jobs: Vec<JobRef>,
+    /// Indexes into `jobs` of the jobs matching the filter.
+    visible: Vec<usize>,
+    /// The selected position within `visible`.
+    selected: usize,
+    filter: String,
+    editing_filter: bool,
+}
+
+impl App {
+    /// Creates the app state for the given jobs, with the first job selected.
+    pub fn new(jobs: Vec<JobRef>) -> Self {
+        let visible = (0..jobs.len()).collect();
+        Self {
+            jobs,
+            visible,
+            selected: 0,
+            filter: String::new(),
+            editing_filter: false,
+        }
+    }
+
+    /// Returns the selected job, if any jobs match the filter.
+    pub fn selected_job(&self) -> Option<&JobRef> {
+        self.visible.get(self.selected).map(|&i| &self.jobs[i])
+    }
+
+    /// Returns the jobs matching the filter, in order.
+    pub fn visible_jobs(&self) -> impl Iterator<Item = &JobRef> {
+        self.visible.iter().map(|&i| &self.jobs[i])
+    }
+
+    /// Moves the selection down by `n` rows, stopping at the last row.
+    pub fn next(&mut self, n: usize) {
+        self.selected = (self.selected + n).min(self.visible.len().saturating_sub(1));
+    }
+
+    /// Moves the selection up by `n` rows, stopping at the first row.
+    pub fn previous(&mut self, n: usize) {
+        self.selected = self.selected.saturating_sub(n);
+    }
+
+    /// Sets the filter, keeping only jobs whose title, company, location or tags contain it
+    /// (ignoring case). The selection returns to the first matching job.
+    pub fn set_filter(&mut self, filter: &str) {
+        self.filter = filter.to_string();
+        let pat = filter.trim().to_lowercase();
+        self.visible = self
+            .jobs
+            .iter()
+            .enumerate()
+            .filter(|(_, job)| {
+                pat.is_empty()
+                    || [&job.title, &job.company, &job.location]
+                        .into_iter()
+                        .chain(job.tags.iter())
+                        .any(|field| field.to_lowercase().contains(&pat))
+            })
+            .map(|(i, _)| i)
+            .collect();
+        self.selected = 0;
+    }
+
+    /// Handles a key press, returning false if the app should quit.
+    pub fn handle_key(&mut self, key: KeyCode) -> bool {
+        if self.editing_filter {
+            match key {
+                KeyCode::Char(c) => {
+                    let filter = format!("{}{}", self.filter, c);
+                    self.set_filter(&filter);
+                }
+                KeyCode::Backspace => {
+                    let mut filter = self.filter.clone();
+                    filter.pop();
+                    self.set_filter(&filter);
+                }
+                KeyCode::Enter | KeyCode::Esc => self.editing_filter = false,
+                _ => {}
+            }
+            return true;
+        }
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Down | KeyCode::Char('j') => self.next(1),
+            KeyCode::Up | KeyCode::Char('k') => self.previous(1),
+            KeyCode::PageDown => self.next(PAGE_SIZE),
+            KeyCode::PageUp => self.previous(PAGE_SIZE),
+            KeyCode::Char('/') => self.editing_filter = true,
+            _ => {}
+        }
+        true
+    }
+
+    /// Renders the job list, detail pane and filter input.
+    fn render(&self, frame: &mut Frame) {
+        let [main, input] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(3)]).areas(frame.area());
+        let [list_area, detail_area] =
+            Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)])
+                .areas(main);
+
+        let items = self
+            .visible_jobs()
+            .map(|job| ListItem::new(format!("{} - {}", job.title, job.company)))
+            .collect::<Vec<ListItem>>();
+        let list = List::new(items)
+            .block(Block::bordered().title(format!(" Jobs ({}) ", self.visible.len())))
+            .highlight_style(
+                Style::default()
+                    .fg(Color::Green)
+                    .add_modifier(Modifier::BOLD | Modifier::REVERSED),
+            );
+        let mut state =
+            ListState::default().with_selected(self.selected_job().map(|_| self.selected));
+        frame.render_stateful_widget(list, list_area, &mut state);
+
+        let detail = match self.selected_job() {
+            Some(job) => {
+                let or_na = |s: &str| {
+                    if s.is_empty() {
+                        NOT_AVAILABLE.to_string()
+                    } else {
+                        s.to_string()
+                    }
+                };
+                format!(
+                    "Position: {}\nCompany: {}\nDate Posted: {}\nLocation: {}\nRemuneration: {}\n\
+                    Tags: {}\nApply: {}\nSite: {}",
+                    job.title,
+                    job.company,
+                    job.date_posted,
+                    or_na(&job.location),
+                    or_na(&job.remuneration),
+                    or_na(&job.tags.join(", ")),
+                    or_na(&job.apply),
+                    job.site
+                )
+            }
+            None => "No jobs match the filter.".into(),
+        };
+        frame.render_widget(
+            Paragraph::new(detail)
+                .block(Block::bordered().title(" Details "))
+                .wrap(Wrap { trim: true }),
+            detail_area,
+        );
+
+        let title = if self.editing_filter {
+            " Filter (Enter to finish) "
+        } else {
+            " Filter (/ to edit, q to quit) "
+        };
+        frame.render_widget(
+            Paragraph::new(self.filter.as_str()).block(Block::bordered().title(title)),
+            input,
+        );
+    }
+
+    /// Runs the event loop until the user quits.
+    fn run(mut self, terminal: &mut DefaultTerminal) -> Result<(), Box<dyn Error>> {
+        loop {
+            terminal.draw(|frame| self.render(frame))?;
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press && !self.handle_key(key.code) {
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+/// Runs the TUI for the given jobs, restoring the terminal afterwards.
+pub fn run(jobs: Vec<JobRef>) -> Result<(), Box<dyn Error>> {
+    let mut terminal = ratatui::init();
+    let result = App::new(jobs).run(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use ratatui::crossterm::event::KeyCode;
+
+    use crate::repository::{Job, JobRef};
+
+    use super::App;
+
+    fn jobs(titles: &[&str]) -> Vec<JobRef> {
+        titles
+            .iter()
+            .map(|title| {
+                Rc::new(Job {
+                    title: title.to_string(),
+                    company: "Company_1".into(),
+                    date_posted: "2022-07-27".into(),
+                    location: "Remote".into(),
+                    remuneration: "".into(),
+                    tags: vec!["rust".into()],
+                    apply: "https://site1.com".into(),
+                    site: "https://site1.com",
+                    ..Default::default()
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_navigation() {
+        let mut app = App::new(jobs(&["A Engineer", "B Engineer", "C Engineer"]));
+        assert_eq!(app.selected_job().unwrap().title, "A Engineer");
+
+        app.handle_key(KeyCode::Down);
+        app.handle_key(KeyCode::Char('j'));
+        assert_eq!(app.selected_job().unwrap().title, "C Engineer");
+        app.handle_key(KeyCode::Down);
+        assert_eq!(app.selected_job().unwrap().title, "C Engineer");
+
+        app.handle_key(KeyCode::PageUp);
+        assert_eq!(app.selected_job().unwrap().title, "A Engineer");
+        assert!(!app.handle_key(KeyCode::Char('q')));
+    }
+
+    #[test]
+    fn test_filter() {
+        let mut app = App::new(jobs(&["Backend Engineer", "Frontend Engineer"]));
+        app.handle_key(KeyCode::Down);
+
+        app.handle_key(KeyCode::Char('/'));
+        "front".chars().for_each(|c| {
+            app.handle_key(KeyCode::Char(c));
+        });
+        // keys are typed into the filter while editing, so 'q' doesn't quit
+        assert!(app.handle_key(KeyCode::Enter));
+        assert_eq!(app.visible_jobs().count(), 1);
+        assert_eq!(app.selected_job().unwrap().title, "Frontend Engineer");
+
+        app.set_filter("nothing matches");
+        assert!(app.selected_job().is_none());
+        app.handle_key(KeyCode::Down);
+        assert!(app.selected_job().is_none());
+    }
+}